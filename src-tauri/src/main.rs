@@ -2,43 +2,321 @@
 
 #[cfg(not(target_os = "windows"))]
 use auto_launch::AutoLaunchBuilder;
+use futures_util::StreamExt;
 use reqwest::Client as HttpClient;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 use std::net::SocketAddr;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 use std::process::Command;
-use std::sync::{Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
 use tauri::{
-  AppHandle, CustomMenuItem, Manager, State, SystemTray, SystemTrayEvent, SystemTrayMenu,
+  AppHandle, CustomMenuItem, Manager, RunEvent, State, SystemTray, SystemTrayEvent, SystemTrayMenu,
   SystemTrayMenuItem, Window, WindowEvent,
 };
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::lookup_host;
+use tokio::sync::Notify;
 use tokio::time::timeout;
+use trust_dns_proto::op::{Edns, Message, MessageType, OpCode, Query};
+use trust_dns_proto::rr::rdata::opt::{ClientSubnet, EdnsCode, EdnsOption};
+use trust_dns_proto::rr::{DNSClass, Name, RData, Record, RecordType};
 use trust_dns_resolver::config::{NameServerConfig, Protocol, ResolverConfig, ResolverOpts};
 use trust_dns_resolver::TokioAsyncResolver;
-use surge_ping::{Client as PingClient, Config as PingConfig, ICMP, PingIdentifier, PingSequence};
+use surge_ping::{
+  Client as PingClient, Config as PingConfig, IcmpPacket, ICMP, PingIdentifier, PingSequence, SurgeError,
+};
 
 const GITHUB_REPO: &str = "SM8KE1/PulseNet";
 const GITHUB_RELEASES_URL: &str = "https://api.github.com/repos/SM8KE1/PulseNet/releases/latest";
 const GITHUB_RELEASES_LIST_URL: &str = "https://api.github.com/repos/SM8KE1/PulseNet/releases?per_page=20";
+const HTTP_REQUEST_TIMEOUT_SECS: u64 = 30;
+const HTTP_CONNECT_TIMEOUT_SECS: u64 = 10;
+const HTTP_POOL_MAX_IDLE_PER_HOST: usize = 4;
+const CLOSE_ACTIONS: [&str; 4] = ["hide", "minimize", "exit", "ask"];
+const UPDATE_DOWNLOAD_MAX_RETRIES: u32 = 5;
+const UPDATE_DOWNLOAD_RETRY_DELAY_MS: u64 = 1000;
+// Overrides the shared client's request timeout; an installer on a slow link takes minutes.
+const UPDATE_DOWNLOAD_REQUEST_TIMEOUT_SECS: u64 = 600;
+const UPDATE_CHECK_TIMEOUT_SECS: u64 = 10;
+const UPDATE_CHECK_RETRIES: u32 = 1;
+const UPDATE_CHECK_RETRY_DELAY_MS: u64 = 1000;
+// Release asset suffixes for this platform, most preferred first.
+#[cfg(target_os = "windows")]
+const UPDATE_ASSET_EXTENSIONS: &[&str] = &[".msi", ".exe"];
+#[cfg(target_os = "macos")]
+const UPDATE_ASSET_EXTENSIONS: &[&str] = &[".dmg", ".app.tar.gz"];
+#[cfg(target_os = "linux")]
+const UPDATE_ASSET_EXTENSIONS: &[&str] = &[".appimage", ".deb"];
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+const UPDATE_ASSET_EXTENSIONS: &[&str] = &[];
+// Filename spellings of each architecture, as published by the usual bundlers.
+const UPDATE_ASSET_ARCHES: [(&str, &[&str]); 3] = [
+  ("x86_64", &["x86_64", "x64", "amd64"]),
+  ("aarch64", &["aarch64", "arm64"]),
+  ("x86", &["x86", "i686", "i386"]),
+];
 
 const CLOUDFLARE_BASE: &str = "https://speed.cloudflare.com";
 const HETZNER_DOWNLOAD_URL: &str = "https://speed.hetzner.de/10MB.bin";
 const HETZNER_UPLOAD_URL: &str = "https://httpbin.org/post";
+// (id, provider, name, ping url, download url). Cloudflare builds its download url per request.
+const SPEEDTEST_SERVERS: [(&str, &str, &str, &str, Option<&str>); 7] = [
+  ("cloudflare", "cloudflare", "Cloudflare (anycast)", "https://speed.cloudflare.com/__ping", None),
+  ("hetzner-nbg", "hetzner", "Hetzner Nuremberg", "https://speed.hetzner.de/", Some(HETZNER_DOWNLOAD_URL)),
+  ("hetzner-fsn", "hetzner", "Hetzner Falkenstein", "https://fsn1-speed.hetzner.com/", Some("https://fsn1-speed.hetzner.com/100MB.bin")),
+  ("hetzner-hel", "hetzner", "Hetzner Helsinki", "https://hel1-speed.hetzner.com/", Some("https://hel1-speed.hetzner.com/100MB.bin")),
+  ("hetzner-ash", "hetzner", "Hetzner Ashburn", "https://ash-speed.hetzner.com/", Some("https://ash-speed.hetzner.com/100MB.bin")),
+  ("hetzner-hil", "hetzner", "Hetzner Hillsboro", "https://hil-speed.hetzner.com/", Some("https://hil-speed.hetzner.com/100MB.bin")),
+  ("hetzner-sin", "hetzner", "Hetzner Singapore", "https://sin-speed.hetzner.com/", Some("https://sin-speed.hetzner.com/100MB.bin")),
+];
+const SPEEDTEST_SERVER_PROBE_TIMEOUT_MS: u64 = 3000;
+const CLOUDFLARE_PING_URL: &str = "https://speed.cloudflare.com/__ping";
+// Lightweight endpoints on separate networks, sampled together so one slow CDN node can't skew latency.
+const LATENCY_ENDPOINTS: [(&str, &str); 4] = [
+  ("Cloudflare", CLOUDFLARE_PING_URL),
+  ("Google", "https://www.gstatic.com/generate_204"),
+  ("Microsoft", "https://www.msftconnecttest.com/connecttest.txt"),
+  ("Apple", "https://captive.apple.com/hotspot-detect.html"),
+];
+const LATENCY_ENDPOINT_TIMEOUT_MS: u64 = 3000;
+// A mix of scripts, styles and blobs from separate CDNs, roughly what a heavy page pulls in.
+const REALISTIC_OBJECTS: [(&str, &str); 7] = [
+  ("Cloudflare 2 MB", "https://speed.cloudflare.com/__down?bytes=2000000"),
+  ("Cloudflare 500 KB", "https://speed.cloudflare.com/__down?bytes=500000"),
+  ("jsDelivr react-dom", "https://cdn.jsdelivr.net/npm/react-dom@18.2.0/umd/react-dom.production.min.js"),
+  ("jsDelivr bootstrap.css", "https://cdn.jsdelivr.net/npm/bootstrap@5.3.2/dist/css/bootstrap.min.css"),
+  ("unpkg lodash", "https://unpkg.com/lodash@4.17.21/lodash.min.js"),
+  ("cdnjs jquery", "https://cdnjs.cloudflare.com/ajax/libs/jquery/3.7.1/jquery.min.js"),
+  ("Google jquery", "https://ajax.googleapis.com/ajax/libs/jquery/3.7.1/jquery.min.js"),
+];
+const REALISTIC_OBJECT_TIMEOUT_SECS: u64 = 20;
 const IPWHOIS_URL: &str = "https://ipwho.is/";
+const IPINFO_URL: &str = "https://ipinfo.io/json";
+const IFCONFIG_URL: &str = "https://ifconfig.co/json";
+const PUBLIC_IP_PROVIDERS: [&str; 4] = ["ipwho.is", "ipinfo.io", "ifconfig.co", "cloudflare"];
+const PUBLIC_IP_PROVIDER_TIMEOUT_MS: u64 = 3000;
 const DOWNLOAD_BYTES: usize = 10 * 1024 * 1024;
 const UPLOAD_BYTES: usize = 5 * 1024 * 1024;
+const LITE_DOWNLOAD_BYTES: usize = 1024 * 1024;
+const LITE_UPLOAD_BYTES: usize = 512 * 1024;
+const SPEEDTEST_MIN_PAYLOAD_BYTES: usize = 1024 * 1024;
+const SPEEDTEST_MAX_PAYLOAD_BYTES: usize = 500 * 1024 * 1024;
+const SPEEDTEST_PROGRESS_INTERVAL_MS: u64 = 250;
+// Transfers override the shared client's request timeout; large payloads on slow links need minutes.
+const SPEEDTEST_TRANSFER_TIMEOUT_SECS: u64 = 600;
+const SPEEDTEST_UPLOAD_CHUNK_BYTES: usize = 64 * 1024;
+static SPEEDTEST_UPLOAD_CHUNK: [u8; SPEEDTEST_UPLOAD_CHUNK_BYTES] = [0; SPEEDTEST_UPLOAD_CHUNK_BYTES];
+// Rough error bars for the lite estimate; small transfers never leave TCP slow start.
+const LITE_MARGIN_PERCENT: f64 = 30.0;
 const PING_SAMPLES: usize = 5;
+const PING_HOST_TIMEOUT_SECS: u64 = 2;
+const PING_HOST_MAX_COUNT: u16 = 100;
+const PING_HOST_INTERVAL_MS: u64 = 200;
+const PING_TCP_DEFAULT_PORT: u16 = 443;
+const PING_DEFAULT_PAYLOAD_BYTES: usize = 32;
+// Largest ICMP echo payload that fits in a single IP datagram (65535 minus IP and ICMP headers).
+const PING_MAX_PAYLOAD_V4: usize = 65507;
+const PING_MAX_PAYLOAD_V6: usize = 65527;
+const TCP_QUALITY_HOST: &str = "speed.cloudflare.com";
+const TCP_QUALITY_BYTES: usize = 5 * 1024 * 1024;
+const TCP_QUALITY_CONNECT_SAMPLES: usize = 3;
+// Anything shorter is dominated by slow start and says nothing about steady-state loss.
+const TCP_QUALITY_MIN_BYTES: usize = 256 * 1024;
+const TCP_ASSUMED_MSS_BYTES: f64 = 1460.0;
 const DNS_TIMEOUT_MS: u64 = 4000;
+const AUTO_HIDE_CHECK_INTERVAL_MS: u64 = 15000;
+const AUTO_HIDE_MAX_MINUTES: u64 = 24 * 60;
+const MONITOR_LOG_DEFAULT_RETENTION_DAYS: u32 = 7;
+const MONITOR_LOG_MAX_RETENTION_DAYS: u32 = 365;
+const MONITOR_LOG_MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+const DNS_FAILOVER_TIMEOUT_MS: u64 = 1500;
+const CNAME_CHAIN_MAX_HOPS: usize = 16;
+const DNS_CACHE_BENCHMARK_DOMAIN: &str = "wikipedia.org";
+const QNAME_MIN_TEST_DOMAIN: &str = "qnamemintest.internet.nl";
+const DNS_LARGE_RESPONSE_DOMAIN: &str = "ietf.org";
+const DNS_FRAGMENTATION_EDNS_PAYLOAD: u16 = 4096;
+// Largest UDP payload that fits an unfragmented 1500-byte IPv4 packet.
+const UDP_UNFRAGMENTED_MAX_BYTES: usize = 1472;
+const DNS_RECOMMEND_ATTEMPTS: usize = 5;
+const DNS_RELIABILITY_DEFAULT_ATTEMPTS: usize = 20;
+const DNS_RELIABILITY_MAX_ATTEMPTS: usize = 500;
+const DNS_RELIABILITY_INTERVAL_MS: u64 = 100;
+const DNS_RECOMMEND_DOMAIN: &str = "cloudflare.com";
+// Added to the score (in ms) so a slightly slower validating resolver can outrank a non-validating one.
+const DNS_RECOMMEND_NO_DNSSEC_PENALTY_MS: f64 = 20.0;
+// TEST-NET-1 (RFC 5737) and the IPv6 discard prefix (RFC 6666) never answer, standing in for a dead primary.
+const DNS_BLACKHOLE_V4: &str = "192.0.2.1";
+const DNS_BLACKHOLE_V6: &str = "100::1";
+// On nearly every ad blocklist, so a forwarder that filters will not return its real address.
+const LOCAL_RESOLVER_BLOCKLIST_DOMAIN: &str = "doubleclick.net";
+const LOCAL_RESOLVER_REFERENCE: &str = "1.1.1.1";
 const DNS_ADAPTER_CACHE_TTL_MS: u128 = 5000;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+const MAX_ADAPTER_DNS_SERVERS: usize = 4;
+// Without systemd-resolved the whole system shares one server list, exposed as this pseudo adapter.
+#[cfg(target_os = "linux")]
+const RESOLV_CONF_ADAPTER: &str = "resolv.conf";
+#[cfg(target_os = "linux")]
+const RESOLV_CONF_PATH: &str = "/etc/resolv.conf";
+#[cfg(target_os = "windows")]
+const DOH_POLICY_KEY: &str = "HKLM:\\SOFTWARE\\Policies\\Microsoft\\Windows NT\\DNSClient";
+#[cfg(target_os = "windows")]
+const DNS_REVERT_MIN_SECS: u64 = 10;
+#[cfg(target_os = "windows")]
+const DNS_REVERT_MAX_SECS: u64 = 3600;
+#[cfg(target_os = "windows")]
+const DNS_REVERT_DEFAULT_SECS: u64 = 60;
+const TIME_SYNC_WARN_MS: i64 = 5000;
+const NTP_DEFAULT_SERVER: &str = "pool.ntp.org";
+const NTP_TIMEOUT_MS: u64 = 3000;
+const NTP_OFFSET_WARN_MS: f64 = 1000.0;
+// Seconds between the NTP epoch (1900) and the Unix epoch (1970).
+const NTP_UNIX_EPOCH_DELTA: f64 = 2_208_988_800.0;
+const DIAGNOSTIC_PING_HOSTS: [&str; 2] = ["1.1.1.1", "8.8.8.8"];
+const DIAGNOSTIC_DNS_DOMAIN: &str = "google.com";
+const VOIP_PACKET_INTERVAL_MS: u64 = 20;
+const VOIP_PAYLOAD_BYTES: usize = 160;
+const VOIP_DEFAULT_DURATION_SECS: u64 = 10;
+const VOIP_MAX_DURATION_SECS: u64 = 120;
+const STABILITY_DEFAULT_DURATION_SECS: u64 = 300;
+const STABILITY_MAX_DURATION_SECS: u64 = 2 * 60 * 60;
+const STABILITY_INTERVAL_MS: u64 = 1000;
+// A sample is a spike when it is both this many times the median and this far above it.
+const STABILITY_SPIKE_FACTOR: f64 = 2.0;
+const STABILITY_SPIKE_MIN_MS: f64 = 50.0;
+// (interval ms, probes) from slowest to fastest.
+const ICMP_RATE_TIERS: [(u64, usize); 3] = [(500, 10), (100, 20), (10, 50)];
+const ICMP_RATE_LIMIT_LOSS_GAP: f64 = 20.0;
+const REORDER_DEFAULT_COUNT: usize = 100;
+const REORDER_MAX_COUNT: usize = 1000;
+const REORDER_INTERVAL_MS: u64 = 5;
+const ICMP_TIMESTAMP_SAMPLES: usize = 5;
+const ICMP_TIMESTAMP_TIMEOUT_MS: u64 = 1000;
+const MS_PER_DAY: f64 = 86_400_000.0;
+const THROUGHPUT_INTERVAL_MS: u64 = 1000;
+const PING_MONITOR_INTERVAL_MS: u64 = 5000;
+const PING_MONITOR_MIN_INTERVAL_MS: u64 = 1000;
+const PING_STREAM_MIN_INTERVAL_MS: u64 = 100;
+// A day of samples at the default interval.
+const PING_HISTORY_MAX_SAMPLES: usize = 17280;
+const MTR_INTERVAL_MS: u64 = 1000;
+const MTR_MIN_INTERVAL_MS: u64 = 500;
+const MTR_DEFAULT_MAX_HOPS: u8 = 30;
+const MTR_PROBE_TIMEOUT_MS: u64 = 1000;
+const MTR_PROBE_PAYLOAD_BYTES: usize = 56;
+// Per-hop statistics cover this many most recent rounds.
+const MTR_WINDOW_ROUNDS: usize = 100;
+const TRACEROUTE_DEFAULT_MAX_HOPS: u8 = 30;
+const TRACEROUTE_PROBES_PER_HOP: u16 = 3;
+const MTU_PROBE_SMALL_PAYLOAD: u32 = 64;
+const MTU_PROBE_MAX_PAYLOAD: u32 = 1472;
+// IPv4 header (20) + ICMP header (8).
+const MTU_PROBE_HEADER_BYTES: u32 = 28;
+const MTU_DEFAULT_TARGET: &str = "1.1.1.1";
+// IPv4 header (20) + UDP header (8).
+const UDP_OVERHEAD_BYTES: u32 = 28;
+// Queries whose answers land at assorted sizes, mostly above 1 KB, from a tiny request.
+const INBOUND_MTU_DNS_PROBES: [(&str, RecordType); 5] = [
+  ("example.com", RecordType::A),
+  (".", RecordType::DNSKEY),
+  ("ietf.org", RecordType::DNSKEY),
+  ("isc.org", RecordType::DNSKEY),
+  ("google.com", RecordType::TXT),
+];
+const PORT_SCAN_DEFAULT_TIMEOUT_MS: u64 = 1000;
+// Caps open sockets so a long port list can't exhaust file descriptors.
+const PORT_SCAN_MAX_IN_FLIGHT: usize = 64;
+const PORT_SCAN_DEADLINE_MS: u64 = 15_000;
+const SPEEDTEST_HISTORY_MAX_ENTRIES: usize = 200;
+const SPEEDTEST_HISTORY_DEFAULT_DAYS: u64 = 30;
+// Throughput this far below the average (or latency this far above) counts as degraded.
+const SPEEDTEST_DEGRADED_THROUGHPUT_PERCENT: f64 = 20.0;
+const SPEEDTEST_DEGRADED_LATENCY_PERCENT: f64 = 50.0;
+const THROUGHPUT_MIN_INTERVAL_MS: u64 = 250;
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+const GAME_SERVERS: [(&str, &str, &str); 12] = [
+  ("AWS N. Virginia", "us-east", "dynamodb.us-east-1.amazonaws.com"),
+  ("AWS Ohio", "us-east", "dynamodb.us-east-2.amazonaws.com"),
+  ("AWS Oregon", "us-west", "dynamodb.us-west-2.amazonaws.com"),
+  ("AWS Sao Paulo", "sa-east", "dynamodb.sa-east-1.amazonaws.com"),
+  ("AWS Frankfurt", "eu-central", "dynamodb.eu-central-1.amazonaws.com"),
+  ("AWS Ireland", "eu-west", "dynamodb.eu-west-1.amazonaws.com"),
+  ("AWS Stockholm", "eu-north", "dynamodb.eu-north-1.amazonaws.com"),
+  ("AWS Bahrain", "me-south", "dynamodb.me-south-1.amazonaws.com"),
+  ("AWS Mumbai", "ap-south", "dynamodb.ap-south-1.amazonaws.com"),
+  ("AWS Singapore", "ap-southeast", "dynamodb.ap-southeast-1.amazonaws.com"),
+  ("AWS Tokyo", "ap-northeast", "dynamodb.ap-northeast-1.amazonaws.com"),
+  ("AWS Sydney", "oceania", "dynamodb.ap-southeast-2.amazonaws.com"),
+];
+// The DynamoDB endpoints drop ICMP, so the built-in servers are timed with a TCP handshake.
+const GAME_SERVER_TCP_PORT: u16 = 443;
+const GAME_LATENCY_SAMPLES: usize = 3;
+const GEODNS_PING_SAMPLES: usize = 3;
+const DNS_SERVER_PING_SAMPLES: usize = 4;
+const LAN_BASELINE_SAMPLES: usize = 5;
+const SERVICE_CHECKS: [(&str, &str); 10] = [
+  ("Google", "https://www.google.com"),
+  ("Cloudflare", "https://www.cloudflare.com"),
+  ("GitHub", "https://github.com"),
+  ("Microsoft", "https://www.microsoft.com"),
+  ("Apple", "https://www.apple.com"),
+  ("Amazon", "https://www.amazon.com"),
+  ("YouTube", "https://www.youtube.com"),
+  ("Wikipedia", "https://www.wikipedia.org"),
+  ("Discord", "https://discord.com"),
+  ("Steam", "https://store.steampowered.com"),
+];
+const SERVICE_CHECK_TIMEOUT_MS: u64 = 5000;
+const PROXY_ECHO_HOST: &str = "httpbin.org";
+const PROXY_REQUEST_HEADERS: [&str; 7] = [
+  "via",
+  "forwarded",
+  "x-forwarded-for",
+  "x-real-ip",
+  "client-ip",
+  "x-bluecoat-via",
+  "x-proxy-id",
+];
+const PROXY_RESPONSE_HEADERS: [&str; 5] = ["via", "x-cache-lookup", "x-squid-error", "proxy-agent", "proxy-connection"];
+const TRANSFER_ESTIMATE_SIZES: [(&str, u64); 4] = [
+  ("50 MB app", 50_000_000),
+  ("1 GB update", 1_000_000_000),
+  ("5 GB movie", 5_000_000_000),
+  ("70 GB game", 70_000_000_000),
+];
+const STUN_SERVERS: [&str; 3] = ["stun.l.google.com:19302", "stun.cloudflare.com:3478", "stun1.l.google.com:19302"];
+// Google and Cloudflare ignore CHANGE-REQUEST, so filtering is probed against a server that
+// advertises an alternate address (RFC 5780 OTHER-ADDRESS) when none of the above does.
+const STUN_RFC5780_SERVER: &str = "stun.stunprotocol.org:3478";
+const STUN_MAGIC_COOKIE: u32 = 0x2112_A442;
+const STUN_TIMEOUT_MS: u64 = 2000;
+const IPV6_ROUTE_PROBE: &str = "[2606:4700:4700::1111]:53";
+const HAPPY_EYEBALLS_DEFAULT_PORT: u16 = 443;
+const HAPPY_EYEBALLS_CONNECT_TIMEOUT_MS: u64 = 3000;
+// A preferred family losing by less than this is noise, not a misconfiguration.
+const HAPPY_EYEBALLS_TOLERANCE_MS: f64 = 50.0;
+const TLS_INFO_TIMEOUT_MS: u64 = 5000;
+const TLS_BENCHMARK_DEFAULT_HOSTS: [&str; 5] = [
+  "www.google.com",
+  "www.cloudflare.com",
+  "github.com",
+  "www.microsoft.com",
+  "www.amazon.com",
+];
+const TLS_BENCHMARK_MAX_HOSTS: usize = 20;
+
 const DNS_SERVERS: [&str; 8] = [
   "8.8.8.8",
   "8.8.4.4",
@@ -50,6 +328,18 @@ const DNS_SERVERS: [&str; 8] = [
   "208.67.220.220",
 ];
 
+// Certificate names the built-in resolvers present for DNS-over-TLS and DNS-over-HTTPS.
+const DNS_SERVER_TLS_NAMES: [(&str, &str, &str); 8] = [
+  ("8.8.8.8", "dns.google", "dns.google"),
+  ("8.8.4.4", "dns.google", "dns.google"),
+  ("1.1.1.1", "cloudflare-dns.com", "cloudflare-dns.com"),
+  ("1.0.0.1", "cloudflare-dns.com", "cloudflare-dns.com"),
+  ("9.9.9.9", "dns.quad9.net", "dns.quad9.net"),
+  ("149.112.112.112", "dns.quad9.net", "dns.quad9.net"),
+  ("208.67.222.222", "dns.opendns.com", "doh.opendns.com"),
+  ("208.67.220.220", "dns.opendns.com", "doh.opendns.com"),
+];
+
 fn parse_dns_server_socket(server: &str) -> Option<SocketAddr> {
   let trimmed = server.trim();
   if trimmed.is_empty() {
@@ -69,21 +359,204 @@ fn parse_dns_server_socket(server: &str) -> Option<SocketAddr> {
 
 struct AppState {
   close_action: Mutex<String>,
+  throughput_monitor: Mutex<Option<JoinHandle<()>>>,
+  last_activity: Mutex<u128>,
+  auto_hide: Mutex<Option<JoinHandle<()>>>,
+  monitor_logging: Mutex<Option<MonitorLogConfig>>,
+  operations: Mutex<HashMap<u64, CancelToken>>,
+  ping_monitors: Mutex<HashMap<String, JoinHandle<()>>>,
+  ping_history: Mutex<HashMap<String, VecDeque<PingSample>>>,
+  ping_streams: Mutex<HashMap<u64, JoinHandle<()>>>,
+  mtr_task: Mutex<Option<JoinHandle<()>>>,
+  mtr_hops: Mutex<Vec<MtrHopWindow>>,
+  http_client: Mutex<SharedHttpClient>,
+  #[cfg(target_os = "windows")]
+  pending_dns_revert: Mutex<Option<PendingDnsRevert>>,
+  // Held across a whole temporary change so two requests can't both capture a backup.
+  #[cfg(target_os = "windows")]
+  dns_change: tokio::sync::Mutex<()>,
+}
+
+#[derive(Clone, Default)]
+struct CancelToken {
+  cancelled: Arc<AtomicBool>,
+  notify: Arc<Notify>,
+}
+
+impl CancelToken {
+  fn cancel(&self) {
+    self.cancelled.store(true, Ordering::SeqCst);
+    self.notify.notify_waiters();
+  }
+
+  async fn cancelled(&self) {
+    loop {
+      // Register interest before checking the flag so a concurrent cancel can't be missed.
+      let notified = self.notify.notified();
+      if self.cancelled.load(Ordering::SeqCst) {
+        return;
+      }
+      notified.await;
+    }
+  }
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+#[derive(Serialize, Deserialize)]
+struct DnsBackup {
+  adapter: String,
+  // The family the change targets; Linux and macOS snapshots still hold both families' servers.
+  family: AddressFamily,
+  servers: Vec<String>,
+  dhcp: bool,
+}
+
+#[cfg(target_os = "windows")]
+struct PendingDnsRevert {
+  backup: DnsBackup,
+  handle: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct MtrHopWindow {
+  address: Option<std::net::IpAddr>,
+  hostname: Option<String>,
+  samples: VecDeque<Option<f64>>,
+}
+
+struct MonitorLogConfig {
+  dir: PathBuf,
+  retention_days: u32,
+  last_pruned_day: i64,
 }
 
 impl Default for AppState {
   fn default() -> Self {
     Self {
       close_action: Mutex::new("ask".to_string()),
+      throughput_monitor: Mutex::new(None),
+      last_activity: Mutex::new(now_millis()),
+      auto_hide: Mutex::new(None),
+      monitor_logging: Mutex::new(None),
+      operations: Mutex::new(HashMap::new()),
+      ping_monitors: Mutex::new(HashMap::new()),
+      ping_history: Mutex::new(HashMap::new()),
+      ping_streams: Mutex::new(HashMap::new()),
+      mtr_task: Mutex::new(None),
+      mtr_hops: Mutex::new(Vec::new()),
+      http_client: Mutex::new(SharedHttpClient {
+        client: build_http_client(None).unwrap_or_default(),
+        proxy: None,
+      }),
+      #[cfg(target_os = "windows")]
+      pending_dns_revert: Mutex::new(None),
+      #[cfg(target_os = "windows")]
+      dns_change: tokio::sync::Mutex::new(()),
+    }
+  }
+}
+
+struct SharedHttpClient {
+  client: HttpClient,
+  proxy: Option<String>,
+}
+
+// Shared across speedtests and update checks so connections are pooled and a stuck request times out.
+fn build_http_client(proxy: Option<&str>) -> Result<HttpClient, reqwest::Error> {
+  let mut builder = HttpClient::builder()
+    .user_agent(format!("PulseNet/{}", env!("CARGO_PKG_VERSION")))
+    .timeout(Duration::from_secs(HTTP_REQUEST_TIMEOUT_SECS))
+    .connect_timeout(Duration::from_secs(HTTP_CONNECT_TIMEOUT_SECS))
+    .pool_max_idle_per_host(HTTP_POOL_MAX_IDLE_PER_HOST);
+  if let Some(proxy) = proxy {
+    // Credentials embedded in the URL are picked up by reqwest for both HTTP and SOCKS proxies.
+    builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+  }
+  builder.build()
+}
+
+// The shared client plus whether it routes through a proxy.
+fn shared_http_client(state: &AppState) -> (HttpClient, bool) {
+  state
+    .http_client
+    .lock()
+    .map(|guard| (guard.client.clone(), guard.proxy.is_some()))
+    .unwrap_or_default()
+}
+
+fn shared_proxy(state: &AppState) -> Option<String> {
+  state.http_client.lock().ok().and_then(|guard| guard.proxy.clone())
+}
+
+#[tauri::command]
+fn set_proxy(state: State<AppState>, url: Option<String>) -> Result<bool, ErrorCode> {
+  let proxy = url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty());
+  if let Some(proxy) = proxy.as_deref() {
+    let parsed = reqwest::Url::parse(proxy).map_err(|_| ErrorCode::InvalidInput)?;
+    if !matches!(parsed.scheme(), "http" | "https" | "socks5" | "socks5h") || parsed.host_str().is_none() {
+      return Err(ErrorCode::InvalidInput);
     }
   }
+  let client = build_http_client(proxy.as_deref()).map_err(|_| ErrorCode::InvalidInput)?;
+  let mut guard = state.http_client.lock().map_err(|_| ErrorCode::CommandFailed)?;
+  *guard = SharedHttpClient { client, proxy };
+  Ok(guard.proxy.is_some())
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum ErrorCode {
+  InvalidDomain,
+  InvalidServer,
+  InvalidInput,
+  ResolveFailed,
+  PermissionDenied,
+  PingFailed,
+  Timeout,
+  LookupFailed,
+  CommandFailed,
+  UnsupportedPlatform,
+  RequestFailed,
+  InvalidResponse,
+  UpdateCheckFailed,
+  FileWriteFailed,
+  ElevationRequired,
+  TooManyServers,
+  GatewayNotFound,
+  DownloadFailed,
+  ChecksumMismatch,
+  Cancelled,
+  FragmentationNeeded,
+  TlsHandshakeFailed,
+  RateLimited,
+}
+
+fn ping_client_error_code(error: &std::io::Error) -> ErrorCode {
+  if error.kind() == std::io::ErrorKind::PermissionDenied
+    || error.to_string().to_lowercase().contains("permission")
+  {
+    ErrorCode::PermissionDenied
+  } else {
+    ErrorCode::PingFailed
+  }
 }
 
 #[derive(Serialize)]
 struct PingResponse {
   alive: bool,
+  // Average of the successful samples, kept for callers that read a single value.
   time: Option<f64>,
-  error: Option<String>,
+  samples: Vec<f64>,
+  min: Option<f64>,
+  max: Option<f64>,
+  avg: Option<f64>,
+  #[serde(rename = "packetLoss")]
+  packet_loss: f64,
+  // "icmp" or "tcp", whichever produced the samples.
+  method: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -92,25 +565,435 @@ struct DnsResult {
   status: bool,
   #[serde(rename = "responseTimeMs")]
   response_time_ms: u128,
-  error: Option<String>,
+  records: Vec<String>,
+  protocol: String,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SystemResolverResult {
+  // Where the resolver was found: "system", "adapter:<name>" or "dhcp:<name>".
+  sources: Vec<String>,
+  #[serde(flatten)]
+  result: DnsResult,
+}
+
+#[derive(Serialize)]
+struct SystemResolversResponse {
+  domain: String,
+  results: Vec<SystemResolverResult>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
 }
 
 #[derive(Serialize)]
 struct DnsResponse {
-  error: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
   results: Vec<DnsResult>,
+  fastest: Option<String>,
+  recommended: Option<String>,
+  // Custom servers that couldn't be parsed; they are left out of `results`.
+  rejected: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct DnsFailoverResult {
+  domain: String,
+  primary: String,
+  secondary: String,
+  #[serde(rename = "primaryOk")]
+  primary_ok: bool,
+  #[serde(rename = "primaryMs")]
+  primary_ms: Option<f64>,
+  #[serde(rename = "secondaryOk")]
+  secondary_ok: bool,
+  #[serde(rename = "secondaryMs")]
+  secondary_ms: Option<f64>,
+  #[serde(rename = "failoverOk")]
+  failover_ok: bool,
+  #[serde(rename = "failoverMs")]
+  failover_ms: Option<f64>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct UdpFragmentationResult {
+  server: String,
+  domain: String,
+  #[serde(rename = "smallUdpOk")]
+  small_udp_ok: bool,
+  #[serde(rename = "largeUdpSize")]
+  large_udp_size: Option<usize>,
+  #[serde(rename = "largeUdpTruncated")]
+  large_udp_truncated: bool,
+  #[serde(rename = "tcpSize")]
+  tcp_size: Option<usize>,
+  verdict: String,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DnsResponseDiagnostics {
+  server: String,
+  domain: String,
+  #[serde(rename = "recordType")]
+  record_type: String,
+  #[serde(rename = "udpResponded")]
+  udp_responded: bool,
+  #[serde(rename = "udpSize")]
+  udp_size: Option<usize>,
+  #[serde(rename = "udpMs")]
+  udp_ms: Option<f64>,
+  truncated: bool,
+  #[serde(rename = "serverEdnsPayload")]
+  server_edns_payload: Option<u16>,
+  #[serde(rename = "tcpSupported")]
+  tcp_supported: bool,
+  #[serde(rename = "tcpSize")]
+  tcp_size: Option<usize>,
+  #[serde(rename = "tcpMs")]
+  tcp_ms: Option<f64>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DnsCacheBenchmarkResult {
+  server: String,
+  domain: String,
+  #[serde(rename = "coldMs")]
+  cold_ms: Option<f64>,
+  #[serde(rename = "warmMs")]
+  warm_ms: Option<f64>,
+  #[serde(rename = "speedupRatio")]
+  speedup_ratio: Option<f64>,
+  ttl: Option<u32>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+struct DnsFailureBreakdown {
+  timeout: usize,
+  servfail: usize,
+  refused: usize,
+  network: usize,
+  other: usize,
+}
+
+#[derive(Serialize)]
+struct DnsReliabilityResult {
+  server: String,
+  domain: String,
+  attempts: usize,
+  successes: usize,
+  #[serde(rename = "successRate")]
+  success_rate: f64,
+  #[serde(rename = "minMs")]
+  min_ms: Option<f64>,
+  #[serde(rename = "averageMs")]
+  average_ms: Option<f64>,
+  #[serde(rename = "p50Ms")]
+  p50_ms: Option<f64>,
+  #[serde(rename = "p95Ms")]
+  p95_ms: Option<f64>,
+  #[serde(rename = "worstMs")]
+  worst_ms: Option<f64>,
+  failures: DnsFailureBreakdown,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DnsRecommendation {
+  server: String,
+  rank: usize,
+  #[serde(rename = "averageMs")]
+  average_ms: Option<f64>,
+  #[serde(rename = "successRate")]
+  success_rate: f64,
+  dnssec: bool,
+  #[serde(rename = "isCurrent")]
+  is_current: bool,
+  score: Option<f64>,
+  rationale: String,
+}
+
+#[derive(Serialize)]
+struct DnsRecommendationResult {
+  adapter: Option<String>,
+  recommended: Option<String>,
+  servers: Vec<DnsRecommendation>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CnameHop {
+  name: String,
+  target: String,
+  #[serde(rename = "recordType")]
+  record_type: String,
+  ttl: u32,
+}
+
+#[derive(Serialize)]
+struct CnameChainResult {
+  host: String,
+  server: String,
+  chain: Vec<CnameHop>,
+  addresses: Vec<String>,
+  #[serde(rename = "loopDetected")]
+  loop_detected: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SplitDnsEntry {
+  domain: String,
+  #[serde(rename = "systemAddresses")]
+  system_addresses: Vec<String>,
+  #[serde(rename = "publicAddresses")]
+  public_addresses: Vec<String>,
+  differs: bool,
+  #[serde(rename = "systemError")]
+  system_error: Option<ErrorCode>,
+  #[serde(rename = "publicError")]
+  public_error: Option<ErrorCode>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PropagationEntry {
+  server: String,
+  kind: String,
+  nameserver: Option<String>,
+  addresses: Vec<String>,
+  matches: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DnsPropagationResult {
+  domain: String,
+  expected: String,
+  #[serde(rename = "propagatedCount")]
+  propagated_count: usize,
+  #[serde(rename = "totalCount")]
+  total_count: usize,
+  #[serde(rename = "propagationPercent")]
+  propagation_percent: f64,
+  results: Vec<PropagationEntry>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct GeoDnsEntry {
+  server: String,
+  addresses: Vec<String>,
+  #[serde(rename = "bestAddress")]
+  best_address: Option<String>,
+  #[serde(rename = "bestLatencyMs")]
+  best_latency_ms: Option<f64>,
+  error: Option<ErrorCode>,
+}
+
+#[derive(Serialize)]
+struct EndpointLatency {
+  address: String,
+  #[serde(rename = "latencyMs")]
+  latency_ms: Option<f64>,
+  received: usize,
+}
+
+#[derive(Serialize)]
+struct GeoDnsResult {
+  host: String,
+  resolvers: Vec<GeoDnsEntry>,
+  endpoints: Vec<EndpointLatency>,
+  #[serde(rename = "recommendedServer")]
+  recommended_server: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DnsServerLatency {
+  server: String,
+  method: String,
+  #[serde(rename = "latencyMs")]
+  latency_ms: Option<f64>,
+  sent: usize,
+  received: usize,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SplitDnsResult {
+  resolver: String,
+  #[serde(rename = "differingCount")]
+  differing_count: usize,
+  results: Vec<SplitDnsEntry>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ClientSubnetProbe {
+  subnet: String,
+  #[serde(rename = "addressesWithout")]
+  addresses_without: Vec<String>,
+  #[serde(rename = "addressesWith")]
+  addresses_with: Vec<String>,
+  changed: bool,
+  // Scope prefix echoed back by the resolver; None when the reply carried no ECS option.
+  #[serde(rename = "scopePrefix")]
+  scope_prefix: Option<u8>,
+  honored: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DnsQueryResult {
+  server: String,
+  domain: String,
+  #[serde(rename = "recordType")]
+  record_type: String,
+  protocol: String,
+  #[serde(rename = "recursionDesired")]
+  recursion_desired: bool,
+  #[serde(rename = "recursionAvailable")]
+  recursion_available: bool,
+  authoritative: bool,
+  truncated: bool,
+  #[serde(rename = "responseCode")]
+  response_code: String,
+  answers: Vec<String>,
+  authority: Vec<String>,
+  referral: bool,
+  #[serde(rename = "responseTimeMs")]
+  response_time_ms: u128,
+  #[serde(rename = "responseSize")]
+  response_size: usize,
+  #[serde(rename = "qnameMinimization")]
+  qname_minimization: Option<bool>,
+  #[serde(rename = "clientSubnet")]
+  client_subnet: Option<ClientSubnetProbe>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
 }
 
 #[derive(Serialize, Clone)]
 struct DnsAdapter {
   name: String,
   dns: Vec<String>,
+  // True when the servers come from DHCP rather than a static NameServer entry.
+  #[serde(rename = "dhcpEnabled")]
+  dhcp_enabled: bool,
+  suffix: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 struct DnsManagerResult {
   success: bool,
-  error: Option<String>,
+  #[serde(rename = "noChange")]
+  no_change: bool,
+  #[serde(rename = "appliedAndVerified")]
+  applied_and_verified: bool,
+  // The new servers failed a test lookup and the previous configuration was put back.
+  #[serde(rename = "rolledBack")]
+  rolled_back: bool,
+  // Which mechanism applied the change where there is more than one ("resolvectl" or "resolv.conf").
+  backend: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TemporaryDnsResult {
+  success: bool,
+  #[serde(rename = "revertAt")]
+  revert_at: Option<u128>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SystemDohSetting {
+  supported: bool,
+  mode: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct NetworkCategoryResult {
+  adapter: String,
+  #[serde(rename = "networkName")]
+  network_name: Option<String>,
+  category: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AdapterMetric {
+  adapter: String,
+  #[serde(rename = "ipv4Metric")]
+  ipv4_metric: Option<u32>,
+  #[serde(rename = "ipv6Metric")]
+  ipv6_metric: Option<u32>,
+  #[serde(rename = "automaticMetric")]
+  automatic_metric: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SpeedTestServer {
+  id: String,
+  provider: String,
+  name: String,
+  #[serde(rename = "latencyMs")]
+  latency_ms: Option<f64>,
+  #[serde(rename = "jitterMs")]
+  jitter_ms: Option<f64>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -125,14 +1008,306 @@ struct SpeedTestResult {
   jitter_ms: f64,
   ip: String,
   country: String,
-  error: Option<String>,
+  #[serde(rename = "ipProvider")]
+  ip_provider: Option<String>,
+  #[serde(rename = "connectionType")]
+  connection_type: Option<ConnectionType>,
+  #[serde(rename = "transferEstimates")]
+  transfer_estimates: Vec<TransferEstimate>,
+  mode: String,
+  estimated: bool,
+  #[serde(rename = "marginPercent")]
+  margin_percent: Option<f64>,
+  #[serde(rename = "dataUsedBytes")]
+  data_used_bytes: u64,
+  #[serde(rename = "downloadBytes")]
+  download_bytes: u64,
+  #[serde(rename = "uploadBytes")]
+  upload_bytes: u64,
+  #[serde(rename = "latencyEndpoints")]
+  latency_endpoints: Option<Vec<LatencyEndpoint>>,
+  #[serde(rename = "viaProxy")]
+  via_proxy: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LatencyEndpoint {
+  name: String,
+  url: String,
+  #[serde(rename = "latencyMs")]
+  latency_ms: Option<f64>,
+  #[serde(rename = "jitterMs")]
+  jitter_ms: Option<f64>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct SpeedTestProgress {
+  provider: String,
+  phase: String,
+  mbps: f64,
+  percent: f64,
+  bytes: u64,
+}
+
+#[derive(Deserialize, Default)]
+struct SpeedTestOptions {
+  lite: Option<bool>,
+  server: Option<String>,
+  #[serde(rename = "downloadBytes")]
+  download_bytes: Option<usize>,
+  #[serde(rename = "uploadBytes")]
+  upload_bytes: Option<usize>,
+  #[serde(rename = "uploadUrl")]
+  upload_url: Option<String>,
+}
+
+struct SpeedTestEndpoints {
+  ping_url: String,
+  download_url: String,
+  upload_url: String,
+}
+
+#[derive(Serialize)]
+struct DualStackSpeedTestResult {
+  ipv4: Option<SpeedTestResult>,
+  ipv6: Option<SpeedTestResult>,
+  #[serde(rename = "ipv4Error")]
+  ipv4_error: Option<ErrorCode>,
+  #[serde(rename = "ipv6Error")]
+  ipv6_error: Option<ErrorCode>,
+  // IPv6 relative to IPv4; negative means the v6 path is slower.
+  #[serde(rename = "downloadDeltaPercent")]
+  download_delta_percent: Option<f64>,
+  #[serde(rename = "uploadDeltaPercent")]
+  upload_delta_percent: Option<f64>,
+  #[serde(rename = "latencyDeltaMs")]
+  latency_delta_ms: Option<f64>,
+  verdict: String,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+struct StoredSpeedTest {
+  timestamp: u128,
+  provider: String,
+  mode: String,
+  #[serde(rename = "downloadMbps")]
+  download_mbps: f64,
+  #[serde(rename = "uploadMbps")]
+  upload_mbps: f64,
+  #[serde(rename = "latencyMs")]
+  latency_ms: f64,
+  #[serde(rename = "jitterMs")]
+  jitter_ms: f64,
+}
+
+#[derive(Serialize)]
+struct HistoryComparison {
+  samples: usize,
+  #[serde(rename = "windowDays")]
+  window_days: u64,
+  latest: Option<StoredSpeedTest>,
+  #[serde(rename = "averageDownloadMbps")]
+  average_download_mbps: Option<f64>,
+  #[serde(rename = "averageUploadMbps")]
+  average_upload_mbps: Option<f64>,
+  #[serde(rename = "averageLatencyMs")]
+  average_latency_ms: Option<f64>,
+  #[serde(rename = "bestDownloadMbps")]
+  best_download_mbps: Option<f64>,
+  #[serde(rename = "bestUploadMbps")]
+  best_upload_mbps: Option<f64>,
+  #[serde(rename = "bestLatencyMs")]
+  best_latency_ms: Option<f64>,
+  #[serde(rename = "downloadDeltaPercent")]
+  download_delta_percent: Option<f64>,
+  #[serde(rename = "uploadDeltaPercent")]
+  upload_delta_percent: Option<f64>,
+  #[serde(rename = "latencyDeltaPercent")]
+  latency_delta_percent: Option<f64>,
+  degraded: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ObjectTiming {
+  name: String,
+  url: String,
+  bytes: usize,
+  #[serde(rename = "statusCode")]
+  status_code: Option<u16>,
+  #[serde(rename = "ttfbMs")]
+  ttfb_ms: Option<f64>,
+  #[serde(rename = "totalMs")]
+  total_ms: Option<f64>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RealisticThroughputResult {
+  #[serde(rename = "aggregateMbps")]
+  aggregate_mbps: f64,
+  #[serde(rename = "totalBytes")]
+  total_bytes: usize,
+  #[serde(rename = "wallTimeMs")]
+  wall_time_ms: f64,
+  completed: usize,
+  objects: Vec<ObjectTiming>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LocalResolverResult {
+  resolver: Option<String>,
+  #[serde(rename = "isLocal")]
+  is_local: bool,
+  #[serde(rename = "localForwarder")]
+  local_forwarder: bool,
+  software: Option<String>,
+  #[serde(rename = "piHole")]
+  pi_hole: bool,
+  #[serde(rename = "blocksAds")]
+  blocks_ads: Option<bool>,
+  evidence: Vec<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CgnatResult {
+  verdict: String,
+  #[serde(rename = "localAddress")]
+  local_address: Option<String>,
+  gateway: Option<String>,
+  #[serde(rename = "publicIp")]
+  public_ip: Option<String>,
+  evidence: Vec<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TransparentProxyResult {
+  #[serde(rename = "proxyDetected")]
+  proxy_detected: bool,
+  evidence: Vec<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ServiceStatus {
+  name: String,
+  url: String,
+  up: bool,
+  method: String,
+  #[serde(rename = "statusCode")]
+  status_code: Option<u16>,
+  #[serde(rename = "latencyMs")]
+  latency_ms: Option<f64>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ServiceReachabilityResult {
+  services: Vec<ServiceStatus>,
+  #[serde(rename = "upCount")]
+  up_count: usize,
+  #[serde(rename = "downCount")]
+  down_count: usize,
+  verdict: String,
+}
+
+#[derive(Serialize)]
+struct TransferEstimate {
+  label: String,
+  #[serde(rename = "sizeBytes")]
+  size_bytes: u64,
+  #[serde(rename = "downloadSeconds")]
+  download_seconds: Option<f64>,
+  #[serde(rename = "uploadSeconds")]
+  upload_seconds: Option<f64>,
+}
+
+#[derive(Serialize, Clone)]
+struct ConnectionType {
+  interface: Option<String>,
+  #[serde(rename = "type")]
+  kind: String,
+  ssid: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TcpQualityResult {
+  host: String,
+  port: u16,
+  #[serde(rename = "connectMs")]
+  connect_ms: f64,
+  #[serde(rename = "bytesTransferred")]
+  bytes_transferred: usize,
+  #[serde(rename = "throughputMbps")]
+  throughput_mbps: f64,
+  #[serde(rename = "estimatedLossPercent")]
+  estimated_loss_percent: f64,
+  grade: String,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
 }
 
 #[derive(Serialize)]
 struct PublicNetworkInfo {
   ip: String,
   country: String,
-  error: Option<String>,
+  provider: Option<String>,
+  city: Option<String>,
+  region: Option<String>,
+  latitude: Option<f64>,
+  longitude: Option<f64>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+struct PublicIpInfo {
+  ip: Option<String>,
+  #[serde(rename = "countryCode")]
+  country_code: Option<String>,
+  #[serde(rename = "countryName")]
+  country_name: Option<String>,
+  city: Option<String>,
+  region: Option<String>,
+  isp: Option<String>,
+  asn: Option<u64>,
+  org: Option<String>,
+  latitude: Option<f64>,
+  longitude: Option<f64>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -146,40 +1321,765 @@ struct UpdateCheckResult {
   #[serde(rename = "isPrerelease")]
   is_prerelease: bool,
   url: String,
-  error: Option<String>,
+  #[serde(rename = "assetUrl")]
+  asset_url: Option<String>,
+  #[serde(rename = "assetName")]
+  asset_name: Option<String>,
+  sha256: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
 }
 
-#[derive(Deserialize, Serialize)]
-struct AutoLaunchPref {
+#[derive(Serialize)]
+struct MonitorLoggingStatus {
   enabled: bool,
+  dir: Option<String>,
+  #[serde(rename = "retentionDays")]
+  retention_days: u32,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
 }
 
-fn auto_launch_config_path(app: &tauri::AppHandle) -> PathBuf {
-  if let Some(dir) = app.path_resolver().app_config_dir() {
-    return dir.join("auto-launch.json");
-  }
-  PathBuf::from("auto-launch.json")
+#[derive(Serialize)]
+struct UpdateDownloadResult {
+  success: bool,
+  path: String,
+  #[serde(rename = "bytesDownloaded")]
+  bytes_downloaded: u64,
+  resumes: u32,
+  sha256: String,
+  verified: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
 }
 
-#[cfg(not(target_os = "windows"))]
-fn read_auto_launch_pref(app: &tauri::AppHandle) -> Option<bool> {
-  let path = auto_launch_config_path(app);
-  let raw = fs::read_to_string(path).ok()?;
-  let parsed: AutoLaunchPref = serde_json::from_str(&raw).ok()?;
-  Some(parsed.enabled)
+#[derive(Serialize, Clone)]
+struct UpdateDownloadProgress {
+  downloaded: u64,
+  total: Option<u64>,
+  resumes: u32,
 }
 
-fn write_auto_launch_pref(app: &tauri::AppHandle, enabled: bool) {
-  let path = auto_launch_config_path(app);
-  if let Some(parent) = path.parent() {
-    let _ = fs::create_dir_all(parent);
-  }
-  let data = AutoLaunchPref { enabled };
-  let _ = fs::write(path, serde_json::to_vec(&data).unwrap_or_default());
+#[derive(Serialize)]
+struct TimeSyncResult {
+  #[serde(rename = "offsetMs")]
+  offset_ms: i64,
+  #[serde(rename = "roundTripMs")]
+  round_trip_ms: f64,
+  #[serde(rename = "serverTime")]
+  server_time: String,
+  source: String,
+  warning: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct NtpOffsetResult {
+  server: String,
+  #[serde(rename = "offsetMs")]
+  offset_ms: Option<f64>,
+  #[serde(rename = "delayMs")]
+  delay_ms: Option<f64>,
+  stratum: Option<u8>,
+  #[serde(rename = "serverTime")]
+  server_time: Option<String>,
+  #[serde(rename = "exceedsThreshold")]
+  exceeds_threshold: bool,
+  warning: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct DnsTestOptions {
+  attempts: Option<usize>,
+  #[serde(rename = "cacheSize")]
+  cache_size: Option<usize>,
+  #[serde(rename = "useHostsFile")]
+  use_hosts_file: Option<bool>,
+  edns0: Option<bool>,
+  #[serde(rename = "timeoutMs")]
+  timeout_ms: Option<u64>,
+}
+
+#[derive(Deserialize, Clone)]
+struct GameServer {
+  name: String,
+  region: String,
+  host: String,
+  // When set the server is measured by TCP connect time on this port instead of ICMP.
+  port: Option<u16>,
+}
+
+#[derive(Serialize)]
+struct GameLatencyResult {
+  name: String,
+  region: String,
+  host: String,
+  alive: bool,
+  #[serde(rename = "latencyMs")]
+  latency_ms: Option<f64>,
+  #[serde(rename = "samplesReceived")]
+  samples_received: usize,
+  // "icmp" or "tcp", whichever produced the samples.
+  method: String,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ActiveConnection {
+  protocol: String,
+  #[serde(rename = "localAddress")]
+  local_address: String,
+  #[serde(rename = "localPort")]
+  local_port: u16,
+  #[serde(rename = "remoteAddress")]
+  remote_address: String,
+  #[serde(rename = "remotePort")]
+  remote_port: u16,
+  state: String,
+  pid: Option<u32>,
+  #[serde(rename = "processName")]
+  process_name: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ActiveConnectionsResult {
+  connections: Vec<ActiveConnection>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HappyEyeballsResult {
+  host: String,
+  port: u16,
+  #[serde(rename = "ipv4Address")]
+  ipv4_address: Option<String>,
+  #[serde(rename = "ipv6Address")]
+  ipv6_address: Option<String>,
+  #[serde(rename = "ipv4ConnectMs")]
+  ipv4_connect_ms: Option<f64>,
+  #[serde(rename = "ipv6ConnectMs")]
+  ipv6_connect_ms: Option<f64>,
+  #[serde(rename = "ipv4Error")]
+  ipv4_error: Option<ErrorCode>,
+  #[serde(rename = "ipv6Error")]
+  ipv6_error: Option<ErrorCode>,
+  #[serde(rename = "preferredFamily")]
+  preferred_family: Option<String>,
+  winner: Option<String>,
+  #[serde(rename = "marginMs")]
+  margin_ms: Option<f64>,
+  #[serde(rename = "fallbackPenaltyMs")]
+  fallback_penalty_ms: Option<f64>,
+  verdict: String,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Ipv6UsageResult {
+  #[serde(rename = "usedFamily")]
+  used_family: Option<String>,
+  #[serde(rename = "peerAddress")]
+  peer_address: Option<String>,
+  #[serde(rename = "clientAddress")]
+  client_address: Option<String>,
+  #[serde(rename = "ipv6Configured")]
+  ipv6_configured: bool,
+  #[serde(rename = "endpointHasIpv6")]
+  endpoint_has_ipv6: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ArpEntry {
+  ip: String,
+  mac: Option<String>,
+  interface: Option<String>,
+  state: String,
+}
+
+#[derive(Serialize)]
+struct ArpTableResult {
+  entries: Vec<ArpEntry>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct VpnMtuResult {
+  interface: String,
+  #[serde(rename = "sourceAddress")]
+  source_address: Option<String>,
+  target: String,
+  #[serde(rename = "smallPayloadOk")]
+  small_payload_ok: bool,
+  #[serde(rename = "largePayloadOk")]
+  large_payload_ok: bool,
+  mtu: Option<u32>,
+  #[serde(rename = "recommendedMss")]
+  recommended_mss: Option<u32>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct InboundMtuProbe {
+  query: String,
+  #[serde(rename = "responseBytes")]
+  response_bytes: Option<usize>,
+  #[serde(rename = "udpOk")]
+  udp_ok: bool,
+}
+
+#[derive(Serialize)]
+struct AsymmetricMtuResult {
+  host: String,
+  #[serde(rename = "dnsServer")]
+  dns_server: String,
+  #[serde(rename = "outboundMtu")]
+  outbound_mtu: Option<u32>,
+  #[serde(rename = "inboundMtuAtLeast")]
+  inbound_mtu_at_least: Option<u32>,
+  #[serde(rename = "inboundMtuBelow")]
+  inbound_mtu_below: Option<u32>,
+  probes: Vec<InboundMtuProbe>,
+  verdict: String,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TlsCertificateInfo {
+  subject: String,
+  issuer: String,
+  #[serde(rename = "notBefore")]
+  not_before: Option<String>,
+  #[serde(rename = "notAfter")]
+  not_after: Option<String>,
+  sans: Vec<String>,
+  #[serde(rename = "selfSigned")]
+  self_signed: bool,
+  expired: bool,
+}
+
+#[derive(Serialize)]
+struct TlsHandshakeTiming {
+  host: String,
+  port: u16,
+  address: Option<String>,
+  #[serde(rename = "dnsMs")]
+  dns_ms: Option<f64>,
+  #[serde(rename = "tcpConnectMs")]
+  tcp_connect_ms: Option<f64>,
+  #[serde(rename = "tlsHandshakeMs")]
+  tls_handshake_ms: Option<f64>,
+  #[serde(rename = "totalMs")]
+  total_ms: Option<f64>,
+  #[serde(rename = "tlsVersion")]
+  tls_version: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TlsBenchmarkResult {
+  results: Vec<TlsHandshakeTiming>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TlsInfoResult {
+  host: String,
+  port: u16,
+  #[serde(rename = "tlsVersion")]
+  tls_version: Option<String>,
+  #[serde(rename = "cipherSuite")]
+  cipher_suite: Option<String>,
+  #[serde(rename = "handshakeMs")]
+  handshake_ms: Option<f64>,
+  trusted: bool,
+  #[serde(rename = "verifyError")]
+  verify_error: Option<String>,
+  chain: Vec<TlsCertificateInfo>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct NatTypeResult {
+  #[serde(rename = "natType")]
+  nat_type: String,
+  #[serde(rename = "mappedAddress")]
+  mapped_address: Option<String>,
+  #[serde(rename = "localAddress")]
+  local_address: Option<String>,
+  #[serde(rename = "mappingBehavior")]
+  mapping_behavior: String,
+  #[serde(rename = "filteringBehavior")]
+  filtering_behavior: String,
+  reasoning: Vec<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct DefaultRoute {
+  gateway: String,
+  interface: String,
+  metric: u32,
+  family: String,
+}
+
+#[derive(Serialize)]
+struct MultipleDefaultRoutesResult {
+  routes: Vec<DefaultRoute>,
+  #[serde(rename = "multipleIpv4")]
+  multiple_ipv4: bool,
+  #[serde(rename = "multipleIpv6")]
+  multiple_ipv6: bool,
+  // Two routes with the same lowest metric leave the choice to the OS per connection.
+  #[serde(rename = "equalMetric")]
+  equal_metric: bool,
+  #[serde(rename = "preferredInterface")]
+  preferred_interface: Option<String>,
+  warning: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct LanBaselineResult {
+  #[serde(rename = "loopbackLatencyMs")]
+  loopback_latency_ms: Option<f64>,
+  gateway: Option<String>,
+  #[serde(rename = "gatewayLatencyMs")]
+  gateway_latency_ms: Option<f64>,
+  #[serde(rename = "gatewayPacketsReceived")]
+  gateway_packets_received: usize,
+  #[serde(rename = "gatewayHttp")]
+  gateway_http: bool,
+  #[serde(rename = "gatewayHttpResponseMs")]
+  gateway_http_response_ms: Option<f64>,
+  #[serde(rename = "gatewayHttpMbps")]
+  gateway_http_mbps: Option<f64>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct IcmpRateSample {
+  #[serde(rename = "intervalMs")]
+  interval_ms: u64,
+  sent: usize,
+  received: usize,
+  #[serde(rename = "lossPercent")]
+  loss_percent: f64,
+}
+
+#[derive(Serialize)]
+struct IcmpRateLimitResult {
+  host: String,
+  samples: Vec<IcmpRateSample>,
+  #[serde(rename = "rateLimited")]
+  rate_limited: bool,
+  verdict: String,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PacketReorderingResult {
+  host: String,
+  sent: usize,
+  received: usize,
+  reordered: usize,
+  #[serde(rename = "reorderingPercent")]
+  reordering_percent: f64,
+  // Largest number of positions a reply arrived behind where its sequence belonged.
+  #[serde(rename = "maxDisplacement")]
+  max_displacement: usize,
+  #[serde(rename = "intervalMs")]
+  interval_ms: u64,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct OneWayDelayResult {
+  host: String,
+  supported: bool,
+  samples: usize,
+  #[serde(rename = "roundTripMs")]
+  round_trip_ms: Option<f64>,
+  #[serde(rename = "outboundMs")]
+  outbound_ms: Option<f64>,
+  #[serde(rename = "inboundMs")]
+  inbound_ms: Option<f64>,
+  #[serde(rename = "asymmetryMs")]
+  asymmetry_ms: Option<f64>,
+  // True when the local clock offset from NTP was subtracted; the target's own clock
+  // error can't be measured and still skews the split.
+  #[serde(rename = "clockCorrected")]
+  clock_corrected: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct StabilityOutage {
+  #[serde(rename = "startOffsetMs")]
+  start_offset_ms: u64,
+  #[serde(rename = "durationMs")]
+  duration_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct StabilityProgress {
+  target: String,
+  #[serde(rename = "elapsedSeconds")]
+  elapsed_seconds: u64,
+  #[serde(rename = "durationSeconds")]
+  duration_seconds: u64,
+  #[serde(rename = "latencyMs")]
+  latency_ms: Option<f64>,
+  #[serde(rename = "outageCount")]
+  outage_count: usize,
+  #[serde(rename = "inOutage")]
+  in_outage: bool,
+}
+
+#[derive(Serialize)]
+struct StabilityResult {
+  target: String,
+  #[serde(rename = "durationSeconds")]
+  duration_seconds: u64,
+  #[serde(rename = "samplesSent")]
+  samples_sent: usize,
+  #[serde(rename = "samplesReceived")]
+  samples_received: usize,
+  #[serde(rename = "lossPercent")]
+  loss_percent: f64,
+  #[serde(rename = "avgLatencyMs")]
+  avg_latency_ms: Option<f64>,
+  #[serde(rename = "medianLatencyMs")]
+  median_latency_ms: Option<f64>,
+  #[serde(rename = "outageCount")]
+  outage_count: usize,
+  #[serde(rename = "totalOutageMs")]
+  total_outage_ms: u64,
+  #[serde(rename = "longestOutageMs")]
+  longest_outage_ms: u64,
+  outages: Vec<StabilityOutage>,
+  #[serde(rename = "spikeCount")]
+  spike_count: usize,
+  #[serde(rename = "worstSpikeMs")]
+  worst_spike_ms: Option<f64>,
+  grade: String,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct VoipQualityResult {
+  target: String,
+  #[serde(rename = "packetsSent")]
+  packets_sent: usize,
+  #[serde(rename = "packetsReceived")]
+  packets_received: usize,
+  #[serde(rename = "avgLatencyMs")]
+  avg_latency_ms: f64,
+  #[serde(rename = "jitterMs")]
+  jitter_ms: f64,
+  #[serde(rename = "packetLoss")]
+  packet_loss: f64,
+  #[serde(rename = "rFactor")]
+  r_factor: f64,
+  mos: f64,
+  quality: String,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct NetworkInterfaceInfo {
+  name: String,
+  description: Option<String>,
+  mac: String,
+  status: String,
+}
+
+#[derive(Serialize)]
+struct DiagnosticReportResult {
+  success: bool,
+  path: String,
+  redacted: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct InterfaceThroughput {
+  name: String,
+  #[serde(rename = "downloadMbps")]
+  download_mbps: f64,
+  #[serde(rename = "uploadMbps")]
+  upload_mbps: f64,
+  #[serde(rename = "receivedBytes")]
+  received_bytes: u64,
+  #[serde(rename = "sentBytes")]
+  sent_bytes: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct PingSample {
+  #[serde(rename = "targetId")]
+  target_id: String,
+  timestamp: u128,
+  success: bool,
+  #[serde(rename = "latencyMs")]
+  latency_ms: Option<f64>,
+}
+
+#[derive(Serialize, Clone)]
+struct PingStreamSample {
+  #[serde(rename = "streamId")]
+  stream_id: u64,
+  host: String,
+  seq: u64,
+  timestamp: u128,
+  #[serde(rename = "rttMs")]
+  rtt_ms: Option<f64>,
+  error: Option<ErrorCode>,
+}
+
+#[derive(Serialize)]
+struct PingStreamStartResult {
+  #[serde(rename = "streamId")]
+  stream_id: Option<u64>,
+  target: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct MtrHop {
+  hop: u8,
+  address: Option<String>,
+  hostname: Option<String>,
+  sent: usize,
+  received: usize,
+  #[serde(rename = "lossPercent")]
+  loss_percent: f64,
+  #[serde(rename = "lastMs")]
+  last_ms: Option<f64>,
+  #[serde(rename = "avgMs")]
+  avg_ms: Option<f64>,
+  #[serde(rename = "bestMs")]
+  best_ms: Option<f64>,
+  #[serde(rename = "worstMs")]
+  worst_ms: Option<f64>,
+  #[serde(rename = "jitterMs")]
+  jitter_ms: Option<f64>,
+}
+
+#[derive(Serialize, Clone)]
+struct MtrUpdate {
+  host: String,
+  target: String,
+  round: u64,
+  #[serde(rename = "reachedTarget")]
+  reached_target: bool,
+  hops: Vec<MtrHop>,
+}
+
+#[derive(Serialize, Clone)]
+struct TracerouteHop {
+  hop: u8,
+  // "*" when no router answered at this TTL.
+  address: String,
+  hostname: Option<String>,
+  sent: usize,
+  received: usize,
+  #[serde(rename = "rttMs")]
+  rtt_ms: Vec<f64>,
+  #[serde(rename = "avgMs")]
+  avg_ms: Option<f64>,
+}
+
+// Follows a "traceroute-hop" event once the responder's reverse lookup finishes.
+#[derive(Serialize, Clone)]
+struct TracerouteHopName {
+  hop: u8,
+  hostname: String,
+}
+
+#[derive(Serialize)]
+struct TracerouteResult {
+  host: String,
+  target: Option<String>,
+  #[serde(rename = "reachedTarget")]
+  reached_target: bool,
+  hops: Vec<TracerouteHop>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct PortStatus {
+  port: u16,
+  // None when the scan deadline passed before the port's probe finished.
+  open: Option<bool>,
+  #[serde(rename = "latencyMs")]
+  latency_ms: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct PortScanResult {
+  host: String,
+  target: Option<String>,
+  ports: Vec<PortStatus>,
+  // The overall deadline cut the scan short; unfinished ports are reported with an unknown state.
+  #[serde(rename = "timedOut")]
+  timed_out: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct MtrStartResult {
+  started: bool,
+  target: Option<String>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct UptimeResult {
+  #[serde(rename = "targetId")]
+  target_id: String,
+  #[serde(rename = "windowMinutes")]
+  window_minutes: u64,
+  samples: usize,
+  successful: usize,
+  #[serde(rename = "uptimePercent")]
+  uptime_percent: Option<f64>,
+  #[serde(rename = "longestOutageMs")]
+  longest_outage_ms: u128,
+  #[serde(rename = "longestOutageStart")]
+  longest_outage_start: Option<u128>,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+struct ThroughputSample {
+  timestamp: u128,
+  interfaces: Vec<InterfaceThroughput>,
+}
+
+#[derive(Serialize)]
+struct AutoLaunchResult {
+  enabled: bool,
+  error: Option<ErrorCode>,
+  #[serde(rename = "errorMessage")]
+  error_message: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct AutoLaunchPref {
+  enabled: bool,
+}
+
+fn auto_launch_config_path(app: &tauri::AppHandle) -> PathBuf {
+  if let Some(dir) = app.path_resolver().app_config_dir() {
+    return dir.join("auto-launch.json");
+  }
+  PathBuf::from("auto-launch.json")
+}
+
+#[cfg(not(target_os = "windows"))]
+fn read_auto_launch_pref(app: &tauri::AppHandle) -> Option<bool> {
+  let path = auto_launch_config_path(app);
+  let raw = fs::read_to_string(path).ok()?;
+  let parsed: AutoLaunchPref = serde_json::from_str(&raw).ok()?;
+  Some(parsed.enabled)
+}
+
+fn write_auto_launch_pref(app: &tauri::AppHandle, enabled: bool) {
+  let path = auto_launch_config_path(app);
+  if let Some(parent) = path.parent() {
+    let _ = fs::create_dir_all(parent);
+  }
+  let data = AutoLaunchPref { enabled };
+  let _ = fs::write(path, serde_json::to_vec(&data).unwrap_or_default());
+}
+
+#[derive(Deserialize, Serialize)]
+struct CloseActionPref {
+  action: String,
+}
+
+fn close_action_config_path(app: &tauri::AppHandle) -> PathBuf {
+  if let Some(dir) = app.path_resolver().app_config_dir() {
+    return dir.join("close-action.json");
+  }
+  PathBuf::from("close-action.json")
+}
+
+// A missing, corrupt or unknown value yields None so the caller keeps the "ask" default.
+fn read_close_action_pref(app: &tauri::AppHandle) -> Option<String> {
+  let raw = fs::read_to_string(close_action_config_path(app)).ok()?;
+  let parsed: CloseActionPref = serde_json::from_str(&raw).ok()?;
+  Some(parsed.action).filter(|action| CLOSE_ACTIONS.contains(&action.as_str()))
+}
+
+fn write_close_action_pref(app: &tauri::AppHandle, action: &str) {
+  let path = close_action_config_path(app);
+  if let Some(parent) = path.parent() {
+    let _ = fs::create_dir_all(parent);
+  }
+  let data = CloseActionPref { action: action.to_string() };
+  let _ = fs::write(path, serde_json::to_vec(&data).unwrap_or_default());
 }
 
 #[cfg(not(target_os = "windows"))]
-fn auto_launcher() -> auto_launch::AutoLaunch {
+fn auto_launcher() -> Result<auto_launch::AutoLaunch, auto_launch::Error> {
   let app_path = env::current_exe()
     .ok()
     .and_then(|path| path.to_str().map(|s| s.to_string()))
@@ -188,7 +2088,6 @@ fn auto_launcher() -> auto_launch::AutoLaunch {
     .set_app_name("PulseNet")
     .set_app_path(&app_path)
     .build()
-    .unwrap()
 }
 
 #[cfg(target_os = "windows")]
@@ -206,16 +2105,16 @@ fn is_auto_launch_enabled() -> bool {
 }
 
 #[cfg(target_os = "windows")]
-fn set_auto_launch_enabled(enabled: bool) -> bool {
+fn set_auto_launch_enabled(enabled: bool) -> Result<(), ErrorDetail> {
   let app_path = env::current_exe()
     .ok()
     .and_then(|path| path.to_str().map(|s| s.to_string()))
     .unwrap_or_default();
   if app_path.is_empty() {
-    return false;
+    return Err((ErrorCode::CommandFailed, Some("could not resolve the executable path".to_string())));
   }
 
-  if enabled {
+  let output = if enabled {
     Command::new("schtasks")
       .args([
         "/Create",
@@ -229,16 +2128,25 @@ fn set_auto_launch_enabled(enabled: bool) -> bool {
         "/TR",
         &format!("\"{}\"", app_path),
       ])
-      .status()
-      .map(|status| status.success())
-      .unwrap_or(false)
+      .output()
   } else {
     Command::new("schtasks")
       .args(["/Delete", "/TN", auto_launch_task_name(), "/F"])
-      .status()
-      .map(|status| status.success())
-      .unwrap_or(false)
+      .output()
+  };
+  let output = output.map_err(|error| (ErrorCode::CommandFailed, Some(error.to_string())))?;
+  if output.status.success() {
+    return Ok(());
   }
+  let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+  let message = if stderr.is_empty() { String::from_utf8_lossy(&output.stdout).trim().to_string() } else { stderr };
+  // A HIGHEST run-level task needs an elevated caller; schtasks reports that as access denied.
+  let code = if message.to_lowercase().contains("access is denied") {
+    ErrorCode::ElevationRequired
+  } else {
+    ErrorCode::CommandFailed
+  };
+  Err((code, Some(message)))
 }
 
 fn sanitize_domain(input: &str) -> String {
@@ -265,19 +2173,79 @@ fn now_millis() -> u128 {
     .unwrap_or(0)
 }
 
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+enum AddressFamily {
+  Ipv4,
+  Ipv6,
+}
+
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+impl AddressFamily {
+  fn parse(value: Option<&str>) -> Result<Self, ErrorDetail> {
+    match value.map(|item| item.trim().to_ascii_lowercase()).as_deref() {
+      None | Some("") | Some("ipv4") => Ok(Self::Ipv4),
+      Some("ipv6") => Ok(Self::Ipv6),
+      Some(other) => Err((ErrorCode::InvalidInput, Some(format!("Unknown address family \"{other}\"")))),
+    }
+  }
+
+  fn label(self) -> &'static str {
+    match self {
+      Self::Ipv4 => "IPv4",
+      Self::Ipv6 => "IPv6",
+    }
+  }
+
+  // Static IPv6 servers are stored under the Tcpip6 service rather than Tcpip.
+  #[cfg(target_os = "windows")]
+  fn tcpip_service(self) -> &'static str {
+    match self {
+      Self::Ipv4 => "Tcpip",
+      Self::Ipv6 => "Tcpip6",
+    }
+  }
+
+  #[cfg(target_os = "windows")]
+  fn other(self) -> Self {
+    match self {
+      Self::Ipv4 => Self::Ipv6,
+      Self::Ipv6 => Self::Ipv4,
+    }
+  }
+
+  fn matches(self, ip: &std::net::IpAddr) -> bool {
+    match self {
+      Self::Ipv4 => ip.is_ipv4(),
+      Self::Ipv6 => ip.is_ipv6(),
+    }
+  }
+}
+
+#[cfg(target_os = "windows")]
+type DnsAdapterCache = Mutex<HashMap<AddressFamily, (u128, Vec<DnsAdapter>)>>;
+
 #[cfg(target_os = "windows")]
-fn dns_adapter_cache() -> &'static Mutex<Option<(u128, Vec<DnsAdapter>)>> {
-  static CACHE: OnceLock<Mutex<Option<(u128, Vec<DnsAdapter>)>>> = OnceLock::new();
-  CACHE.get_or_init(|| Mutex::new(None))
+fn dns_adapter_cache() -> &'static DnsAdapterCache {
+  static CACHE: OnceLock<DnsAdapterCache> = OnceLock::new();
+  CACHE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
 #[cfg(target_os = "windows")]
 fn clear_dns_adapter_cache() {
   if let Ok(mut guard) = dns_adapter_cache().lock() {
-    *guard = None;
+    guard.clear();
   }
 }
 
+#[cfg(target_os = "windows")]
+fn hidden_command(program: &str) -> Command {
+  let mut command = Command::new(program);
+  command.creation_flags(CREATE_NO_WINDOW);
+  command
+}
+
 #[cfg(target_os = "windows")]
 fn run_powershell(command: &str) -> Result<String, String> {
   let output = Command::new("powershell")
@@ -300,6 +2268,50 @@ fn run_powershell(command: &str) -> Result<String, String> {
   }
 }
 
+#[cfg(target_os = "windows")]
+fn is_process_elevated() -> bool {
+  run_powershell(
+    "([Security.Principal.WindowsPrincipal][Security.Principal.WindowsIdentity]::GetCurrent()).IsInRole([Security.Principal.WindowsBuiltInRole]::Administrator)",
+  )
+  .map(|output| output.eq_ignore_ascii_case("true"))
+  .unwrap_or(false)
+}
+
+#[cfg(unix)]
+fn is_process_elevated() -> bool {
+  // SAFETY: geteuid has no preconditions and cannot fail.
+  unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(not(any(target_os = "windows", unix)))]
+fn is_process_elevated() -> bool {
+  false
+}
+
+#[tauri::command]
+fn is_elevated() -> bool {
+  is_process_elevated()
+}
+
+#[tauri::command]
+fn relaunch_as_admin(app: AppHandle) -> Result<(), ErrorCode> {
+  #[cfg(target_os = "windows")]
+  {
+    let exe = env::current_exe().map_err(|_| ErrorCode::CommandFailed)?;
+    let script = format!("Start-Process -FilePath '{}' -Verb RunAs", ps_escape_single(&exe.to_string_lossy()));
+    // Declining the UAC prompt makes Start-Process fail, and this instance simply keeps running.
+    run_powershell(&script).map_err(|_| ErrorCode::ElevationRequired)?;
+    app.exit(0);
+    Ok(())
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    let _ = app;
+    Err(ErrorCode::UnsupportedPlatform)
+  }
+}
+
 fn ps_escape_single(value: &str) -> String {
   value.replace('\'', "''")
 }
@@ -340,655 +2352,9601 @@ fn parse_dns_adapters_from_output(output: &str) -> Vec<DnsAdapter> {
           .collect::<Vec<String>>()
       })
       .unwrap_or_default();
-    adapters.push(DnsAdapter { name, dns });
+    // Older PowerShell builds serialize the Dhcp enum as its number (1 = Enabled).
+    let dhcp = match item.get("Dhcp") {
+      Some(serde_json::Value::String(value)) => value.eq_ignore_ascii_case("enabled"),
+      Some(serde_json::Value::Number(value)) => value.as_u64() == Some(1),
+      _ => false,
+    };
+    let static_dns = item.get("StaticDns").and_then(|value| value.as_bool()).unwrap_or(false);
+    let suffix = item
+      .get("ConnectionSpecificSuffix")
+      .and_then(|value| value.as_str())
+      .map(|value| value.trim().to_string())
+      .filter(|value| !value.is_empty());
+    adapters.push(DnsAdapter {
+      name,
+      dns,
+      dhcp_enabled: dhcp && !static_dns,
+      suffix,
+    });
   }
   adapters.sort_by(|left, right| left.name.cmp(&right.name));
   adapters
 }
 
-#[tauri::command]
-async fn ping_host(host: String) -> PingResponse {
-  let host_addr = match lookup_host(format!("{}:0", host)).await {
-    Ok(mut addrs) => addrs.next(),
-    Err(error) => {
-      return PingResponse {
-        alive: false,
-        time: None,
-        error: Some(error.to_string()),
+struct InterfaceCounters {
+  name: String,
+  received_bytes: u64,
+  sent_bytes: u64,
+}
+
+#[cfg(target_os = "windows")]
+fn read_interface_counters() -> Vec<InterfaceCounters> {
+  let command = "Get-NetAdapterStatistics | Select-Object Name,ReceivedBytes,SentBytes | ConvertTo-Json -Compress";
+  let output = match run_powershell(command) {
+    Ok(stdout) => stdout,
+    Err(_) => return vec![],
+  };
+  let parsed = match serde_json::from_str::<serde_json::Value>(&output) {
+    Ok(value) => value,
+    Err(_) => return vec![],
+  };
+  let items = if let Some(array) = parsed.as_array() {
+    array.clone()
+  } else {
+    vec![parsed]
+  };
+  items
+    .iter()
+    .filter_map(|item| {
+      let name = item.get("Name").and_then(|value| value.as_str())?.trim().to_string();
+      if name.is_empty() {
+        return None;
+      }
+      Some(InterfaceCounters {
+        name,
+        received_bytes: item.get("ReceivedBytes").and_then(|value| value.as_u64()).unwrap_or(0),
+        sent_bytes: item.get("SentBytes").and_then(|value| value.as_u64()).unwrap_or(0),
+      })
+    })
+    .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn read_interface_counters() -> Vec<InterfaceCounters> {
+  let raw = fs::read_to_string("/proc/net/dev").unwrap_or_default();
+  raw
+    .lines()
+    .skip(2)
+    .filter_map(|line| {
+      let (name, rest) = line.split_once(':')?;
+      let fields: Vec<u64> = rest
+        .split_whitespace()
+        .map(|field| field.parse::<u64>().unwrap_or(0))
+        .collect();
+      if fields.len() < 9 {
+        return None;
+      }
+      Some(InterfaceCounters {
+        name: name.trim().to_string(),
+        received_bytes: fields[0],
+        sent_bytes: fields[8],
+      })
+    })
+    .collect()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn read_interface_counters() -> Vec<InterfaceCounters> {
+  let output = match Command::new("netstat").args(["-ibn"]).output() {
+    Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+    Err(_) => return vec![],
+  };
+  output
+    .lines()
+    .skip(1)
+    .filter_map(|line| {
+      let fields: Vec<&str> = line.split_whitespace().collect();
+      // Only the link-level row carries the interface totals; address rows repeat them.
+      if fields.len() < 10 || !fields[2].starts_with("<Link#") {
+        return None;
       }
+      let len = fields.len();
+      Some(InterfaceCounters {
+        name: fields[0].to_string(),
+        received_bytes: fields[len - 5].parse().unwrap_or(0),
+        sent_bytes: fields[len - 2].parse().unwrap_or(0),
+      })
+    })
+    .collect()
+}
+
+fn bytes_to_mbps(bytes: u64, seconds: f64) -> f64 {
+  if seconds <= 0.0 {
+    return 0.0;
+  }
+  ((bytes as f64 * 8.0) / seconds / 1_000_000.0 * 100.0).round() / 100.0
+}
+
+fn monitor_log_day(millis: u128) -> i64 {
+  (millis / 1000 / 86400) as i64
+}
+
+fn prune_monitor_logs(dir: &Path, retention_days: u32, today: i64) {
+  let Ok(entries) = fs::read_dir(dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let name = entry.file_name().to_string_lossy().to_string();
+    if !name.starts_with("pulsenet-") || !name.ends_with(".csv") {
+      continue;
+    }
+    // pulsenet-<kind>-YYYY-MM-DD[.N].csv
+    let Some((_, rest)) = name.trim_start_matches("pulsenet-").split_once('-') else {
+      continue;
+    };
+    let date: Vec<i64> = rest
+      .get(..10)
+      .unwrap_or("")
+      .split('-')
+      .filter_map(|part| part.parse().ok())
+      .collect();
+    if date.len() != 3 {
+      continue;
     }
+    if today - days_from_civil(date[0], date[1], date[2]) >= retention_days as i64 {
+      let _ = fs::remove_file(entry.path());
+    }
+  }
+}
+
+fn monitor_log_path(dir: &Path, kind: &str, day: i64) -> PathBuf {
+  let (year, month, date) = civil_from_days(day);
+  let stem = format!("pulsenet-{}-{:04}-{:02}-{:02}", kind, year, month, date);
+  let mut path = dir.join(format!("{}.csv", stem));
+  let mut part = 1;
+  // Roll over to a numbered part once a day's file gets too large.
+  while fs::metadata(&path).map(|meta| meta.len() >= MONITOR_LOG_MAX_FILE_BYTES).unwrap_or(false) {
+    part += 1;
+    path = dir.join(format!("{}.{}.csv", stem, part));
+  }
+  path
+}
+
+fn append_monitor_log(app: &AppHandle, kind: &'static str, header: &'static str, rows: Vec<String>) {
+  if rows.is_empty() {
+    return;
+  }
+  let today = monitor_log_day(now_millis());
+  // Only the config is read under the lock; pruning and the write happen on a blocking thread.
+  let (dir, prune_retention) = {
+    let state: State<AppState> = app.state();
+    let Ok(mut guard) = state.monitor_logging.lock() else {
+      return;
+    };
+    let Some(config) = guard.as_mut() else {
+      return;
+    };
+    let prune = config.last_pruned_day != today;
+    config.last_pruned_day = today;
+    (config.dir.clone(), prune.then_some(config.retention_days))
   };
+  tauri::async_runtime::spawn_blocking(move || {
+    if let Some(retention_days) = prune_retention {
+      prune_monitor_logs(&dir, retention_days, today);
+    }
+    write_monitor_log(&dir, kind, header, &rows, today);
+  });
+}
 
-  let addr = match host_addr {
-    Some(addr) => addr,
-    None => {
-      return PingResponse {
-        alive: false,
-        time: None,
-        error: Some("Unable to resolve host".to_string()),
-      }
+fn write_monitor_log(dir: &Path, kind: &str, header: &str, rows: &[String], today: i64) {
+  // Monitors append from separate blocking tasks; serialize them so two writers can't both see
+  // a missing file and write the header twice.
+  static WRITE_LOCK: Mutex<()> = Mutex::new(());
+  let _guard = WRITE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+  let path = monitor_log_path(dir, kind, today);
+  let is_new = !path.exists();
+  let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(&path) else {
+    return;
+  };
+  let mut content = String::new();
+  if is_new {
+    content.push_str(header);
+    content.push('\n');
+  }
+  for row in rows {
+    content.push_str(row);
+    content.push('\n');
+  }
+  let _ = file.write_all(content.as_bytes());
+}
+
+#[tauri::command]
+fn set_monitor_logging(
+  state: State<AppState>,
+  enabled: bool,
+  dir: Option<String>,
+  retention_days: Option<u32>,
+) -> MonitorLoggingStatus {
+  let retention_days = retention_days
+    .unwrap_or(MONITOR_LOG_DEFAULT_RETENTION_DAYS)
+    .clamp(1, MONITOR_LOG_MAX_RETENTION_DAYS);
+  let mut status = MonitorLoggingStatus {
+    enabled: false,
+    dir: None,
+    retention_days,
+    error: None,
+    error_message: None,
+  };
+  let config = if enabled {
+    let Some(dir) = dir.map(|value| value.trim().to_string()).filter(|value| !value.is_empty()) else {
+      status.error = Some(ErrorCode::InvalidInput);
+      return status;
+    };
+    let dir = PathBuf::from(dir);
+    if let Err(error) = fs::create_dir_all(&dir) {
+      status.error = Some(ErrorCode::FileWriteFailed);
+      status.error_message = Some(error.to_string());
+      return status;
+    }
+    let today = monitor_log_day(now_millis());
+    prune_monitor_logs(&dir, retention_days, today);
+    Some((dir, today))
+  } else {
+    None
+  };
+  let mut guard = match state.monitor_logging.lock() {
+    Ok(guard) => guard,
+    Err(_) => {
+      status.error = Some(ErrorCode::CommandFailed);
+      return status;
     }
   };
+  let Some((dir, today)) = config else {
+    *guard = None;
+    return status;
+  };
+  status.enabled = true;
+  status.dir = Some(dir.to_string_lossy().to_string());
+  *guard = Some(MonitorLogConfig {
+    dir,
+    retention_days,
+    last_pruned_day: today,
+  });
+  status
+}
 
-  let mut config_builder = PingConfig::builder();
-  if addr.is_ipv6() {
-    config_builder = config_builder.kind(ICMP::V6);
+async fn run_ping_monitor(app: AppHandle, target_id: String, host: String, interval: Duration) {
+  loop {
+    let response = ping_host(host.clone(), None, None, None, None).await;
+    let sample = PingSample {
+      target_id: target_id.clone(),
+      timestamp: now_millis(),
+      success: response.alive,
+      latency_ms: response.time,
+    };
+    {
+      let state: State<AppState> = app.state();
+      if let Ok(mut guard) = state.ping_history.lock() {
+        let history = guard.entry(target_id.clone()).or_default();
+        if history.len() >= PING_HISTORY_MAX_SAMPLES {
+          history.pop_front();
+        }
+        history.push_back(sample.clone());
+      };
+    }
+    append_monitor_log(
+      &app,
+      "ping",
+      "timestamp,target,host,success,latency_ms",
+      vec![format!(
+        "{},\"{}\",\"{}\",{},{}",
+        format_utc_timestamp(sample.timestamp),
+        target_id.replace('"', "\"\""),
+        host.replace('"', "\"\""),
+        sample.success,
+        sample.latency_ms.map(|value| value.to_string()).unwrap_or_default()
+      )],
+    );
+    let _ = app.emit_all("ping-sample", sample);
+    tokio::time::sleep(interval).await;
   }
-  let config = config_builder.build();
-  let client = match PingClient::new(&config) {
+}
+
+#[tauri::command]
+fn start_ping_monitor(
+  app: AppHandle,
+  state: State<AppState>,
+  target_id: String,
+  host: String,
+  interval_ms: Option<u64>,
+) -> bool {
+  let host = host.trim().to_string();
+  if target_id.trim().is_empty() || host.is_empty() {
+    return false;
+  }
+  let interval = Duration::from_millis(
+    interval_ms
+      .unwrap_or(PING_MONITOR_INTERVAL_MS)
+      .max(PING_MONITOR_MIN_INTERVAL_MS),
+  );
+  let mut guard = match state.ping_monitors.lock() {
+    Ok(guard) => guard,
+    Err(_) => return false,
+  };
+  if let Some(handle) = guard.remove(&target_id) {
+    handle.abort();
+  }
+  let handle = tauri::async_runtime::spawn(run_ping_monitor(app, target_id.clone(), host, interval));
+  guard.insert(target_id, handle);
+  true
+}
+
+#[tauri::command]
+fn stop_ping_monitor(state: State<AppState>, target_id: String) -> bool {
+  match state.ping_monitors.lock() {
+    Ok(mut guard) => match guard.remove(&target_id) {
+      Some(handle) => {
+        handle.abort();
+        true
+      }
+      None => false,
+    },
+    Err(_) => false,
+  }
+}
+
+async fn run_ping_stream(
+  app: AppHandle,
+  stream_id: u64,
+  host: String,
+  client: PingClient,
+  addr: SocketAddr,
+  interval: Duration,
+) {
+  let mut interval = tokio::time::interval(interval);
+  // Probes run on their own tasks so a slow reply never delays the next send; the set
+  // aborts any still in flight when the stream itself is aborted.
+  let mut probes = tokio::task::JoinSet::new();
+  let mut seq: u64 = 0;
+  loop {
+    interval.tick().await;
+    while probes.try_join_next().is_some() {}
+    let mut pinger = new_pinger(&client, &addr, Duration::from_secs(PING_HOST_TIMEOUT_SECS)).await;
+    let app = app.clone();
+    let host = host.clone();
+    probes.spawn(async move {
+      let timestamp = now_millis();
+      let (rtt_ms, error) = match pinger.ping(PingSequence(seq as u16), &[0u8; PING_DEFAULT_PAYLOAD_BYTES]).await {
+        Ok((packet, rtt)) => match icmp_reply_error(&packet) {
+          Some((code, _)) => (None, Some(code)),
+          None => (Some(rtt.as_secs_f64() * 1000.0), None),
+        },
+        Err(error) => (None, Some(ping_error_code(&error))),
+      };
+      let _ = app.emit_all(
+        "ping-stream-sample",
+        PingStreamSample {
+          stream_id,
+          host,
+          seq,
+          timestamp,
+          rtt_ms,
+          error,
+        },
+      );
+    });
+    seq += 1;
+  }
+}
+
+#[tauri::command]
+async fn start_ping_stream(app: AppHandle, host: String, interval_ms: u64) -> PingStreamStartResult {
+  static NEXT_PING_STREAM_ID: AtomicU64 = AtomicU64::new(1);
+  let mut result = PingStreamStartResult {
+    stream_id: None,
+    target: None,
+    error: None,
+    error_message: None,
+  };
+  let host = host.trim().to_string();
+  if host.is_empty() {
+    result.error = Some(ErrorCode::InvalidInput);
+    return result;
+  }
+  let addr = match resolve_ping_target(&host).await {
+    Ok(addr) => addr,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  let client = match build_ping_client(&addr) {
     Ok(client) => client,
-    Err(error) => {
-      return PingResponse {
-        alive: false,
-        time: None,
-        error: Some(error.to_string()),
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  let interval = Duration::from_millis(interval_ms.max(PING_STREAM_MIN_INTERVAL_MS));
+
+  let state: State<AppState> = app.state();
+  let Ok(mut guard) = state.ping_streams.lock() else {
+    result.error = Some(ErrorCode::CommandFailed);
+    return result;
+  };
+  let stream_id = NEXT_PING_STREAM_ID.fetch_add(1, Ordering::Relaxed);
+  let handle = tauri::async_runtime::spawn(run_ping_stream(app.clone(), stream_id, host, client, addr, interval));
+  guard.insert(stream_id, handle);
+  result.stream_id = Some(stream_id);
+  result.target = Some(addr.ip().to_string());
+  result
+}
+
+#[tauri::command]
+fn stop_ping_stream(state: State<AppState>, id: u64) -> bool {
+  match state.ping_streams.lock() {
+    Ok(mut guard) => match guard.remove(&id) {
+      Some(handle) => {
+        handle.abort();
+        true
+      }
+      None => false,
+    },
+    Err(_) => false,
+  }
+}
+
+fn stop_all_ping_streams(state: &AppState) {
+  if let Ok(mut guard) = state.ping_streams.lock() {
+    for (_, handle) in guard.drain() {
+      handle.abort();
+    }
+  }
+}
+
+#[tauri::command]
+fn get_uptime_percent(state: State<AppState>, target_id: String, window_minutes: u64) -> UptimeResult {
+  let mut result = UptimeResult {
+    target_id: target_id.clone(),
+    window_minutes,
+    samples: 0,
+    successful: 0,
+    uptime_percent: None,
+    longest_outage_ms: 0,
+    longest_outage_start: None,
+    error: None,
+    error_message: None,
+  };
+  let now = now_millis();
+  let since = now.saturating_sub(window_minutes as u128 * 60_000);
+  let samples: Vec<PingSample> = match state.ping_history.lock() {
+    Ok(guard) => guard
+      .get(&target_id)
+      .map(|history| history.iter().filter(|sample| sample.timestamp >= since).cloned().collect())
+      .unwrap_or_default(),
+    Err(_) => vec![],
+  };
+  if samples.is_empty() {
+    result.error = Some(ErrorCode::InvalidInput);
+    result.error_message = Some("No samples recorded for this target in the window".to_string());
+    return result;
+  }
+
+  result.samples = samples.len();
+  result.successful = samples.iter().filter(|sample| sample.success).count();
+  result.uptime_percent = Some(round2(result.successful as f64 / result.samples as f64 * 100.0));
+  // An outage runs from its first failed sample until the next success (or now, if still down).
+  let mut outage_start: Option<u128> = None;
+  for sample in &samples {
+    match (sample.success, outage_start) {
+      (false, None) => outage_start = Some(sample.timestamp),
+      (true, Some(start)) => {
+        if sample.timestamp - start > result.longest_outage_ms {
+          result.longest_outage_ms = sample.timestamp - start;
+          result.longest_outage_start = Some(start);
+        }
+        outage_start = None;
+      }
+      _ => {}
+    }
+  }
+  if let Some(start) = outage_start {
+    if now - start > result.longest_outage_ms {
+      result.longest_outage_ms = now - start;
+      result.longest_outage_start = Some(start);
+    }
+  }
+  result
+}
+
+fn mtr_hop_stats(hop: u8, window: &MtrHopWindow) -> MtrHop {
+  let received: Vec<f64> = window.samples.iter().flatten().copied().collect();
+  let sent = window.samples.len();
+  let mut jitter = None;
+  if received.len() > 1 {
+    let sum: f64 = received.windows(2).map(|pair| (pair[1] - pair[0]).abs()).sum();
+    jitter = Some(round2(sum / (received.len() - 1) as f64));
+  }
+  MtrHop {
+    hop,
+    address: window.address.map(|ip| ip.to_string()),
+    hostname: window.hostname.clone(),
+    sent,
+    received: received.len(),
+    loss_percent: if sent == 0 { 0.0 } else { round2((sent - received.len()) as f64 / sent as f64 * 100.0) },
+    last_ms: window.samples.back().copied().flatten().map(round2),
+    avg_ms: if received.is_empty() {
+      None
+    } else {
+      Some(round2(received.iter().sum::<f64>() / received.len() as f64))
+    },
+    best_ms: received.iter().copied().reduce(f64::min).map(round2),
+    worst_ms: received.iter().copied().reduce(f64::max).map(round2),
+    jitter_ms: jitter,
+  }
+}
+
+fn system_resolver() -> TokioAsyncResolver {
+  TokioAsyncResolver::tokio_from_system_conf()
+    .unwrap_or_else(|_| TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default()))
+}
+
+async fn reverse_dns(resolver: &TokioAsyncResolver, ip: std::net::IpAddr) -> Option<String> {
+  let lookup = timeout(Duration::from_millis(DNS_TIMEOUT_MS), resolver.reverse_lookup(ip))
+    .await
+    .ok()?
+    .ok()?;
+  lookup
+    .iter()
+    .next()
+    .map(|name| name.to_utf8().trim_end_matches('.').to_string())
+}
+
+// Fills in a hop's hostname in the background so a slow PTR lookup never delays a round.
+async fn resolve_mtr_hop_name(app: AppHandle, resolver: TokioAsyncResolver, index: usize, ip: std::net::IpAddr) {
+  let Some(hostname) = reverse_dns(&resolver, ip).await else {
+    return;
+  };
+  let state: State<AppState> = app.state();
+  if let Ok(mut hops) = state.mtr_hops.lock() {
+    if let Some(window) = hops.get_mut(index).filter(|window| window.address == Some(ip)) {
+      window.hostname = Some(hostname);
+    }
+  };
+}
+
+fn packet_source(packet: &IcmpPacket) -> std::net::IpAddr {
+  match packet {
+    IcmpPacket::V4(packet) => std::net::IpAddr::V4(packet.get_source()),
+    IcmpPacket::V6(packet) => std::net::IpAddr::V6(packet.get_source()),
+  }
+}
+
+// Raw IPv4 sockets hand back the IP header; raw ICMPv6 sockets return the message alone.
+fn icmp_message(packet: &[u8], v6: bool) -> Option<&[u8]> {
+  if v6 {
+    return Some(packet);
+  }
+  packet.get(((packet.first()? & 0x0f) as usize) * 4..)
+}
+
+// The identifier and sequence of the echo request an ICMP message answers: an echo reply's own,
+// or the ones a router quoted from our request in a Time Exceeded, Destination Unreachable
+// or Packet Too Big.
+fn answered_echo(message: &[u8], v6: bool) -> Option<(u16, u16)> {
+  let echo_fields = |icmp: &[u8]| -> Option<(u16, u16)> {
+    let fields = icmp.get(4..8)?;
+    Some((u16::from_be_bytes([fields[0], fields[1]]), u16::from_be_bytes([fields[2], fields[3]])))
+  };
+  let icmp = icmp_message(message, v6)?;
+  if v6 {
+    // ICMPv6 errors quote our request's 40-byte IPv6 header.
+    return match icmp.first()? {
+      129 => echo_fields(icmp),
+      1..=3 => icmp.get(48..).filter(|quoted| quoted.first() == Some(&128)).and_then(echo_fields),
+      _ => None,
+    };
+  }
+  // ICMPv4 errors quote the original IP header, whose length varies with its options.
+  match icmp.first()? {
+    0 => echo_fields(icmp),
+    3 | 11 => {
+      let inner = icmp.get(8..)?;
+      let quoted = inner.get(((inner.first()? & 0x0f) as usize) * 4..)?;
+      if quoted.first() == Some(&8) {
+        echo_fields(quoted)
+      } else {
+        None
       }
     }
+    _ => None,
+  }
+}
+
+// Sends one echo request per (hop limit, sequence) pair and returns who answered each, and when.
+// A raw socket is needed: routers answer with Time Exceeded from their own address, and
+// surge-ping only delivers replies sent by the target itself.
+fn probe_ttls(
+  target: SocketAddr,
+  probes: &[(u8, u16)],
+  wait: Duration,
+) -> Result<Vec<Option<(std::net::IpAddr, f64)>>, ErrorDetail> {
+  use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+  use std::mem::MaybeUninit;
+
+  let v6 = target.is_ipv6();
+  let (domain, protocol) = if v6 {
+    (Domain::IPV6, Protocol::ICMPV6)
+  } else {
+    (Domain::IPV4, Protocol::ICMPV4)
   };
+  let socket = Socket::new(domain, Type::RAW, Some(protocol))
+    .map_err(|error| (ping_client_error_code(&error), Some(error.to_string())))?;
+  // Windows only delivers to raw sockets bound to a concrete local address.
+  let local = local_address_towards(target.ip())
+    .ok_or((ErrorCode::RequestFailed, Some("no route to target".to_string())))?;
+  socket
+    .bind(&SockAddr::from(SocketAddr::new(local, 0)))
+    .map_err(|error| (ErrorCode::RequestFailed, Some(error.to_string())))?;
+  let destination = SockAddr::from(target);
+  let identifier = next_ping_identifier().0;
+  let mut sent_at = Vec::with_capacity(probes.len());
+  for &(ttl, seq) in probes {
+    let hop_limit = if v6 {
+      socket.set_unicast_hops_v6(ttl as u32)
+    } else {
+      socket.set_ttl(ttl as u32)
+    };
+    hop_limit.map_err(|error| (ErrorCode::PingFailed, Some(error.to_string())))?;
+    let mut packet = [0u8; 8 + MTR_PROBE_PAYLOAD_BYTES];
+    packet[0] = if v6 { 128 } else { 8 };
+    packet[4..6].copy_from_slice(&identifier.to_be_bytes());
+    packet[6..8].copy_from_slice(&seq.to_be_bytes());
+    // The kernel fills in the ICMPv6 checksum, which covers a pseudo-header we can't see.
+    if !v6 {
+      let checksum = icmp_checksum(&packet);
+      packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    }
+    socket
+      .send_to(&packet, &destination)
+      .map_err(|error| (ErrorCode::PingFailed, Some(error.to_string())))?;
+    sent_at.push(Instant::now());
+  }
 
-  let identifier = PingIdentifier((std::process::id() & 0xffff) as u16);
-  let mut pinger = client.pinger(addr.ip(), identifier).await;
-  if let SocketAddr::V6(v6_addr) = addr {
-    pinger.scope_id(v6_addr.scope_id());
+  let mut replies = vec![None; probes.len()];
+  let mut buffer = [MaybeUninit::<u8>::uninit(); 1500];
+  let started = Instant::now();
+  while replies.iter().any(Option::is_none) {
+    let Some(remaining) = wait.checked_sub(started.elapsed()).filter(|remaining| !remaining.is_zero()) else {
+      break;
+    };
+    if socket.set_read_timeout(Some(remaining)).is_err() {
+      break;
+    }
+    let Ok((len, from)) = socket.recv_from(&mut buffer) else {
+      break;
+    };
+    let received_at = Instant::now();
+    // SAFETY: recv_from initialised the first `len` bytes of the buffer.
+    let message = unsafe { std::slice::from_raw_parts(buffer.as_ptr() as *const u8, len) };
+    let (Some((id, seq)), Some(responder)) = (answered_echo(message, v6), from.as_socket()) else {
+      continue;
+    };
+    if id != identifier {
+      continue;
+    }
+    if let Some(index) = probes.iter().position(|&(_, probe_seq)| probe_seq == seq) {
+      if replies[index].is_none() {
+        let rtt = received_at.duration_since(sent_at[index]).as_secs_f64() * 1000.0;
+        replies[index] = Some((responder.ip(), rtt));
+      }
+    }
+  }
+  Ok(replies)
+}
+
+async fn run_mtr(app: AppHandle, host: String, addr: SocketAddr, max_hops: u8, interval: Duration) {
+  let resolver = system_resolver();
+  let mut round: u64 = 0;
+  loop {
+    round += 1;
+    // Every TTL is probed at once, so a round costs one timeout rather than one per hop.
+    let probes: Vec<(u8, u16)> = (1..=max_hops).map(|ttl| (ttl, ttl as u16)).collect();
+    let wait = Duration::from_millis(MTR_PROBE_TIMEOUT_MS);
+    let replies = tauri::async_runtime::spawn_blocking(move || probe_ttls(addr, &probes, wait))
+      .await
+      .ok()
+      .and_then(Result::ok)
+      .unwrap_or_else(|| vec![None; max_hops as usize]);
+    let reached_at = replies
+      .iter()
+      .position(|reply| matches!(reply, Some((ip, _)) if *ip == addr.ip()));
+
+    let update = {
+      let state: State<AppState> = app.state();
+      let Ok(mut hops) = state.mtr_hops.lock() else {
+        return;
+      };
+      hops.resize_with(max_hops as usize, MtrHopWindow::default);
+      for (index, (window, reply)) in hops.iter_mut().zip(&replies).enumerate() {
+        if window.samples.len() >= MTR_WINDOW_ROUNDS {
+          window.samples.pop_front();
+        }
+        window.samples.push_back(reply.map(|(_, ms)| ms));
+        if let Some((ip, _)) = reply {
+          if window.address != Some(*ip) {
+            window.address = Some(*ip);
+            window.hostname = None;
+            tauri::async_runtime::spawn(resolve_mtr_hop_name(app.clone(), resolver.clone(), index, *ip));
+          }
+        }
+      }
+      // Hops past the target only echo the target again; without it, stop at the last responder.
+      let path_len = reached_at
+        .map(|index| index + 1)
+        .or_else(|| hops.iter().rposition(|window| window.address.is_some()).map(|index| index + 1))
+        .unwrap_or(hops.len());
+      MtrUpdate {
+        host: host.clone(),
+        target: addr.ip().to_string(),
+        round,
+        reached_target: reached_at.is_some(),
+        hops: hops
+          .iter()
+          .take(path_len)
+          .enumerate()
+          .map(|(index, window)| mtr_hop_stats(index as u8 + 1, window))
+          .collect(),
+      }
+    };
+    let _ = app.emit_all("mtr-update", update);
+    tokio::time::sleep(interval).await;
+  }
+}
+
+#[tauri::command]
+async fn start_mtr(
+  app: AppHandle,
+  host: String,
+  interval_ms: Option<u64>,
+  max_hops: Option<u8>,
+) -> MtrStartResult {
+  let mut result = MtrStartResult {
+    started: false,
+    target: None,
+    error: None,
+    error_message: None,
+  };
+  let host = host.trim().to_string();
+  if host.is_empty() {
+    result.error = Some(ErrorCode::InvalidInput);
+    return result;
+  }
+  let addr = match resolve_ping_target(&host).await {
+    Ok(addr) => addr,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  let max_hops = max_hops.unwrap_or(MTR_DEFAULT_MAX_HOPS).clamp(1, 64);
+  // Opening the raw socket with nothing to send surfaces a missing privilege before the loop starts.
+  if let Err((code, message)) = probe_ttls(addr, &[], Duration::ZERO) {
+    result.error = Some(code);
+    result.error_message = message;
+    return result;
+  }
+  let interval = Duration::from_millis(interval_ms.unwrap_or(MTR_INTERVAL_MS).max(MTR_MIN_INTERVAL_MS));
+
+  let state: State<AppState> = app.state();
+  let Ok(mut guard) = state.mtr_task.lock() else {
+    return result;
+  };
+  if let Some(handle) = guard.take() {
+    handle.abort();
+  }
+  if let Ok(mut hops) = state.mtr_hops.lock() {
+    hops.clear();
+  }
+  *guard = Some(tauri::async_runtime::spawn(run_mtr(app.clone(), host, addr, max_hops, interval)));
+  result.started = true;
+  result.target = Some(addr.ip().to_string());
+  result
+}
+
+fn stop_mtr_task(state: &AppState) -> bool {
+  let stopped = match state.mtr_task.lock() {
+    Ok(mut guard) => match guard.take() {
+      Some(handle) => {
+        handle.abort();
+        true
+      }
+      None => false,
+    },
+    Err(_) => false,
+  };
+  if let Ok(mut hops) = state.mtr_hops.lock() {
+    hops.clear();
+  }
+  stopped
+}
+
+#[tauri::command]
+fn stop_mtr(state: State<AppState>) -> bool {
+  stop_mtr_task(&state)
+}
+
+#[tauri::command]
+async fn traceroute(
+  app: AppHandle,
+  host: String,
+  max_hops: Option<u8>,
+  operation_id: Option<u64>,
+) -> Result<TracerouteResult, ErrorCode> {
+  run_cancellable(&app, operation_id, run_traceroute(app.clone(), host, max_hops)).await
+}
+
+async fn run_traceroute(app: AppHandle, host: String, max_hops: Option<u8>) -> TracerouteResult {
+  let host = host.trim().to_string();
+  let mut result = TracerouteResult {
+    host: host.clone(),
+    target: None,
+    reached_target: false,
+    hops: vec![],
+    error: None,
+    error_message: None,
+  };
+  if host.is_empty() {
+    result.error = Some(ErrorCode::InvalidInput);
+    return result;
+  }
+  let addr = match resolve_ping_target(&host).await {
+    Ok(addr) => addr,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  result.target = Some(addr.ip().to_string());
+  let resolver = system_resolver();
+  let mut lookups = Vec::new();
+  let max_hops = max_hops.unwrap_or(TRACEROUTE_DEFAULT_MAX_HOPS).clamp(1, 64);
+  for ttl in 1..=max_hops {
+    // The probes for one TTL go out together, so a silent hop costs a single timeout.
+    let probes: Vec<(u8, u16)> = (0..TRACEROUTE_PROBES_PER_HOP)
+      .map(|probe| (ttl, (ttl as u16) * TRACEROUTE_PROBES_PER_HOP + probe))
+      .collect();
+    let wait = Duration::from_millis(MTR_PROBE_TIMEOUT_MS);
+    let probed = tauri::async_runtime::spawn_blocking(move || probe_ttls(addr, &probes, wait))
+      .await
+      .unwrap_or_else(|error| Err((ErrorCode::PingFailed, Some(error.to_string()))));
+    let replies: Vec<(std::net::IpAddr, f64)> = match probed {
+      Ok(replies) => replies.into_iter().flatten().collect(),
+      Err((code, message)) => {
+        result.error = Some(code);
+        result.error_message = message;
+        return result;
+      }
+    };
+    let responder = replies.first().map(|(ip, _)| *ip);
+    let rtt_ms: Vec<f64> = replies.iter().map(|(_, ms)| round2(*ms)).collect();
+    // Names arrive later in their own event, so a slow PTR lookup never holds up the next hop.
+    if let Some(ip) = responder {
+      let (app, resolver) = (app.clone(), resolver.clone());
+      lookups.push(tauri::async_runtime::spawn(async move {
+        let hostname = reverse_dns(&resolver, ip).await?;
+        let _ = app.emit_all("traceroute-hop-name", TracerouteHopName { hop: ttl, hostname: hostname.clone() });
+        Some((ttl, hostname))
+      }));
+    }
+    let hop = TracerouteHop {
+      hop: ttl,
+      address: responder.map_or_else(|| "*".to_string(), |ip| ip.to_string()),
+      hostname: None,
+      sent: TRACEROUTE_PROBES_PER_HOP as usize,
+      received: rtt_ms.len(),
+      avg_ms: if rtt_ms.is_empty() { None } else { Some(round2(rtt_ms.iter().sum::<f64>() / rtt_ms.len() as f64)) },
+      rtt_ms,
+    };
+    let _ = app.emit_all("traceroute-hop", hop.clone());
+    result.hops.push(hop);
+    if replies.iter().any(|(ip, _)| *ip == addr.ip()) {
+      result.reached_target = true;
+      break;
+    }
+  }
+  for lookup in lookups {
+    if let Ok(Some((ttl, hostname))) = lookup.await {
+      if let Some(hop) = result.hops.iter_mut().find(|hop| hop.hop == ttl) {
+        hop.hostname = Some(hostname);
+      }
+    }
   }
-  pinger.timeout(Duration::from_secs(2));
+  result
+}
+
+async fn run_throughput_monitor(app: AppHandle, interval: Duration) {
+  let mut previous: HashMap<String, (u64, u64)> = HashMap::new();
+  let mut last_read = Instant::now();
+  loop {
+    let counters = tauri::async_runtime::spawn_blocking(read_interface_counters)
+      .await
+      .unwrap_or_default();
+    let elapsed = last_read.elapsed().as_secs_f64();
+    last_read = Instant::now();
+
+    let interfaces: Vec<InterfaceThroughput> = counters
+      .iter()
+      .filter_map(|counter| {
+        let (prev_rx, prev_tx) = previous.get(&counter.name)?;
+        Some(InterfaceThroughput {
+          name: counter.name.clone(),
+          download_mbps: bytes_to_mbps(counter.received_bytes.saturating_sub(*prev_rx), elapsed),
+          upload_mbps: bytes_to_mbps(counter.sent_bytes.saturating_sub(*prev_tx), elapsed),
+          received_bytes: counter.received_bytes,
+          sent_bytes: counter.sent_bytes,
+        })
+      })
+      .collect();
+    if !interfaces.is_empty() {
+      let timestamp = now_millis();
+      let rows: Vec<String> = interfaces
+        .iter()
+        .map(|item| {
+          format!(
+            "{},\"{}\",{},{},{},{}",
+            format_utc_timestamp(timestamp),
+            item.name.replace('"', "\"\""),
+            item.download_mbps,
+            item.upload_mbps,
+            item.received_bytes,
+            item.sent_bytes
+          )
+        })
+        .collect();
+      append_monitor_log(
+        &app,
+        "throughput",
+        "timestamp,interface,download_mbps,upload_mbps,received_bytes,sent_bytes",
+        rows,
+      );
+      let _ = app.emit_all("throughput-sample", ThroughputSample { timestamp, interfaces });
+    }
+
+    previous = counters
+      .into_iter()
+      .map(|counter| (counter.name, (counter.received_bytes, counter.sent_bytes)))
+      .collect();
+    tokio::time::sleep(interval).await;
+  }
+}
+
+type ErrorDetail = (ErrorCode, Option<String>);
+
+fn next_ping_identifier() -> PingIdentifier {
+  static NEXT: OnceLock<AtomicU16> = OnceLock::new();
+  let counter = NEXT.get_or_init(|| AtomicU16::new((std::process::id() & 0xffff) as u16));
+  PingIdentifier(counter.fetch_add(1, Ordering::Relaxed))
+}
+
+async fn resolve_ping_target(host: &str) -> Result<SocketAddr, ErrorDetail> {
+  match lookup_host(format!("{}:0", host.trim())).await {
+    Ok(mut addrs) => addrs.next().ok_or((ErrorCode::ResolveFailed, None)),
+    Err(error) => Err((ErrorCode::ResolveFailed, Some(error.to_string()))),
+  }
+}
+
+fn build_ping_client(addr: &SocketAddr) -> Result<PingClient, ErrorDetail> {
+  let mut config_builder = PingConfig::builder();
+  if addr.is_ipv6() {
+    config_builder = config_builder.kind(ICMP::V6);
+  }
+  let config = config_builder.build();
+  PingClient::new(&config).map_err(|error| (ping_client_error_code(&error), Some(error.to_string())))
+}
+
+async fn new_pinger(client: &PingClient, addr: &SocketAddr, wait: Duration) -> surge_ping::Pinger {
+  let mut pinger = client.pinger(addr.ip(), next_ping_identifier()).await;
+  if let SocketAddr::V6(v6_addr) = addr {
+    pinger.scope_id(v6_addr.scope_id());
+  }
+  pinger.timeout(wait);
+  pinger
+}
+
+fn is_message_too_long(error: &std::io::Error) -> bool {
+  #[cfg(target_os = "windows")]
+  const EMSGSIZE: i32 = 10040;
+  #[cfg(target_os = "linux")]
+  const EMSGSIZE: i32 = 90;
+  #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+  const EMSGSIZE: i32 = 40;
+  error.raw_os_error() == Some(EMSGSIZE)
+}
+
+fn ping_error_code(error: &SurgeError) -> ErrorCode {
+  match error {
+    SurgeError::Timeout { .. } => ErrorCode::Timeout,
+    _ => ErrorCode::PingFailed,
+  }
+}
+
+// Sets DF on an IPv4 socket so an oversized packet is refused along the path instead of fragmented.
+fn set_dont_fragment(socket: &socket2::Socket) -> std::io::Result<()> {
+  #[cfg(any(target_os = "linux", target_os = "macos"))]
+  {
+    use std::os::fd::AsRawFd;
+    #[cfg(target_os = "linux")]
+    let (option, value) = (libc::IP_MTU_DISCOVER, libc::IP_PMTUDISC_DO);
+    #[cfg(target_os = "macos")]
+    let (option, value): (libc::c_int, libc::c_int) = (libc::IP_DONTFRAG, 1);
+    // SAFETY: the option value points at a live c_int and the length passed is its size.
+    let status = unsafe {
+      libc::setsockopt(
+        socket.as_raw_fd(),
+        libc::IPPROTO_IP,
+        option,
+        (&value as *const libc::c_int).cast(),
+        std::mem::size_of::<libc::c_int>() as libc::socklen_t,
+      )
+    };
+    if status != 0 {
+      return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+  }
+
+  #[cfg(target_os = "windows")]
+  {
+    use std::os::windows::io::AsRawSocket;
+    use windows_sys::Win32::Networking::WinSock::{setsockopt, IPPROTO_IP, IP_DONTFRAGMENT};
+    let value: i32 = 1;
+    // SAFETY: the option value points at a live i32 and the length passed is its size.
+    let status = unsafe {
+      setsockopt(
+        socket.as_raw_socket() as usize,
+        IPPROTO_IP,
+        IP_DONTFRAGMENT,
+        (&value as *const i32).cast(),
+        std::mem::size_of::<i32>() as i32,
+      )
+    };
+    if status != 0 {
+      return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+  }
+
+  #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+  {
+    let _ = socket;
+    Err(std::io::ErrorKind::Unsupported.into())
+  }
+}
+
+// surge-ping sends without DF and only delivers replies from the target itself, so a router's
+// Fragmentation Needed or Packet Too Big never reaches ping_host. When large echoes go
+// unanswered, one more is sent from a raw socket with DF set to see whether the path MTU is why.
+fn fragmentation_check(target: SocketAddr, payload_size: usize) -> Option<ErrorDetail> {
+  use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+  use std::mem::MaybeUninit;
+
+  let v6 = target.is_ipv6();
+  let (domain, protocol) = if v6 {
+    (Domain::IPV6, Protocol::ICMPV6)
+  } else {
+    (Domain::IPV4, Protocol::ICMPV4)
+  };
+  let socket = Socket::new(domain, Type::RAW, Some(protocol)).ok()?;
+  let local = local_address_towards(target.ip())?;
+  socket.bind(&SockAddr::from(SocketAddr::new(local, 0))).ok()?;
+  // IPv6 routers never fragment, so only IPv4 needs DF to get an error back.
+  if !v6 {
+    set_dont_fragment(&socket).ok()?;
+  }
+  let identifier = next_ping_identifier().0;
+  let mut packet = vec![0u8; 8 + payload_size];
+  packet[0] = if v6 { 128 } else { 8 };
+  packet[4..6].copy_from_slice(&identifier.to_be_bytes());
+  if !v6 {
+    let checksum = icmp_checksum(&packet);
+    packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+  }
+  let size = packet.len() + if v6 { 40 } else { 20 };
+  if let Err(error) = socket.send_to(&packet, &SockAddr::from(target)) {
+    // The kernel already knows this path (or the local link) has a smaller MTU.
+    return is_message_too_long(&error).then(|| {
+      let message = format!("A {size}-byte packet exceeds the MTU of the path to {}", target.ip());
+      (ErrorCode::FragmentationNeeded, Some(message))
+    });
+  }
+
+  let mut buffer = [MaybeUninit::<u8>::uninit(); 1500];
+  let wait = Duration::from_secs(PING_HOST_TIMEOUT_SECS);
+  let started = Instant::now();
+  while let Some(remaining) = wait.checked_sub(started.elapsed()).filter(|remaining| !remaining.is_zero()) {
+    socket.set_read_timeout(Some(remaining)).ok()?;
+    // An echo reply too large for the buffer fails here, which means the size got through anyway.
+    let (len, from) = socket.recv_from(&mut buffer).ok()?;
+    // SAFETY: recv_from initialised the first `len` bytes of the buffer.
+    let message = unsafe { std::slice::from_raw_parts(buffer.as_ptr() as *const u8, len) };
+    if answered_echo(message, v6).map(|(id, _)| id) != Some(identifier) {
+      continue;
+    }
+    let icmp = icmp_message(message, v6)?;
+    let mtu = match (v6, icmp[0], icmp[1]) {
+      (false, 3, 4) => u16::from_be_bytes([icmp[6], icmp[7]]) as u32,
+      (true, 2, _) => u32::from_be_bytes([icmp[4], icmp[5], icmp[6], icmp[7]]),
+      // An echo reply or an unrelated error: the size was not what failed.
+      _ => return None,
+    };
+    let router = from.as_socket().map(|addr| addr.ip().to_string()).unwrap_or_default();
+    // Routers that predate RFC 1191 leave the next-hop MTU at zero.
+    let message = if mtu == 0 {
+      format!("{router} cannot forward a {size}-byte packet without fragmenting it")
+    } else {
+      format!("{router} reported a path MTU of {mtu} bytes, smaller than the {size}-byte packet")
+    };
+    return Some((ErrorCode::FragmentationNeeded, Some(message)));
+  }
+  None
+}
+
+// Replies to an echo request that are not echo replies, e.g. a router's destination unreachable.
+fn icmp_reply_error(packet: &IcmpPacket) -> Option<ErrorDetail> {
+  let (kind, code) = match packet {
+    IcmpPacket::V4(packet) => {
+      let (kind, code) = (packet.get_icmp_type().0, packet.get_icmp_code().0);
+      if kind == 0 {
+        return None;
+      }
+      (kind, code)
+    }
+    IcmpPacket::V6(packet) => {
+      let (kind, code) = (packet.get_icmpv6_type().0, packet.get_icmpv6_code().0);
+      if kind == 129 {
+        return None;
+      }
+      (kind, code)
+    }
+  };
+  Some((
+    ErrorCode::PingFailed,
+    Some(format!("{} replied with ICMP type {kind} code {code}", packet_source(packet))),
+  ))
+}
+
+fn failed_ping(code: ErrorCode, message: Option<String>) -> PingResponse {
+  PingResponse {
+    alive: false,
+    time: None,
+    samples: vec![],
+    min: None,
+    max: None,
+    avg: None,
+    packet_loss: 100.0,
+    method: None,
+    error: Some(code),
+    error_message: message,
+  }
+}
+
+#[tauri::command]
+async fn ping_host(
+  host: String,
+  count: Option<u16>,
+  payload_size: Option<usize>,
+  mode: Option<String>,
+  port: Option<u16>,
+) -> PingResponse {
+  let count = count.unwrap_or(1).clamp(1, PING_HOST_MAX_COUNT);
+  let mode = mode
+    .map(|value| value.trim().to_ascii_lowercase())
+    .unwrap_or_else(|| "icmp".to_string());
+  if !matches!(mode.as_str(), "icmp" | "tcp" | "auto") {
+    return failed_ping(ErrorCode::InvalidInput, Some(format!("Unknown ping mode \"{mode}\"")));
+  }
+  let addr = match resolve_ping_target(&host).await {
+    Ok(addr) => addr,
+    Err((code, message)) => return failed_ping(code, message),
+  };
+
+  // TCP mode times a full handshake instead, for networks where raw ICMP needs
+  // elevation or is filtered; auto only falls back when the ICMP socket can't be opened.
+  let client = match mode.as_str() {
+    "tcp" => None,
+    _ => match build_ping_client(&addr) {
+      Ok(client) => Some(client),
+      Err(_) if mode == "auto" => None,
+      Err((code, message)) => return failed_ping(code, message),
+    },
+  };
+  let method = if client.is_some() { "icmp" } else { "tcp" };
+  let mut tcp_addr = addr;
+  tcp_addr.set_port(port.unwrap_or(PING_TCP_DEFAULT_PORT));
+
+  let max_payload = if addr.is_ipv4() { PING_MAX_PAYLOAD_V4 } else { PING_MAX_PAYLOAD_V6 };
+  let payload_size = payload_size.unwrap_or(PING_DEFAULT_PAYLOAD_BYTES).min(max_payload);
+  let per_packet = Duration::from_secs(PING_HOST_TIMEOUT_SECS);
+  let payload = vec![0u8; payload_size];
+  let mut samples = Vec::with_capacity(count as usize);
+  let mut last_error: Option<ErrorDetail> = None;
+  let series = async {
+    for seq in 0..count {
+      if seq > 0 {
+        tokio::time::sleep(Duration::from_millis(PING_HOST_INTERVAL_MS)).await;
+      }
+      let outcome = match &client {
+        Some(client) => {
+          let mut pinger = new_pinger(client, &addr, per_packet).await;
+          match timeout(per_packet, pinger.ping(PingSequence(seq), &payload)).await {
+            Ok(Ok((packet, rtt))) => match icmp_reply_error(&packet) {
+              Some(detail) => Err(detail),
+              None => Ok(rtt.as_secs_f64() * 1000.0),
+            },
+            Ok(Err(error)) => Err((ping_error_code(&error), Some(error.to_string()))),
+            Err(_) => Err((ErrorCode::Timeout, None)),
+          }
+        }
+        None => measure_tcp_connect(tcp_addr, per_packet).await,
+      };
+      match outcome {
+        Ok(rtt) => samples.push(rtt),
+        Err(detail) => last_error = Some(detail),
+      }
+    }
+  };
+  // Every packet may use its full timeout plus the spacing between sends.
+  let total = (per_packet + Duration::from_millis(PING_HOST_INTERVAL_MS)) * count as u32;
+  if timeout(total, series).await.is_err() {
+    last_error = Some((ErrorCode::Timeout, None));
+  }
+
+  if samples.is_empty() {
+    // Oversized echoes that all went unanswered may have hit a smaller MTU rather than plain loss.
+    let timed_out = matches!(last_error, Some((ErrorCode::Timeout, _)));
+    if client.is_some() && timed_out && payload_size > PING_DEFAULT_PAYLOAD_BYTES {
+      let check = tauri::async_runtime::spawn_blocking(move || fragmentation_check(addr, payload_size));
+      if let Ok(Some(detail)) = check.await {
+        last_error = Some(detail);
+      }
+    }
+    let (code, message) = last_error.unwrap_or((ErrorCode::PingFailed, None));
+    return PingResponse {
+      method: Some(method.to_string()),
+      ..failed_ping(code, message)
+    };
+  }
+  let avg = samples.iter().sum::<f64>() / samples.len() as f64;
+  PingResponse {
+    alive: true,
+    time: Some(avg),
+    min: samples.iter().copied().reduce(f64::min),
+    max: samples.iter().copied().reduce(f64::max),
+    avg: Some(avg),
+    packet_loss: round2((count as usize - samples.len()) as f64 / count as f64 * 100.0),
+    samples,
+    method: Some(method.to_string()),
+    error: None,
+    error_message: None,
+  }
+}
+
+fn rfc3550_jitter(samples: &[Option<f64>]) -> f64 {
+  let mut jitter = 0.0;
+  let mut previous: Option<f64> = None;
+  for rtt in samples.iter().flatten() {
+    if let Some(last) = previous {
+      jitter += ((rtt - last).abs() - jitter) / 16.0;
+    }
+    previous = Some(*rtt);
+  }
+  jitter
+}
+
+fn estimate_r_factor(latency_ms: f64, jitter_ms: f64, loss_percent: f64) -> f64 {
+  // Simplified ITU-T G.107 E-model as commonly used for VoIP monitoring.
+  let effective_latency = latency_ms + jitter_ms * 2.0 + 10.0;
+  let mut r = if effective_latency < 160.0 {
+    93.2 - effective_latency / 40.0
+  } else {
+    93.2 - (effective_latency - 120.0) / 10.0
+  };
+  r -= loss_percent * 2.5;
+  r.clamp(0.0, 100.0)
+}
+
+fn r_factor_to_mos(r: f64) -> f64 {
+  if r <= 0.0 {
+    return 1.0;
+  }
+  (1.0 + 0.035 * r + 0.000007 * r * (r - 60.0) * (100.0 - r)).clamp(1.0, 4.5)
+}
+
+fn mos_quality_label(mos: f64) -> &'static str {
+  if mos >= 4.3 {
+    "excellent"
+  } else if mos >= 4.0 {
+    "good"
+  } else if mos >= 3.6 {
+    "fair"
+  } else if mos >= 3.1 {
+    "poor"
+  } else {
+    "bad"
+  }
+}
+
+fn round2(value: f64) -> f64 {
+  (value * 100.0).round() / 100.0
+}
+
+#[tauri::command]
+async fn voip_quality_test(
+  app: AppHandle,
+  target: String,
+  duration_seconds: Option<u64>,
+  operation_id: Option<u64>,
+) -> Result<VoipQualityResult, ErrorCode> {
+  run_cancellable(&app, operation_id, measure_voip_quality(target, duration_seconds)).await
+}
+
+// Sends `count` echoes at a fixed spacing; each probe runs on its own task so a slow
+// reply never delays the next send.
+async fn ping_series(
+  client: &PingClient,
+  addr: &SocketAddr,
+  count: usize,
+  interval_ms: u64,
+  payload_bytes: usize,
+) -> Vec<Option<f64>> {
+  let payload = vec![0u8; payload_bytes];
+  let mut interval = tokio::time::interval(Duration::from_millis(interval_ms));
+  let mut tasks = Vec::with_capacity(count);
+  for seq in 0..count {
+    interval.tick().await;
+    let mut pinger = new_pinger(client, addr, Duration::from_secs(1)).await;
+    let payload = payload.clone();
+    tasks.push(tauri::async_runtime::spawn(async move {
+      pinger
+        .ping(PingSequence(seq as u16), &payload)
+        .await
+        .ok()
+        .map(|(_packet, rtt)| rtt.as_secs_f64() * 1000.0)
+    }));
+  }
+  let mut samples = Vec::with_capacity(count);
+  for task in tasks {
+    samples.push(task.await.unwrap_or(None));
+  }
+  samples
+}
+
+async fn measure_voip_quality(target: String, duration_seconds: Option<u64>) -> VoipQualityResult {
+  let duration = duration_seconds
+    .unwrap_or(VOIP_DEFAULT_DURATION_SECS)
+    .clamp(1, VOIP_MAX_DURATION_SECS);
+  let failed = |code: ErrorCode, message: Option<String>| VoipQualityResult {
+    target: target.clone(),
+    packets_sent: 0,
+    packets_received: 0,
+    avg_latency_ms: 0.0,
+    jitter_ms: 0.0,
+    packet_loss: 0.0,
+    r_factor: 0.0,
+    mos: 0.0,
+    quality: String::new(),
+    error: Some(code),
+    error_message: message,
+  };
+  let addr = match resolve_ping_target(&target).await {
+    Ok(addr) => addr,
+    Err((code, message)) => return failed(code, message),
+  };
+  let client = match build_ping_client(&addr) {
+    Ok(client) => client,
+    Err((code, message)) => return failed(code, message),
+  };
+
+  // 20ms spacing mimics a G.711 RTP stream.
+  let count = (duration * 1000 / VOIP_PACKET_INTERVAL_MS) as usize;
+  let samples = ping_series(&client, &addr, count, VOIP_PACKET_INTERVAL_MS, VOIP_PAYLOAD_BYTES).await;
+
+  let received: Vec<f64> = samples.iter().flatten().copied().collect();
+  let packet_loss = (count - received.len()) as f64 / count.max(1) as f64 * 100.0;
+  let avg_latency = received.iter().sum::<f64>() / received.len().max(1) as f64;
+  let jitter = rfc3550_jitter(&samples);
+  let r_factor = if received.is_empty() {
+    0.0
+  } else {
+    estimate_r_factor(avg_latency, jitter, packet_loss)
+  };
+  let mos = r_factor_to_mos(r_factor);
+
+  VoipQualityResult {
+    target: target.clone(),
+    packets_sent: count,
+    packets_received: received.len(),
+    avg_latency_ms: round2(avg_latency),
+    jitter_ms: round2(jitter),
+    packet_loss: round2(packet_loss),
+    r_factor: round2(r_factor),
+    mos: round2(mos),
+    quality: mos_quality_label(mos).to_string(),
+    error: None,
+    error_message: None,
+  }
+}
+
+fn stability_grade(loss_percent: f64, outages: &[StabilityOutage], spike_ratio: f64) -> &'static str {
+  let longest = outages.iter().map(|outage| outage.duration_ms).max().unwrap_or(0);
+  if outages.is_empty() && loss_percent < 0.5 && spike_ratio <= 0.01 {
+    "excellent"
+  } else if outages.len() <= 1 && longest <= 2000 && loss_percent < 2.0 && spike_ratio <= 0.05 {
+    "good"
+  } else if longest <= 10_000 && loss_percent < 5.0 {
+    "fair"
+  } else {
+    "poor"
+  }
+}
+
+async fn measure_stability(app: AppHandle, target: String, duration_seconds: Option<u64>) -> StabilityResult {
+  let duration = duration_seconds
+    .unwrap_or(STABILITY_DEFAULT_DURATION_SECS)
+    .clamp(1, STABILITY_MAX_DURATION_SECS);
+  let mut result = StabilityResult {
+    target: target.trim().to_string(),
+    duration_seconds: duration,
+    samples_sent: 0,
+    samples_received: 0,
+    loss_percent: 0.0,
+    avg_latency_ms: None,
+    median_latency_ms: None,
+    outage_count: 0,
+    total_outage_ms: 0,
+    longest_outage_ms: 0,
+    outages: vec![],
+    spike_count: 0,
+    worst_spike_ms: None,
+    grade: String::new(),
+    error: None,
+    error_message: None,
+  };
+  let addr = match resolve_ping_target(&result.target).await {
+    Ok(addr) => addr,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  let client = match build_ping_client(&addr) {
+    Ok(client) => client,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+
+  let start = Instant::now();
+  let mut interval = tokio::time::interval(Duration::from_millis(STABILITY_INTERVAL_MS));
+  let mut latencies: Vec<f64> = Vec::new();
+  let mut outage_start: Option<u64> = None;
+  for seq in 0..duration {
+    interval.tick().await;
+    let sent_at = start.elapsed().as_millis() as u64;
+    let mut pinger = new_pinger(&client, &addr, Duration::from_millis(STABILITY_INTERVAL_MS)).await;
+    let latency = pinger
+      .ping(PingSequence(seq as u16), &[0u8; 56])
+      .await
+      .ok()
+      .map(|(_packet, rtt)| rtt.as_secs_f64() * 1000.0);
+    result.samples_sent += 1;
+    match (latency, outage_start) {
+      (Some(ms), Some(began)) => {
+        // The outage lasted until this echo went out and came back.
+        result.outages.push(StabilityOutage {
+          start_offset_ms: began,
+          duration_ms: sent_at - began,
+        });
+        outage_start = None;
+        latencies.push(ms);
+      }
+      (Some(ms), None) => latencies.push(ms),
+      (None, None) => outage_start = Some(sent_at),
+      (None, Some(_)) => {}
+    }
+    let _ = app.emit_all(
+      "stability-progress",
+      StabilityProgress {
+        target: result.target.clone(),
+        elapsed_seconds: seq + 1,
+        duration_seconds: duration,
+        latency_ms: latency.map(round2),
+        outage_count: result.outages.len() + usize::from(outage_start.is_some()),
+        in_outage: outage_start.is_some(),
+      },
+    );
+  }
+  if let Some(began) = outage_start {
+    result.outages.push(StabilityOutage {
+      start_offset_ms: began,
+      duration_ms: start.elapsed().as_millis() as u64 - began,
+    });
+  }
+
+  result.samples_received = latencies.len();
+  result.loss_percent = round2((result.samples_sent - latencies.len()) as f64 / result.samples_sent.max(1) as f64 * 100.0);
+  result.outage_count = result.outages.len();
+  result.total_outage_ms = result.outages.iter().map(|outage| outage.duration_ms).sum();
+  result.longest_outage_ms = result.outages.iter().map(|outage| outage.duration_ms).max().unwrap_or(0);
+  if !latencies.is_empty() {
+    let mut sorted = latencies.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted[sorted.len() / 2];
+    result.median_latency_ms = Some(round2(median));
+    result.avg_latency_ms = Some(round2(latencies.iter().sum::<f64>() / latencies.len() as f64));
+    let spikes: Vec<f64> = latencies
+      .iter()
+      .copied()
+      .filter(|ms| *ms > median * STABILITY_SPIKE_FACTOR && *ms - median > STABILITY_SPIKE_MIN_MS)
+      .collect();
+    result.spike_count = spikes.len();
+    result.worst_spike_ms = spikes.into_iter().reduce(f64::max).map(round2);
+  } else {
+    result.error = Some(ErrorCode::PingFailed);
+  }
+  let spike_ratio = result.spike_count as f64 / result.samples_sent.max(1) as f64;
+  result.grade = stability_grade(result.loss_percent, &result.outages, spike_ratio).to_string();
+  result
+}
+
+#[tauri::command]
+async fn stability_test(
+  app: AppHandle,
+  target: String,
+  duration_seconds: Option<u64>,
+  operation_id: Option<u64>,
+) -> Result<StabilityResult, ErrorCode> {
+  run_cancellable(&app, operation_id, measure_stability(app.clone(), target, duration_seconds)).await
+}
+
+struct PingAverage {
+  latency_ms: Option<f64>,
+  received: usize,
+  error: Option<ErrorCode>,
+  error_message: Option<String>,
+}
+
+#[tauri::command]
+async fn detect_icmp_rate_limit(host: String) -> IcmpRateLimitResult {
+  let mut result = IcmpRateLimitResult {
+    host: host.trim().to_string(),
+    samples: vec![],
+    rate_limited: false,
+    verdict: "unknown".to_string(),
+    error: None,
+    error_message: None,
+  };
+  let client_and_addr = match resolve_ping_target(&host).await {
+    Ok(addr) => build_ping_client(&addr).map(|client| (client, addr)),
+    Err(detail) => Err(detail),
+  };
+  let (client, addr) = match client_and_addr {
+    Ok(pair) => pair,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  // Slowest tier first so a limiter's token bucket is full when the burst starts.
+  for (interval_ms, count) in ICMP_RATE_TIERS {
+    let replies = ping_series(&client, &addr, count, interval_ms, 32).await;
+    let received = replies.iter().flatten().count();
+    result.samples.push(IcmpRateSample {
+      interval_ms,
+      sent: count,
+      received,
+      loss_percent: round2((count - received) as f64 / count as f64 * 100.0),
+    });
+  }
+  let slow = result.samples.first().map(|sample| sample.loss_percent).unwrap_or(0.0);
+  let fast = result.samples.last().map(|sample| sample.loss_percent).unwrap_or(0.0);
+  result.verdict = if slow >= 100.0 && fast >= 100.0 {
+    result.error = Some(ErrorCode::PingFailed);
+    "unreachable"
+  } else if fast - slow >= ICMP_RATE_LIMIT_LOSS_GAP {
+    result.rate_limited = true;
+    "rate-limited"
+  } else if slow > 0.0 || fast > 0.0 {
+    "loss-independent-of-rate"
+  } else {
+    "no-loss"
+  }
+  .to_string();
+  result
+}
+
+#[tauri::command]
+async fn detect_packet_reordering(host: String, count: Option<usize>) -> PacketReorderingResult {
+  let count = count.unwrap_or(REORDER_DEFAULT_COUNT).clamp(10, REORDER_MAX_COUNT);
+  let mut result = PacketReorderingResult {
+    host: host.trim().to_string(),
+    sent: count,
+    received: 0,
+    reordered: 0,
+    reordering_percent: 0.0,
+    max_displacement: 0,
+    interval_ms: REORDER_INTERVAL_MS,
+    error: None,
+    error_message: None,
+  };
+  let client_and_addr = match resolve_ping_target(&host).await {
+    Ok(addr) => build_ping_client(&addr).map(|client| (client, addr)),
+    Err(detail) => Err(detail),
+  };
+  let (client, addr) = match client_and_addr {
+    Ok(pair) => pair,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+
+  let payload = vec![0u8; 32];
+  let mut interval = tokio::time::interval(Duration::from_millis(REORDER_INTERVAL_MS));
+  let mut tasks = Vec::with_capacity(count);
+  for seq in 0..count {
+    interval.tick().await;
+    let mut pinger = new_pinger(&client, &addr, Duration::from_secs(2)).await;
+    let payload = payload.clone();
+    tasks.push(tauri::async_runtime::spawn(async move {
+      // surge-ping stamps each reply as it comes off the socket, so send time plus RTT is the
+      // real arrival order; reading the clock after this task wakes would only show scheduling.
+      let sent_at = Instant::now();
+      pinger
+        .ping(PingSequence(seq as u16), &payload)
+        .await
+        .ok()
+        .map(|(_, rtt)| (sent_at + rtt, seq))
+    }));
+  }
+  let mut arrivals = Vec::with_capacity(count);
+  for task in tasks {
+    if let Ok(Some(arrival)) = task.await {
+      arrivals.push(arrival);
+    }
+  }
+  arrivals.sort_by_key(|(arrived_at, _)| *arrived_at);
+
+  // RFC 4737: a reply is reordered when a higher sequence number arrived before it.
+  let order: Vec<usize> = arrivals.iter().map(|(_, seq)| *seq).collect();
+  let mut highest: Option<usize> = None;
+  for (position, seq) in order.iter().enumerate() {
+    match highest {
+      Some(max) if *seq < max => {
+        result.reordered += 1;
+        let expected = order.iter().filter(|other| **other < *seq).count();
+        result.max_displacement = result.max_displacement.max(position.saturating_sub(expected));
+      }
+      _ => highest = Some(*seq),
+    }
+  }
+  result.received = order.len();
+  if result.received == 0 {
+    result.error = Some(ErrorCode::PingFailed);
+  } else {
+    result.reordering_percent = round2(result.reordered as f64 / result.received as f64 * 100.0);
+  }
+  result
+}
+
+// The source address the OS would pick for `target`; connecting a UDP socket sends nothing.
+fn local_address_towards(target: std::net::IpAddr) -> Option<std::net::IpAddr> {
+  let bind = if target.is_ipv6() { "[::]:0" } else { "0.0.0.0:0" };
+  let probe = std::net::UdpSocket::bind(bind).ok()?;
+  probe.connect((target, 9)).ok()?;
+  probe.local_addr().ok().map(|addr| addr.ip())
+}
+
+fn icmp_checksum(data: &[u8]) -> u16 {
+  let mut sum: u32 = data
+    .chunks(2)
+    .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]) as u32)
+    .sum();
+  while sum >> 16 != 0 {
+    sum = (sum & 0xffff) + (sum >> 16);
+  }
+  !(sum as u16)
+}
+
+fn ms_since_midnight_utc() -> f64 {
+  (unix_seconds_now() * 1000.0) % MS_PER_DAY
+}
+
+// Brings a difference of two "milliseconds since midnight" values back into range when
+// the probe straddles midnight UTC.
+fn wrap_day_ms(delta: f64) -> f64 {
+  if delta > MS_PER_DAY / 2.0 {
+    delta - MS_PER_DAY
+  } else if delta < -MS_PER_DAY / 2.0 {
+    delta + MS_PER_DAY
+  } else {
+    delta
+  }
+}
+
+// ICMP timestamp (type 13/14) needs a raw socket, so this usually requires admin/root.
+// Returns (originate, remote receive, remote transmit, local receive) in ms since midnight.
+fn icmp_timestamp_exchange(target: std::net::Ipv4Addr) -> Result<Vec<(f64, f64, f64, f64)>, ErrorDetail> {
+  use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+  use std::io::Read;
+
+  let socket = Socket::new(Domain::IPV4, Type::RAW, Some(Protocol::ICMPV4))
+    .map_err(|error| (ping_client_error_code(&error), Some(error.to_string())))?;
+  // Windows only delivers to raw sockets bound to a concrete local address.
+  let local = local_address_towards(std::net::IpAddr::V4(target))
+    .ok_or((ErrorCode::RequestFailed, Some("no route to target".to_string())))?;
+  socket
+    .bind(&SockAddr::from(SocketAddr::new(local, 0)))
+    .map_err(|error| (ErrorCode::RequestFailed, Some(error.to_string())))?;
+  let destination = SockAddr::from(SocketAddr::new(std::net::IpAddr::V4(target), 0));
+  let identifier = next_ping_identifier().0;
+  let mut exchanges = Vec::new();
+  let mut buffer = [0u8; 1500];
+  for seq in 0..ICMP_TIMESTAMP_SAMPLES as u16 {
+    let mut packet = [0u8; 20];
+    packet[0] = 13;
+    packet[4..6].copy_from_slice(&identifier.to_be_bytes());
+    packet[6..8].copy_from_slice(&seq.to_be_bytes());
+    let originate = ms_since_midnight_utc();
+    packet[8..12].copy_from_slice(&(originate as u32).to_be_bytes());
+    let checksum = icmp_checksum(&packet);
+    packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+    socket
+      .send_to(&packet, &destination)
+      .map_err(|error| (ErrorCode::RequestFailed, Some(error.to_string())))?;
+    let started = Instant::now();
+    let wait = Duration::from_millis(ICMP_TIMESTAMP_TIMEOUT_MS);
+    while let Some(remaining) = wait.checked_sub(started.elapsed()) {
+      if remaining.is_zero() || socket.set_read_timeout(Some(remaining)).is_err() {
+        break;
+      }
+      let Ok(len) = (&socket).read(&mut buffer) else {
+        break;
+      };
+      let received_at = ms_since_midnight_utc();
+      // Raw IPv4 sockets hand back the IP header as well.
+      let header = ((buffer[0] & 0x0f) as usize) * 4;
+      let reply = &buffer[header.min(len)..len];
+      if reply.len() < 20
+        || reply[0] != 14
+        || reply[4..6] != identifier.to_be_bytes()
+        || reply[6..8] != seq.to_be_bytes()
+      {
+        continue;
+      }
+      let remote_receive = u32::from_be_bytes([reply[12], reply[13], reply[14], reply[15]]);
+      let remote_transmit = u32::from_be_bytes([reply[16], reply[17], reply[18], reply[19]]);
+      // The high bit marks a non-standard time value, which can't be compared with ours.
+      if remote_receive & 0x8000_0000 == 0 && remote_transmit & 0x8000_0000 == 0 {
+        exchanges.push((originate.trunc(), remote_receive as f64, remote_transmit as f64, received_at));
+      }
+      break;
+    }
+  }
+  Ok(exchanges)
+}
+
+#[tauri::command]
+async fn measure_one_way_delay(host: String) -> OneWayDelayResult {
+  let mut result = OneWayDelayResult {
+    host: host.trim().to_string(),
+    supported: false,
+    samples: 0,
+    round_trip_ms: None,
+    outbound_ms: None,
+    inbound_ms: None,
+    asymmetry_ms: None,
+    clock_corrected: false,
+    error: None,
+    error_message: None,
+  };
+  let target = match resolve_ping_target(&host).await {
+    Ok(SocketAddr::V4(addr)) => *addr.ip(),
+    Ok(SocketAddr::V6(_)) => {
+      result.error_message = Some("ICMP timestamps only exist for IPv4".to_string());
+      return result;
+    }
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  let exchanges = match tauri::async_runtime::spawn_blocking(move || icmp_timestamp_exchange(target)).await {
+    Ok(Ok(exchanges)) => exchanges,
+    Ok(Err((code, message))) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+    Err(err) => {
+      result.error = Some(ErrorCode::CommandFailed);
+      result.error_message = Some(err.to_string());
+      return result;
+    }
+  };
+  result.samples = exchanges.len();
+  // The exchange with the lowest round trip saw the least queuing, so its split is the cleanest.
+  let best = exchanges.iter().min_by(|left, right| {
+    let rtt = |exchange: &&(f64, f64, f64, f64)| wrap_day_ms(exchange.3 - exchange.0) - wrap_day_ms(exchange.2 - exchange.1);
+    rtt(left).total_cmp(&rtt(right))
+  });
+  let Some((originate, remote_receive, remote_transmit, received_at)) = best.copied() else {
+    result.error_message = Some("Target does not answer ICMP timestamp requests".to_string());
+    return result;
+  };
+  result.supported = true;
+  let local_offset_ms = match lookup_host(format!("{}:123", NTP_DEFAULT_SERVER)).await.ok().and_then(|mut addrs| addrs.next()) {
+    Some(addr) => sntp_query(addr).await.ok().map(|(offset, _, _, _)| offset * 1000.0),
+    None => None,
+  };
+  result.clock_corrected = local_offset_ms.is_some();
+  let correction = local_offset_ms.unwrap_or(0.0);
+  // Shift our timestamps onto NTP time before comparing them with the target's clock.
+  let outbound = wrap_day_ms(remote_receive - (originate + correction));
+  let inbound = wrap_day_ms((received_at + correction) - remote_transmit);
+  result.round_trip_ms = Some(round2(outbound + inbound));
+  result.outbound_ms = Some(round2(outbound));
+  result.inbound_ms = Some(round2(inbound));
+  result.asymmetry_ms = Some(round2(outbound - inbound));
+  result
+}
+
+async fn ping_average(host: &str, count: usize) -> PingAverage {
+  let mut samples = Vec::new();
+  let mut last_error = None;
+  for _ in 0..count {
+    let response = ping_host(host.to_string(), None, None, None, None).await;
+    match response.time {
+      Some(time) if response.alive => samples.push(time),
+      _ => last_error = Some((response.error, response.error_message)),
+    }
+  }
+  let latency_ms = if samples.is_empty() {
+    None
+  } else {
+    Some(round2(samples.iter().sum::<f64>() / samples.len() as f64))
+  };
+  let (error, error_message) = match (&latency_ms, last_error) {
+    (None, Some((error, message))) => (error, message),
+    _ => (None, None),
+  };
+  PingAverage {
+    latency_ms,
+    received: samples.len(),
+    error,
+    error_message,
+  }
+}
+
+async fn measure_game_server(server: GameServer) -> GameLatencyResult {
+  let (method, average) = match server.port {
+    Some(port) => {
+      let response = ping_host(
+        server.host.clone(),
+        Some(GAME_LATENCY_SAMPLES as u16),
+        None,
+        Some("tcp".to_string()),
+        Some(port),
+      )
+      .await;
+      let average = PingAverage {
+        latency_ms: response.avg.map(round2),
+        received: response.samples.len(),
+        error: if response.alive { None } else { response.error },
+        error_message: if response.alive { None } else { response.error_message },
+      };
+      ("tcp", average)
+    }
+    None => ("icmp", ping_average(&server.host, GAME_LATENCY_SAMPLES).await),
+  };
+  GameLatencyResult {
+    name: server.name,
+    region: server.region,
+    host: server.host,
+    alive: average.latency_ms.is_some(),
+    latency_ms: average.latency_ms,
+    samples_received: average.received,
+    method: method.to_string(),
+    error: average.error,
+    error_message: average.error_message,
+  }
+}
+
+#[tauri::command]
+async fn test_game_latency(region: Option<String>, extra_servers: Option<Vec<GameServer>>) -> Vec<GameLatencyResult> {
+  let filter = region
+    .map(|value| value.trim().to_lowercase())
+    .filter(|value| !value.is_empty());
+  let mut servers: Vec<GameServer> = GAME_SERVERS
+    .iter()
+    .map(|(name, region, host)| GameServer {
+      name: name.to_string(),
+      region: region.to_string(),
+      host: host.to_string(),
+      port: Some(GAME_SERVER_TCP_PORT),
+    })
+    .collect();
+  servers.extend(extra_servers.unwrap_or_default());
+  servers.retain(|server| {
+    !server.host.trim().is_empty()
+      && filter
+        .as_ref()
+        .map(|wanted| server.region.to_lowercase().starts_with(wanted.as_str()))
+        .unwrap_or(true)
+  });
+
+  let tasks: Vec<_> = servers
+    .into_iter()
+    .map(|server| tauri::async_runtime::spawn(measure_game_server(server)))
+    .collect();
+  let mut results = Vec::with_capacity(tasks.len());
+  for task in tasks {
+    if let Ok(result) = task.await {
+      results.push(result);
+    }
+  }
+  results
+}
+
+#[tauri::command]
+fn get_app_version(app: tauri::AppHandle) -> String {
+  app.package_info().version.to_string()
+}
+
+#[tauri::command]
+fn get_username() -> String {
+  env::var("USERNAME")
+    .or_else(|_| env::var("USER"))
+    .unwrap_or_else(|_| "User".to_string())
+}
+
+#[tauri::command]
+fn get_auto_launch(_app: tauri::AppHandle) -> bool {
+  #[cfg(target_os = "windows")]
+  {
+    is_auto_launch_enabled()
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    read_auto_launch_pref(&_app).unwrap_or(false)
+  }
+}
+
+#[tauri::command]
+fn set_auto_launch(app: tauri::AppHandle, enabled: bool) -> AutoLaunchResult {
+  #[cfg(target_os = "windows")]
+  let (enabled, outcome) = {
+    let outcome = set_auto_launch_enabled(enabled);
+    (is_auto_launch_enabled(), outcome)
+  };
+
+  #[cfg(not(target_os = "windows"))]
+  let (enabled, outcome) = match auto_launcher() {
+    Ok(launcher) => {
+      let outcome = if enabled { launcher.enable() } else { launcher.disable() };
+      (
+        launcher.is_enabled().unwrap_or(false),
+        outcome.map_err(|error| (ErrorCode::CommandFailed, Some(error.to_string()))),
+      )
+    }
+    Err(error) => (false, Err((ErrorCode::CommandFailed, Some(error.to_string())))),
+  };
+
+  // Record what actually took effect so a failed toggle doesn't read back as enabled.
+  write_auto_launch_pref(&app, enabled);
+  let (error, error_message) = match outcome {
+    Ok(()) => (None, None),
+    Err((code, message)) => (Some(code), message),
+  };
+  AutoLaunchResult { enabled, error, error_message }
+}
+
+#[tauri::command]
+fn get_close_action(state: State<AppState>) -> String {
+  state
+    .close_action
+    .lock()
+    .map(|guard| guard.clone())
+    .unwrap_or_else(|_| "ask".to_string())
+}
+
+#[tauri::command]
+fn set_close_action(app: AppHandle, state: State<AppState>, action: String) -> String {
+  if CLOSE_ACTIONS.contains(&action.as_str()) {
+    write_close_action_pref(&app, &action);
+    if let Ok(mut guard) = state.close_action.lock() {
+      *guard = action;
+    }
+  }
+  state
+    .close_action
+    .lock()
+    .map(|guard| guard.clone())
+    .unwrap_or_else(|_| "ask".to_string())
+}
+
+#[tauri::command]
+fn begin_operation(state: State<AppState>) -> u64 {
+  static NEXT_OPERATION_ID: AtomicU64 = AtomicU64::new(1);
+  let id = NEXT_OPERATION_ID.fetch_add(1, Ordering::Relaxed);
+  if let Ok(mut guard) = state.operations.lock() {
+    guard.insert(id, CancelToken::default());
+  }
+  id
+}
+
+#[tauri::command]
+fn cancel_operation(state: State<AppState>, id: u64) -> bool {
+  match state.operations.lock() {
+    Ok(guard) => match guard.get(&id) {
+      Some(token) => {
+        token.cancel();
+        true
+      }
+      None => false,
+    },
+    Err(_) => false,
+  }
+}
+
+async fn run_cancellable<T>(
+  app: &AppHandle,
+  operation_id: Option<u64>,
+  work: impl std::future::Future<Output = T>,
+) -> Result<T, ErrorCode> {
+  let Some(id) = operation_id else {
+    return Ok(work.await);
+  };
+  let token = operation_token(app, id);
+  let outcome = run_with_token(&token, work).await;
+  let state: State<AppState> = app.state();
+  if let Ok(mut guard) = state.operations.lock() {
+    guard.remove(&id);
+  }
+  outcome
+}
+
+fn operation_token(app: &AppHandle, id: u64) -> CancelToken {
+  let state: State<AppState> = app.state();
+  state
+    .operations
+    .lock()
+    .map(|mut guard| guard.entry(id).or_default().clone())
+    .unwrap_or_default()
+}
+
+// Blocking work can't be dropped mid-way, so it polls this flag between steps instead.
+fn operation_cancel_flag(app: &AppHandle, operation_id: Option<u64>) -> Arc<AtomicBool> {
+  match operation_id {
+    Some(id) => operation_token(app, id).cancelled,
+    None => Arc::new(AtomicBool::new(false)),
+  }
+}
+
+async fn run_with_token<T>(token: &CancelToken, work: impl std::future::Future<Output = T>) -> Result<T, ErrorCode> {
+  // Dropping the losing future stops the work at its next await point.
+  tokio::select! {
+    value = work => Ok(value),
+    _ = token.cancelled() => Err(ErrorCode::Cancelled),
+  }
+}
+
+fn mark_activity(state: &State<AppState>) {
+  if let Ok(mut guard) = state.last_activity.lock() {
+    *guard = now_millis();
+  }
+}
+
+#[tauri::command]
+fn record_activity(state: State<AppState>) {
+  mark_activity(&state);
+}
+
+async fn run_auto_hide(app: AppHandle, idle: Duration) {
+  loop {
+    tokio::time::sleep(Duration::from_millis(AUTO_HIDE_CHECK_INTERVAL_MS)).await;
+    let state: State<AppState> = app.state();
+    let last_activity = state.last_activity.lock().map(|guard| *guard).unwrap_or_else(|_| now_millis());
+    if now_millis().saturating_sub(last_activity) < idle.as_millis() {
+      continue;
+    }
+    let Some(window) = app.get_window("main") else {
+      continue;
+    };
+    if !window.is_visible().unwrap_or(false) || window.is_minimized().unwrap_or(false) {
+      continue;
+    }
+    let action = state
+      .close_action
+      .lock()
+      .map(|guard| guard.clone())
+      .unwrap_or_else(|_| "ask".to_string());
+    // Users who close to exit or minimize don't rely on the tray, so keep the window reachable from the taskbar.
+    if action == "exit" || action == "minimize" {
+      let _ = window.minimize();
+    } else {
+      let _ = window.hide();
+    }
+  }
+}
+
+#[tauri::command]
+fn set_auto_hide(app: AppHandle, state: State<AppState>, minutes: u64, enabled: bool) -> bool {
+  let mut guard = match state.auto_hide.lock() {
+    Ok(guard) => guard,
+    Err(_) => return false,
+  };
+  if let Some(handle) = guard.take() {
+    handle.abort();
+  }
+  if !enabled || minutes == 0 {
+    return false;
+  }
+  mark_activity(&state);
+  let idle = Duration::from_secs(minutes.min(AUTO_HIDE_MAX_MINUTES) * 60);
+  *guard = Some(tauri::async_runtime::spawn(run_auto_hide(app, idle)));
+  true
+}
+
+#[tauri::command]
+fn perform_close_action(action: String, window: Window) -> bool {
+  match action.as_str() {
+    "exit" => {
+      let app = window.app_handle();
+      revert_pending_dns_change(&app);
+      app.exit(0);
+    }
+    "hide" => {
+      let _ = window.hide();
+    }
+    "minimize" => {
+      let _ = window.minimize();
+    }
+    _ => {}
+  }
+  true
+}
+
+fn name_server_config(socket_addr: SocketAddr, protocol: Protocol, tls_dns_name: Option<String>) -> NameServerConfig {
+  NameServerConfig {
+    socket_addr,
+    protocol,
+    tls_dns_name,
+    trust_negative_responses: false,
+    tls_config: None,
+    bind_addr: None,
+  }
+}
+
+fn udp_name_server(socket_addr: SocketAddr) -> NameServerConfig {
+  name_server_config(socket_addr, Protocol::Udp, None)
+}
+
+fn parse_dns_protocol(value: Option<&str>) -> Result<Protocol, ErrorDetail> {
+  match value.map(|item| item.trim().to_ascii_lowercase()).as_deref() {
+    None | Some("") | Some("udp") => Ok(Protocol::Udp),
+    Some("tcp") => Ok(Protocol::Tcp),
+    Some("tls") => Ok(Protocol::Tls),
+    Some("https") => Ok(Protocol::Https),
+    Some(other) => Err((ErrorCode::InvalidInput, Some(format!("Unknown DNS protocol \"{other}\"")))),
+  }
+}
+
+fn known_tls_dns_name(server: &str, protocol: Protocol) -> Option<&'static str> {
+  let (_, dot, doh) = DNS_SERVER_TLS_NAMES.iter().find(|(address, _, _)| *address == server.trim())?;
+  match protocol {
+    Protocol::Tls => Some(dot),
+    Protocol::Https => Some(doh),
+    _ => None,
+  }
+}
+
+// Bare addresses get the transport's standard port; an explicit ip:port is kept as given.
+fn dns_server_socket_for(server: &str, protocol: Protocol) -> Option<SocketAddr> {
+  let mut socket_addr = parse_dns_server_socket(server)?;
+  if server.trim().parse::<SocketAddr>().is_err() {
+    socket_addr.set_port(match protocol {
+      Protocol::Tls => 853,
+      Protocol::Https => 443,
+      _ => 53,
+    });
+  }
+  Some(socket_addr)
+}
+
+// Replays just the TLS handshake so an encrypted lookup failure can be told apart from a timeout.
+async fn probe_dns_tls_handshake(socket_addr: SocketAddr, tls_dns_name: &str, wait: Duration) -> Option<String> {
+  use tokio_rustls::rustls;
+
+  let Ok(server_name) = rustls::ServerName::try_from(tls_dns_name) else {
+    return Some(format!("Invalid TLS name \"{tls_dns_name}\""));
+  };
+  let outcome = Arc::new(Mutex::new(None));
+  let connector = recording_tls_connector(outcome.clone());
+  let stream = match timeout(wait, tokio::net::TcpStream::connect(socket_addr)).await {
+    Ok(Ok(stream)) => stream,
+    _ => return None,
+  };
+  match timeout(wait, connector.connect(server_name, stream)).await {
+    Ok(Ok(_tls)) => outcome.lock().ok().and_then(|guard| guard.clone()),
+    Ok(Err(error)) => Some(error.to_string()),
+    Err(_) => Some("TLS handshake timed out".to_string()),
+  }
+}
+
+fn build_resolver_opts(options: &DnsTestOptions) -> ResolverOpts {
+  let mut opts = ResolverOpts::default();
+  opts.timeout = Duration::from_millis(options.timeout_ms.unwrap_or(DNS_TIMEOUT_MS).clamp(100, DNS_TIMEOUT_MS * 4));
+  if let Some(attempts) = options.attempts {
+    opts.attempts = attempts.clamp(1, 10);
+  }
+  if let Some(cache_size) = options.cache_size {
+    opts.cache_size = cache_size;
+  }
+  if let Some(use_hosts_file) = options.use_hosts_file {
+    opts.use_hosts_file = use_hosts_file;
+  }
+  if let Some(edns0) = options.edns0 {
+    opts.edns0 = edns0;
+  }
+  opts
+}
+
+fn single_server_resolver_with(socket_addr: SocketAddr, opts: ResolverOpts) -> TokioAsyncResolver {
+  name_server_resolver(udp_name_server(socket_addr), opts)
+}
+
+fn name_server_resolver(name_server: NameServerConfig, opts: ResolverOpts) -> TokioAsyncResolver {
+  let mut resolver_config = ResolverConfig::new();
+  resolver_config.add_name_server(name_server);
+  TokioAsyncResolver::tokio(resolver_config, opts)
+}
+
+fn single_server_resolver(socket_addr: SocketAddr) -> TokioAsyncResolver {
+  single_server_resolver_with(socket_addr, build_resolver_opts(&DnsTestOptions::default()))
+}
+
+#[tauri::command]
+async fn test_dns_servers(domain: String) -> DnsResponse {
+  test_dns_servers_with_custom(domain, None, None, None, None, None).await
+}
+
+async fn test_single_dns_server(
+  server: String,
+  domain: &str,
+  record_type: Option<RecordType>,
+  protocol: Protocol,
+  tls_dns_name: Option<String>,
+  opts: ResolverOpts,
+) -> DnsResult {
+  let start = Instant::now();
+  let failed = |server: String, code: ErrorCode, message: Option<String>| DnsResult {
+    server,
+    status: false,
+    response_time_ms: start.elapsed().as_millis(),
+    records: vec![],
+    protocol: protocol.to_string(),
+    error: Some(code),
+    error_message: message,
+  };
+  let Some(socket_addr) = dns_server_socket_for(&server, protocol) else {
+    return failed(server, ErrorCode::InvalidServer, None);
+  };
+  let encrypted = matches!(protocol, Protocol::Tls | Protocol::Https);
+  let tls_dns_name = tls_dns_name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+  if encrypted && tls_dns_name.is_none() {
+    return failed(
+      server,
+      ErrorCode::InvalidInput,
+      Some("A TLS name is required for encrypted DNS".to_string()),
+    );
+  }
+  let resolver = name_server_resolver(name_server_config(socket_addr, protocol, tls_dns_name.clone()), opts);
+  // The outer timeout covers every attempt the resolver is allowed to make.
+  let overall = opts.timeout * opts.attempts.max(1) as u32;
+  // Without an explicit type this stays an address lookup (A and/or AAAA).
+  let lookup = timeout(overall, async {
+    match record_type {
+      Some(record_type) => resolver
+        .lookup(domain, record_type)
+        .await
+        .map(|lookup| lookup.iter().map(|rdata| rdata.to_string()).collect::<Vec<String>>()),
+      None => resolver
+        .lookup_ip(domain)
+        .await
+        .map(|lookup| lookup.iter().map(|ip| ip.to_string()).collect()),
+    }
+  })
+  .await;
+  let (code, message) = match lookup {
+    Ok(Ok(records)) => {
+      return DnsResult {
+        server,
+        status: true,
+        response_time_ms: start.elapsed().as_millis(),
+        records,
+        protocol: protocol.to_string(),
+        error: None,
+        error_message: None,
+      }
+    }
+    Ok(Err(err)) => (ErrorCode::LookupFailed, Some(err.to_string())),
+    Err(_) => (ErrorCode::Timeout, None),
+  };
+  if let Some(name) = tls_dns_name.as_deref().filter(|_| encrypted) {
+    if let Some(handshake_error) = probe_dns_tls_handshake(socket_addr, name, opts.timeout).await {
+      return failed(server, ErrorCode::TlsHandshakeFailed, Some(handshake_error));
+    }
+  }
+  failed(server, code, message)
+}
+
+#[tauri::command]
+async fn test_dns_servers_with_custom(
+  domain: String,
+  custom_servers: Option<Vec<String>>,
+  options: Option<DnsTestOptions>,
+  record_type: Option<String>,
+  protocol: Option<String>,
+  tls_dns_name: Option<String>,
+) -> DnsResponse {
+  let options = options.unwrap_or_default();
+  let opts = build_resolver_opts(&options);
+  let sanitized = sanitize_domain(&domain);
+  if sanitized.is_empty() {
+    return DnsResponse {
+      error: Some(ErrorCode::InvalidDomain),
+      error_message: None,
+      results: vec![],
+      fastest: None,
+      recommended: None,
+      rejected: vec![],
+    };
+  }
+  let record_type = match record_type.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+    Some(value) => match parse_record_type(Some(value)) {
+      Ok(record_type) => Some(record_type),
+      Err((code, message)) => {
+        return DnsResponse {
+          error: Some(code),
+          error_message: message,
+          results: vec![],
+          fastest: None,
+          recommended: None,
+          rejected: vec![],
+        }
+      }
+    },
+    None => None,
+  };
+  let protocol = match parse_dns_protocol(protocol.as_deref()) {
+    Ok(protocol) => protocol,
+    Err((code, message)) => {
+      return DnsResponse {
+        error: Some(code),
+        error_message: message,
+        results: vec![],
+        fastest: None,
+        recommended: None,
+        rejected: vec![],
+      }
+    }
+  };
+  let mut all_servers: Vec<String> = DNS_SERVERS.iter().map(|item| item.to_string()).collect();
+  // Entries are compared by the socket they resolve to, so "8.8.8.8" and "8.8.8.8:53" are one server.
+  let mut seen: Vec<SocketAddr> = all_servers
+    .iter()
+    .filter_map(|server| dns_server_socket_for(server, protocol))
+    .collect();
+  let mut rejected = Vec::new();
+  for server in custom_servers.unwrap_or_default() {
+    let normalized = server.trim().to_string();
+    if normalized.is_empty() {
+      continue;
+    }
+    match dns_server_socket_for(&normalized, protocol) {
+      Some(socket_addr) if seen.contains(&socket_addr) => {}
+      Some(socket_addr) => {
+        seen.push(socket_addr);
+        all_servers.push(normalized);
+      }
+      None => rejected.push(normalized),
+    }
+  }
+  let mut results = Vec::new();
+  for server in all_servers {
+    // The built-in resolvers carry their own certificate names; the caller's name covers the rest.
+    let server_tls_name = known_tls_dns_name(&server, protocol)
+      .map(str::to_string)
+      .or_else(|| tls_dns_name.clone());
+    results.push(test_single_dns_server(server, &sanitized, record_type, protocol, server_tls_name, opts).await);
+  }
+
+  let fastest = fastest_dns_server(&results, |_| true);
+  // Prefer a resolver whose sibling addresses also answered, so a fast but flaky provider isn't
+  // recommended over one that is equally usable as primary and secondary.
+  let recommended = fastest_dns_server(&results, |result| {
+    let provider = dns_provider_key(&result.server);
+    results
+      .iter()
+      .filter(|other| dns_provider_key(&other.server) == provider)
+      .all(|other| other.status)
+  })
+  .or_else(|| fastest.clone());
+  DnsResponse {
+    error: None,
+    error_message: None,
+    results,
+    fastest,
+    recommended,
+    rejected,
+  }
+}
+
+// Ties keep the earlier entry, so the built-in servers win over custom ones listed after them.
+fn fastest_dns_server(results: &[DnsResult], eligible: impl Fn(&DnsResult) -> bool) -> Option<String> {
+  results
+    .iter()
+    .filter(|result| result.status && eligible(result))
+    .min_by_key(|result| result.response_time_ms)
+    .map(|result| result.server.clone())
+}
+
+fn dns_provider_key(server: &str) -> &str {
+  known_tls_dns_name(server, Protocol::Tls).unwrap_or(server.trim())
+}
+
+async fn timed_resolve(resolver: &TokioAsyncResolver, domain: &str, wait: Duration) -> Result<f64, ErrorDetail> {
+  let start = Instant::now();
+  match timeout(wait, resolver.lookup_ip(domain)).await {
+    Ok(Ok(_)) => Ok(round2(start.elapsed().as_secs_f64() * 1000.0)),
+    Ok(Err(err)) => Err((ErrorCode::LookupFailed, Some(err.to_string()))),
+    Err(_) => Err((ErrorCode::Timeout, None)),
+  }
+}
+
+#[tauri::command]
+async fn test_dns_failover(primary: String, secondary: String, domain: String) -> DnsFailoverResult {
+  let sanitized = sanitize_domain(&domain);
+  let mut result = DnsFailoverResult {
+    domain: sanitized.clone(),
+    primary: primary.trim().to_string(),
+    secondary: secondary.trim().to_string(),
+    primary_ok: false,
+    primary_ms: None,
+    secondary_ok: false,
+    secondary_ms: None,
+    failover_ok: false,
+    failover_ms: None,
+    error: None,
+    error_message: None,
+  };
+  if sanitized.is_empty() {
+    result.error = Some(ErrorCode::InvalidDomain);
+    return result;
+  }
+  let (Some(primary_addr), Some(secondary_addr)) = (
+    parse_dns_server_socket(&result.primary),
+    parse_dns_server_socket(&result.secondary),
+  ) else {
+    result.error = Some(ErrorCode::InvalidServer);
+    return result;
+  };
+
+  let wait = Duration::from_millis(DNS_TIMEOUT_MS);
+  let primary_resolver = single_server_resolver(primary_addr);
+  let secondary_resolver = single_server_resolver(secondary_addr);
+  let (primary_lookup, secondary_lookup) = tokio::join!(
+    timed_resolve(&primary_resolver, &sanitized, wait),
+    timed_resolve(&secondary_resolver, &sanitized, wait)
+  );
+  result.primary_ok = primary_lookup.is_ok();
+  result.primary_ms = primary_lookup.ok();
+  match secondary_lookup {
+    Ok(ms) => {
+      result.secondary_ok = true;
+      result.secondary_ms = Some(ms);
+    }
+    Err((code, message)) => {
+      // Without a working secondary there is nothing to fail over to.
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  }
+
+  let blackhole = if secondary_addr.is_ipv6() { DNS_BLACKHOLE_V6 } else { DNS_BLACKHOLE_V4 };
+  let Some(blackhole_addr) = parse_dns_server_socket(blackhole) else {
+    result.error = Some(ErrorCode::InvalidServer);
+    return result;
+  };
+  let mut resolver_config = ResolverConfig::new();
+  resolver_config.add_name_server(udp_name_server(blackhole_addr));
+  resolver_config.add_name_server(udp_name_server(secondary_addr));
+  let mut opts = ResolverOpts::default();
+  opts.timeout = Duration::from_millis(DNS_FAILOVER_TIMEOUT_MS);
+  opts.num_concurrent_reqs = 1;
+  opts.server_ordering_strategy = trust_dns_resolver::config::ServerOrderingStrategy::UserProvidedOrder;
+  opts.cache_size = 0;
+  let failover_resolver = TokioAsyncResolver::tokio(resolver_config, opts);
+  match timed_resolve(&failover_resolver, &sanitized, wait + Duration::from_millis(DNS_FAILOVER_TIMEOUT_MS)).await {
+    Ok(ms) => {
+      result.failover_ok = true;
+      result.failover_ms = Some(ms);
+    }
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+    }
+  }
+  result
+}
+
+async fn resolve_with_system(domain: &str) -> Result<Vec<String>, ErrorCode> {
+  match timeout(Duration::from_millis(DNS_TIMEOUT_MS), lookup_host((domain, 0))).await {
+    Ok(Ok(addrs)) => Ok(addrs.map(|addr| addr.ip().to_string()).collect()),
+    Ok(Err(_)) => Err(ErrorCode::LookupFailed),
+    Err(_) => Err(ErrorCode::Timeout),
+  }
+}
+
+async fn resolve_with_server(resolver: &TokioAsyncResolver, domain: &str) -> Result<Vec<String>, ErrorCode> {
+  match timeout(Duration::from_millis(DNS_TIMEOUT_MS), resolver.lookup_ip(domain)).await {
+    Ok(Ok(lookup)) => Ok(lookup.iter().map(|ip| ip.to_string()).collect()),
+    Ok(Err(_)) => Err(ErrorCode::LookupFailed),
+    Err(_) => Err(ErrorCode::Timeout),
+  }
+}
+
+fn sorted_unique(mut addresses: Vec<String>) -> Vec<String> {
+  addresses.sort();
+  addresses.dedup();
+  addresses
+}
+
+#[tauri::command]
+async fn test_split_dns(domains: Vec<String>, public_resolver: Option<String>) -> SplitDnsResult {
+  let resolver_name = public_resolver
+    .map(|value| value.trim().to_string())
+    .filter(|value| !value.is_empty())
+    .unwrap_or_else(|| DNS_SERVERS[0].to_string());
+  let mut result = SplitDnsResult {
+    resolver: resolver_name.clone(),
+    differing_count: 0,
+    results: vec![],
+    error: None,
+    error_message: None,
+  };
+  let Some(socket_addr) = parse_dns_server_socket(&resolver_name) else {
+    result.error = Some(ErrorCode::InvalidServer);
+    return result;
+  };
+  let resolver = single_server_resolver(socket_addr);
+
+  for domain in domains {
+    let sanitized = sanitize_domain(&domain);
+    let mut entry = SplitDnsEntry {
+      domain: sanitized.clone(),
+      system_addresses: vec![],
+      public_addresses: vec![],
+      differs: false,
+      system_error: None,
+      public_error: None,
+      error: None,
+      error_message: None,
+    };
+    if sanitized.is_empty() {
+      entry.domain = domain;
+      entry.error = Some(ErrorCode::InvalidDomain);
+      result.results.push(entry);
+      continue;
+    }
+
+    let (system, public) = tokio::join!(
+      resolve_with_system(&sanitized),
+      resolve_with_server(&resolver, &sanitized)
+    );
+    match system {
+      Ok(addresses) => entry.system_addresses = sorted_unique(addresses),
+      Err(code) => entry.system_error = Some(code),
+    }
+    match public {
+      Ok(addresses) => entry.public_addresses = sorted_unique(addresses),
+      Err(code) => entry.public_error = Some(code),
+    }
+    // Only one side answering is the clearest sign of a split rule (e.g. internal-only names).
+    entry.differs = entry.system_error.is_some() != entry.public_error.is_some()
+      || (entry.system_error.is_none() && entry.system_addresses != entry.public_addresses);
+    if entry.differs {
+      result.differing_count += 1;
+    }
+    result.results.push(entry);
+  }
+  result
+}
+
+fn answer_addresses(message: &Message) -> Vec<String> {
+  let addresses = message
+    .answers()
+    .iter()
+    .filter_map(|record| match record.data() {
+      Some(RData::A(address)) => Some(address.to_string()),
+      Some(RData::AAAA(address)) => Some(address.to_string()),
+      _ => None,
+    })
+    .collect();
+  sorted_unique(addresses)
+}
+
+// Walks up the labels until a zone cut answers with NS records, then resolves each
+// nameserver to an address.
+async fn find_authoritative_servers(domain: &str, resolver: SocketAddr) -> Vec<(String, SocketAddr)> {
+  let mut zone = domain.trim_end_matches('.').to_string();
+  loop {
+    let names: Vec<String> = match build_dns_query(&zone, RecordType::NS, true) {
+      Ok(query) => match send_dns_query(resolver, &query).await {
+        Ok(response) => response
+          .message
+          .answers()
+          .iter()
+          .filter_map(|record| match record.data() {
+            Some(RData::NS(ns)) => Some(normalize_dns_name(&ns.0)),
+            _ => None,
+          })
+          .collect(),
+        Err(_) => vec![],
+      },
+      Err(_) => vec![],
+    };
+    if !names.is_empty() {
+      let mut servers = Vec::new();
+      for name in sorted_unique(names) {
+        if let Ok(Some(addr)) = timeout(Duration::from_millis(DNS_TIMEOUT_MS), lookup_host((name.as_str(), 53)))
+          .await
+          .map(|found| found.ok().and_then(|mut addrs| addrs.find(|addr| addr.is_ipv4())))
+        {
+          servers.push((name, addr));
+        }
+      }
+      return servers;
+    }
+    match zone.split_once('.') {
+      Some((_, parent)) if parent.contains('.') => zone = parent.to_string(),
+      _ => return vec![],
+    }
+  }
+}
+
+#[tauri::command]
+async fn check_dns_propagation(domain: String, expected_ip: String) -> DnsPropagationResult {
+  let sanitized = sanitize_domain(&domain);
+  let expected = expected_ip.trim().to_string();
+  let mut result = DnsPropagationResult {
+    domain: sanitized.clone(),
+    expected: expected.clone(),
+    propagated_count: 0,
+    total_count: 0,
+    propagation_percent: 0.0,
+    results: vec![],
+    error: None,
+    error_message: None,
+  };
+  if sanitized.is_empty() {
+    result.error = Some(ErrorCode::InvalidDomain);
+    return result;
+  }
+  let record_type = match expected.parse::<std::net::IpAddr>() {
+    Ok(std::net::IpAddr::V4(_)) => RecordType::A,
+    Ok(std::net::IpAddr::V6(_)) => RecordType::AAAA,
+    Err(_) => {
+      result.error = Some(ErrorCode::InvalidInput);
+      result.error_message = Some("Expected value must be an IP address".to_string());
+      return result;
+    }
+  };
+  let expected_normalized = expected.parse::<std::net::IpAddr>().map(|ip| ip.to_string()).unwrap_or_default();
+
+  let mut targets: Vec<(String, String, Option<String>, SocketAddr)> = DNS_SERVERS
+    .iter()
+    .filter_map(|server| {
+      parse_dns_server_socket(server).map(|addr| (server.to_string(), "recursive".to_string(), None, addr))
+    })
+    .collect();
+  if let Some(resolver) = parse_dns_server_socket(DNS_SERVERS[0]) {
+    for (name, addr) in find_authoritative_servers(&sanitized, resolver).await {
+      targets.push((addr.ip().to_string(), "authoritative".to_string(), Some(name), addr));
+    }
+  }
+
+  let tasks: Vec<_> = targets
+    .into_iter()
+    .map(|(server, kind, nameserver, addr)| {
+      let domain = sanitized.clone();
+      // Authoritative servers are asked directly so their answer isn't served from a cache.
+      let recursion_desired = kind == "recursive";
+      tauri::async_runtime::spawn(async move {
+        let outcome = match build_dns_query(&domain, record_type, recursion_desired) {
+          Ok(query) => send_dns_query(addr, &query).await.map(|response| answer_addresses(&response.message)),
+          Err(detail) => Err(detail),
+        };
+        (server, kind, nameserver, outcome)
+      })
+    })
+    .collect();
+  for task in tasks {
+    let Ok((server, kind, nameserver, outcome)) = task.await else {
+      continue;
+    };
+    let entry = match outcome {
+      Ok(addresses) => PropagationEntry {
+        matches: addresses.contains(&expected_normalized),
+        server,
+        kind,
+        nameserver,
+        addresses,
+        error: None,
+        error_message: None,
+      },
+      Err((code, message)) => PropagationEntry {
+        server,
+        kind,
+        nameserver,
+        addresses: vec![],
+        matches: false,
+        error: Some(code),
+        error_message: message,
+      },
+    };
+    result.total_count += 1;
+    if entry.matches {
+      result.propagated_count += 1;
+    }
+    result.results.push(entry);
+  }
+  if result.total_count > 0 {
+    result.propagation_percent = round2(result.propagated_count as f64 / result.total_count as f64 * 100.0);
+  }
+  result
+}
+
+#[tauri::command]
+async fn compare_geodns(host: String, servers: Option<Vec<String>>) -> GeoDnsResult {
+  let sanitized = sanitize_domain(&host);
+  let mut result = GeoDnsResult {
+    host: sanitized.clone(),
+    resolvers: vec![],
+    endpoints: vec![],
+    recommended_server: None,
+    error: None,
+    error_message: None,
+  };
+  if sanitized.is_empty() {
+    result.error = Some(ErrorCode::InvalidDomain);
+    return result;
+  }
+  let servers: Vec<String> = servers
+    .filter(|list| !list.is_empty())
+    .unwrap_or_else(|| DNS_SERVERS.iter().map(|server| server.to_string()).collect());
+
+  let lookups: Vec<_> = servers
+    .into_iter()
+    .map(|server| {
+      let domain = sanitized.clone();
+      tauri::async_runtime::spawn(async move {
+        let outcome = match parse_dns_server_socket(&server) {
+          Some(addr) => resolve_with_server(&single_server_resolver(addr), &domain).await,
+          None => Err(ErrorCode::InvalidServer),
+        };
+        (server, outcome)
+      })
+    })
+    .collect();
+  for lookup in lookups {
+    let Ok((server, outcome)) = lookup.await else {
+      continue;
+    };
+    let (addresses, error) = match outcome {
+      Ok(addresses) => (sorted_unique(addresses), None),
+      Err(code) => (vec![], Some(code)),
+    };
+    result.resolvers.push(GeoDnsEntry {
+      server,
+      addresses,
+      best_address: None,
+      best_latency_ms: None,
+      error,
+    });
+  }
+
+  // Each distinct endpoint is pinged once even when several resolvers return it.
+  let unique = sorted_unique(result.resolvers.iter().flat_map(|entry| entry.addresses.clone()).collect());
+  if unique.is_empty() {
+    result.error = Some(ErrorCode::LookupFailed);
+    return result;
+  }
+  let pings: Vec<_> = unique
+    .into_iter()
+    .map(|address| {
+      tauri::async_runtime::spawn(async move {
+        let average = ping_average(&address, GEODNS_PING_SAMPLES).await;
+        EndpointLatency {
+          address,
+          latency_ms: average.latency_ms,
+          received: average.received,
+        }
+      })
+    })
+    .collect();
+  for ping in pings {
+    if let Ok(endpoint) = ping.await {
+      result.endpoints.push(endpoint);
+    }
+  }
+
+  for entry in result.resolvers.iter_mut() {
+    let best = result
+      .endpoints
+      .iter()
+      .filter(|endpoint| entry.addresses.contains(&endpoint.address))
+      .filter_map(|endpoint| endpoint.latency_ms.map(|latency| (endpoint.address.clone(), latency)))
+      .min_by(|left, right| left.1.total_cmp(&right.1));
+    if let Some((address, latency)) = best {
+      entry.best_address = Some(address);
+      entry.best_latency_ms = Some(latency);
+    }
+  }
+  result.recommended_server = result
+    .resolvers
+    .iter()
+    .filter_map(|entry| entry.best_latency_ms.map(|latency| (entry.server.clone(), latency)))
+    .min_by(|left, right| left.1.total_cmp(&right.1))
+    .map(|(server, _)| server);
+  result
+}
+
+async fn measure_dns_server_latency(server: String) -> DnsServerLatency {
+  let mut entry = DnsServerLatency {
+    server: server.clone(),
+    method: "icmp".to_string(),
+    latency_ms: None,
+    sent: DNS_SERVER_PING_SAMPLES,
+    received: 0,
+    error: None,
+    error_message: None,
+  };
+  let Some(addr) = parse_dns_server_socket(&server) else {
+    entry.error = Some(ErrorCode::InvalidServer);
+    return entry;
+  };
+  let average = ping_average(&addr.ip().to_string(), DNS_SERVER_PING_SAMPLES).await;
+  if average.latency_ms.is_some() {
+    entry.latency_ms = average.latency_ms;
+    entry.received = average.received;
+    return entry;
+  }
+  // Some resolvers drop ICMP; a TCP handshake on the DNS port still measures pure RTT.
+  entry.method = "tcp".to_string();
+  let mut samples = Vec::new();
+  let mut last_error = (average.error, average.error_message);
+  for _ in 0..DNS_SERVER_PING_SAMPLES {
+    match measure_tcp_connect(addr, Duration::from_millis(DNS_TIMEOUT_MS)).await {
+      Ok(rtt) => samples.push(rtt),
+      Err((code, message)) => last_error = (Some(code), message),
+    }
+  }
+  entry.received = samples.len();
+  if samples.is_empty() {
+    entry.error = last_error.0;
+    entry.error_message = last_error.1;
+  } else {
+    entry.latency_ms = Some(round2(samples.iter().sum::<f64>() / samples.len() as f64));
+  }
+  entry
+}
+
+#[tauri::command]
+async fn ping_dns_servers(custom_servers: Option<Vec<String>>) -> Vec<DnsServerLatency> {
+  let mut servers: Vec<String> = DNS_SERVERS.iter().map(|server| server.to_string()).collect();
+  for server in custom_servers.unwrap_or_default() {
+    let trimmed = server.trim().to_string();
+    if !trimmed.is_empty() && !servers.contains(&trimmed) {
+      servers.push(trimmed);
+    }
+  }
+  let tasks: Vec<_> = servers
+    .into_iter()
+    .map(|server| tauri::async_runtime::spawn(measure_dns_server_latency(server)))
+    .collect();
+  let mut results = Vec::with_capacity(tasks.len());
+  for task in tasks {
+    if let Ok(entry) = task.await {
+      results.push(entry);
+    }
+  }
+  results
+}
+
+struct RawDnsResponse {
+  message: Message,
+  size: usize,
+  elapsed: Duration,
+  over_tcp: bool,
+}
+
+fn next_dns_query_id() -> u16 {
+  static NEXT: OnceLock<AtomicU16> = OnceLock::new();
+  let counter = NEXT.get_or_init(|| AtomicU16::new((now_millis() & 0xffff) as u16));
+  counter.fetch_add(1, Ordering::Relaxed)
+}
+
+fn parse_record_type(value: Option<&str>) -> Result<RecordType, ErrorDetail> {
+  let raw = value.map(|item| item.trim()).filter(|item| !item.is_empty()).unwrap_or("A");
+  raw
+    .to_uppercase()
+    .parse::<RecordType>()
+    .map_err(|error| (ErrorCode::InvalidInput, Some(error.to_string())))
+}
+
+fn build_dns_query(domain: &str, record_type: RecordType, recursion_desired: bool) -> Result<Message, ErrorDetail> {
+  let name = Name::from_ascii(domain).map_err(|error| (ErrorCode::InvalidDomain, Some(error.to_string())))?;
+  let mut message = Message::new();
+  message
+    .set_id(next_dns_query_id())
+    .set_message_type(MessageType::Query)
+    .set_op_code(OpCode::Query)
+    .set_recursion_desired(recursion_desired)
+    .add_query(Query::query(name, record_type));
+  let mut edns = Edns::new();
+  edns.set_max_payload(1232);
+  message.set_edns(edns);
+  Ok(message)
+}
+
+async fn exchange_dns_udp(server: SocketAddr, request: &[u8], id: u16) -> std::io::Result<Vec<u8>> {
+  let bind_addr: SocketAddr = if server.is_ipv6() {
+    "[::]:0".parse().unwrap()
+  } else {
+    "0.0.0.0:0".parse().unwrap()
+  };
+  let socket = tokio::net::UdpSocket::bind(bind_addr).await?;
+  socket.connect(server).await?;
+  socket.send(request).await?;
+  let mut buffer = vec![0u8; 65535];
+  loop {
+    let len = socket.recv(&mut buffer).await?;
+    // Ignore stray datagrams that don't belong to this query.
+    if len >= 2 && u16::from_be_bytes([buffer[0], buffer[1]]) == id {
+      buffer.truncate(len);
+      return Ok(buffer);
+    }
+  }
+}
+
+async fn exchange_dns_tcp(server: SocketAddr, request: &[u8]) -> std::io::Result<Vec<u8>> {
+  let mut stream = tokio::net::TcpStream::connect(server).await?;
+  let mut framed = (request.len() as u16).to_be_bytes().to_vec();
+  framed.extend_from_slice(request);
+  stream.write_all(&framed).await?;
+  let mut len_bytes = [0u8; 2];
+  stream.read_exact(&mut len_bytes).await?;
+  let mut buffer = vec![0u8; u16::from_be_bytes(len_bytes) as usize];
+  stream.read_exact(&mut buffer).await?;
+  Ok(buffer)
+}
+
+async fn send_dns_message(server: SocketAddr, message: &Message, use_tcp: bool) -> Result<RawDnsResponse, ErrorDetail> {
+  let request = message
+    .to_vec()
+    .map_err(|error| (ErrorCode::InvalidInput, Some(error.to_string())))?;
+  let start = Instant::now();
+  let exchange = async {
+    if use_tcp {
+      exchange_dns_tcp(server, &request).await
+    } else {
+      exchange_dns_udp(server, &request, message.id()).await
+    }
+  };
+  let bytes = match timeout(Duration::from_millis(DNS_TIMEOUT_MS), exchange).await {
+    Ok(Ok(bytes)) => bytes,
+    Ok(Err(error)) => return Err((ErrorCode::LookupFailed, Some(error.to_string()))),
+    Err(_) => return Err((ErrorCode::Timeout, None)),
+  };
+  let elapsed = start.elapsed();
+  let response = Message::from_vec(&bytes).map_err(|error| (ErrorCode::InvalidResponse, Some(error.to_string())))?;
+  Ok(RawDnsResponse {
+    message: response,
+    size: bytes.len(),
+    elapsed,
+    over_tcp: use_tcp,
+  })
+}
+
+async fn send_dns_query(server: SocketAddr, message: &Message) -> Result<RawDnsResponse, ErrorDetail> {
+  let response = send_dns_message(server, message, false).await?;
+  if response.message.truncated() {
+    return send_dns_message(server, message, true).await;
+  }
+  Ok(response)
+}
+
+fn response_code_name(message: &Message) -> String {
+  format!("{:?}", message.response_code()).to_uppercase()
+}
+
+fn format_dns_records(records: &[Record]) -> Vec<String> {
+  records.iter().map(|record| record.to_string()).collect()
+}
+
+fn is_dns_referral(message: &Message) -> bool {
+  message.answers().is_empty()
+    && !message.authoritative()
+    && message
+      .name_servers()
+      .iter()
+      .any(|record| matches!(record.data(), Some(RData::NS(_))))
+}
+
+#[tauri::command]
+async fn dns_response_diagnostics(
+  server: String,
+  domain: Option<String>,
+  record_type: Option<String>,
+) -> DnsResponseDiagnostics {
+  let sanitized = sanitize_domain(domain.as_deref().unwrap_or(DNS_LARGE_RESPONSE_DOMAIN));
+  let mut result = DnsResponseDiagnostics {
+    server: server.trim().to_string(),
+    domain: sanitized.clone(),
+    record_type: record_type.clone().unwrap_or_else(|| "DNSKEY".to_string()).to_uppercase(),
+    udp_responded: false,
+    udp_size: None,
+    udp_ms: None,
+    truncated: false,
+    server_edns_payload: None,
+    tcp_supported: false,
+    tcp_size: None,
+    tcp_ms: None,
+    error: None,
+    error_message: None,
+  };
+  if sanitized.is_empty() {
+    result.error = Some(ErrorCode::InvalidDomain);
+    return result;
+  }
+  let Some(server_addr) = parse_dns_server_socket(&result.server) else {
+    result.error = Some(ErrorCode::InvalidServer);
+    return result;
+  };
+  let record_type = match parse_record_type(Some(&result.record_type)) {
+    Ok(record_type) => record_type,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  let mut query = match build_dns_query(&sanitized, record_type, true) {
+    Ok(query) => query,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  // Asking for DNSSEC records (DO bit) makes the answer large enough to exercise truncation.
+  if let Some(edns) = query.extensions_mut() {
+    edns.set_dnssec_ok(true);
+  }
+
+  let (udp, tcp) = tokio::join!(
+    send_dns_message(server_addr, &query, false),
+    send_dns_message(server_addr, &query, true)
+  );
+  match udp {
+    Ok(response) => {
+      result.udp_responded = true;
+      result.udp_size = Some(response.size);
+      result.udp_ms = Some(round2(response.elapsed.as_secs_f64() * 1000.0));
+      result.truncated = response.message.truncated();
+      result.server_edns_payload = response.message.extensions().as_ref().map(|edns| edns.max_payload());
+    }
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+    }
+  }
+  if let Ok(response) = tcp {
+    result.tcp_supported = true;
+    result.tcp_size = Some(response.size);
+    result.tcp_ms = Some(round2(response.elapsed.as_secs_f64() * 1000.0));
+  }
+  result
+}
+
+#[tauri::command]
+async fn test_udp_fragmentation(server: String, domain: Option<String>) -> UdpFragmentationResult {
+  let sanitized = sanitize_domain(domain.as_deref().unwrap_or(DNS_LARGE_RESPONSE_DOMAIN));
+  let mut result = UdpFragmentationResult {
+    server: server.trim().to_string(),
+    domain: sanitized.clone(),
+    small_udp_ok: false,
+    large_udp_size: None,
+    large_udp_truncated: false,
+    tcp_size: None,
+    verdict: "unknown".to_string(),
+    error: None,
+    error_message: None,
+  };
+  if sanitized.is_empty() {
+    result.error = Some(ErrorCode::InvalidDomain);
+    return result;
+  }
+  let Some(server_addr) = parse_dns_server_socket(&result.server) else {
+    result.error = Some(ErrorCode::InvalidServer);
+    return result;
+  };
+  let (small, mut large) = match (
+    build_dns_query(&sanitized, RecordType::A, true),
+    build_dns_query(&sanitized, RecordType::DNSKEY, true),
+  ) {
+    (Ok(small), Ok(large)) => (small, large),
+    (Err((code, message)), _) | (_, Err((code, message))) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  // A 4096-byte EDNS buffer plus DNSSEC records invites an answer bigger than one packet.
+  if let Some(edns) = large.extensions_mut() {
+    edns.set_max_payload(DNS_FRAGMENTATION_EDNS_PAYLOAD);
+    edns.set_dnssec_ok(true);
+  }
+
+  let (small_udp, large_udp, large_tcp) = tokio::join!(
+    send_dns_message(server_addr, &small, false),
+    send_dns_message(server_addr, &large, false),
+    send_dns_message(server_addr, &large, true)
+  );
+  result.small_udp_ok = small_udp.is_ok();
+  if let Ok(response) = &large_udp {
+    result.large_udp_size = Some(response.size);
+    result.large_udp_truncated = response.message.truncated();
+  }
+  if let Ok(response) = &large_tcp {
+    result.tcp_size = Some(response.size);
+  }
+
+  let tcp_size = result.tcp_size.unwrap_or(0);
+  result.verdict = if !result.small_udp_ok {
+    if let Err((code, message)) = small_udp {
+      result.error = Some(code);
+      result.error_message = message;
+    }
+    "udp-blocked"
+  } else if let Some(size) = result.large_udp_size {
+    if result.large_udp_truncated {
+      // The server capped the answer itself, so nothing fragmented was sent.
+      "server-truncated"
+    } else if size > UDP_UNFRAGMENTED_MAX_BYTES {
+      "fragments-pass"
+    } else {
+      "response-too-small"
+    }
+  } else if tcp_size > UDP_UNFRAGMENTED_MAX_BYTES {
+    "fragments-dropped"
+  } else if result.tcp_size.is_some() {
+    "large-udp-lost"
+  } else {
+    "unknown"
+  }
+  .to_string();
+  result
+}
+
+#[tauri::command]
+async fn dns_cache_benchmark(server: String, domain: Option<String>) -> DnsCacheBenchmarkResult {
+  let sanitized = sanitize_domain(domain.as_deref().unwrap_or(DNS_CACHE_BENCHMARK_DOMAIN));
+  let mut result = DnsCacheBenchmarkResult {
+    server: server.trim().to_string(),
+    domain: sanitized.clone(),
+    cold_ms: None,
+    warm_ms: None,
+    speedup_ratio: None,
+    ttl: None,
+    error: None,
+    error_message: None,
+  };
+  if sanitized.is_empty() {
+    result.error = Some(ErrorCode::InvalidDomain);
+    return result;
+  }
+  let Some(server_addr) = parse_dns_server_socket(&result.server) else {
+    result.error = Some(ErrorCode::InvalidServer);
+    return result;
+  };
+
+  // Raw queries on purpose: the resolver crate keeps its own cache and would hide the server's.
+  let mut timings = Vec::new();
+  for _ in 0..2 {
+    let response = match build_dns_query(&sanitized, RecordType::A, true) {
+      Ok(query) => send_dns_query(server_addr, &query).await,
+      Err(detail) => Err(detail),
+    };
+    match response {
+      Ok(response) => {
+        if result.ttl.is_none() {
+          result.ttl = response.message.answers().iter().map(|record| record.ttl()).min();
+        }
+        timings.push(round2(response.elapsed.as_secs_f64() * 1000.0));
+      }
+      Err((code, message)) => {
+        result.error = Some(code);
+        result.error_message = message;
+        return result;
+      }
+    }
+  }
+  result.cold_ms = Some(timings[0]);
+  result.warm_ms = Some(timings[1]);
+  if timings[1] > 0.0 {
+    result.speedup_ratio = Some(round2(timings[0] / timings[1]));
+  }
+  result
+}
+
+async fn evaluate_dns_server(server: String, socket_addr: SocketAddr, is_current: bool) -> DnsRecommendation {
+  let mut timings = Vec::new();
+  let mut dnssec = false;
+  for _ in 0..DNS_RECOMMEND_ATTEMPTS {
+    let Ok(mut query) = build_dns_query(DNS_RECOMMEND_DOMAIN, RecordType::A, true) else {
+      break;
+    };
+    // Setting AD on the query asks a validating resolver to report its verdict (RFC 6840).
+    query.set_authentic_data(true);
+    if let Ok(response) = send_dns_query(socket_addr, &query).await {
+      if response.message.response_code() == trust_dns_proto::op::ResponseCode::NoError {
+        timings.push(response.elapsed.as_secs_f64() * 1000.0);
+        dnssec |= response.message.authentic_data();
+      }
+    }
+  }
+  let success_rate = round2(timings.len() as f64 / DNS_RECOMMEND_ATTEMPTS as f64);
+  let average_ms = if timings.is_empty() {
+    None
+  } else {
+    Some(round2(timings.iter().sum::<f64>() / timings.len() as f64))
+  };
+  let score = average_ms.map(|average| {
+    round2(
+      average
+        + (1.0 - success_rate) * DNS_TIMEOUT_MS as f64
+        + if dnssec { 0.0 } else { DNS_RECOMMEND_NO_DNSSEC_PENALTY_MS },
+    )
+  });
+  DnsRecommendation {
+    server,
+    rank: 0,
+    average_ms,
+    success_rate,
+    dnssec,
+    is_current,
+    score,
+    rationale: String::new(),
+  }
+}
+
+fn sorted_percentile(sorted: &[f64], percentile: f64) -> Option<f64> {
+  if sorted.is_empty() {
+    return None;
+  }
+  let index = ((sorted.len() - 1) as f64 * percentile).round() as usize;
+  Some(round2(sorted[index.min(sorted.len() - 1)]))
+}
+
+#[tauri::command]
+async fn dns_reliability_test(server: String, domain: String, attempts: Option<usize>) -> DnsReliabilityResult {
+  let sanitized = sanitize_domain(&domain);
+  let attempts = attempts
+    .unwrap_or(DNS_RELIABILITY_DEFAULT_ATTEMPTS)
+    .clamp(1, DNS_RELIABILITY_MAX_ATTEMPTS);
+  let mut result = DnsReliabilityResult {
+    server: server.trim().to_string(),
+    domain: sanitized.clone(),
+    attempts,
+    successes: 0,
+    success_rate: 0.0,
+    min_ms: None,
+    average_ms: None,
+    p50_ms: None,
+    p95_ms: None,
+    worst_ms: None,
+    failures: DnsFailureBreakdown::default(),
+    error: None,
+    error_message: None,
+  };
+  if sanitized.is_empty() {
+    result.error = Some(ErrorCode::InvalidDomain);
+    return result;
+  }
+  let Some(server_addr) = parse_dns_server_socket(&result.server) else {
+    result.error = Some(ErrorCode::InvalidServer);
+    return result;
+  };
+
+  let mut timings = Vec::with_capacity(attempts);
+  for attempt in 0..attempts {
+    if attempt > 0 {
+      tokio::time::sleep(Duration::from_millis(DNS_RELIABILITY_INTERVAL_MS)).await;
+    }
+    // A fresh message per attempt so every query carries its own id.
+    let query = match build_dns_query(&sanitized, RecordType::A, true) {
+      Ok(query) => query,
+      Err((code, message)) => {
+        result.error = Some(code);
+        result.error_message = message;
+        return result;
+      }
+    };
+    match send_dns_query(server_addr, &query).await {
+      Ok(response) => match response.message.response_code() {
+        // NXDOMAIN is still a real answer from a working resolver.
+        trust_dns_proto::op::ResponseCode::NoError | trust_dns_proto::op::ResponseCode::NXDomain => {
+          timings.push(response.elapsed.as_secs_f64() * 1000.0);
+        }
+        trust_dns_proto::op::ResponseCode::ServFail => result.failures.servfail += 1,
+        trust_dns_proto::op::ResponseCode::Refused => result.failures.refused += 1,
+        _ => result.failures.other += 1,
+      },
+      Err((ErrorCode::Timeout, _)) => result.failures.timeout += 1,
+      Err(_) => result.failures.network += 1,
+    }
+  }
+
+  result.successes = timings.len();
+  result.success_rate = round2(timings.len() as f64 / attempts as f64);
+  if timings.is_empty() {
+    result.error = Some(ErrorCode::LookupFailed);
+    return result;
+  }
+  timings.sort_by(|a, b| a.total_cmp(b));
+  result.min_ms = timings.first().map(|value| round2(*value));
+  result.worst_ms = timings.last().map(|value| round2(*value));
+  result.average_ms = Some(round2(timings.iter().sum::<f64>() / timings.len() as f64));
+  result.p50_ms = sorted_percentile(&timings, 0.5);
+  result.p95_ms = sorted_percentile(&timings, 0.95);
+  result
+}
+
+fn dns_recommendation_rationale(item: &DnsRecommendation, fastest_ms: Option<f64>) -> String {
+  let Some(average) = item.average_ms else {
+    return "Unreachable from this network".to_string();
+  };
+  let mut parts = Vec::new();
+  if fastest_ms == Some(average) {
+    parts.push("fastest".to_string());
+  } else if let Some(fastest) = fastest_ms {
+    parts.push(format!("{:.0} ms slower than the fastest", average - fastest));
+  }
+  if item.success_rate >= 1.0 {
+    parts.push("reliable".to_string());
+  } else {
+    parts.push(format!("{:.0}% of queries failed", (1.0 - item.success_rate) * 100.0));
+  }
+  parts.push(if item.dnssec { "validating (DNSSEC)" } else { "no DNSSEC" }.to_string());
+  if item.is_current {
+    parts.push("currently in use".to_string());
+  }
+  let mut rationale = parts.join(", ");
+  if let Some(first) = rationale.get_mut(..1) {
+    first.make_ascii_uppercase();
+  }
+  rationale
+}
+
+#[tauri::command]
+async fn recommend_dns(adapter_name: Option<String>) -> DnsRecommendationResult {
+  let adapter_name = adapter_name.map(|value| value.trim().to_string()).filter(|value| !value.is_empty());
+  let current: Vec<String> = match adapter_name.clone() {
+    Some(name) => tauri::async_runtime::spawn_blocking(move || {
+      list_dns_adapters(None, None)
+        .into_iter()
+        .find(|adapter| adapter.name == name)
+        .map(|adapter| adapter.dns)
+        .unwrap_or_default()
+    })
+    .await
+    .unwrap_or_default(),
+    None => vec![],
+  };
+  let mut servers: Vec<String> = DNS_SERVERS.iter().map(|item| item.to_string()).collect();
+  for server in &current {
+    if !servers.contains(server) {
+      servers.push(server.clone());
+    }
+  }
+
+  let tasks: Vec<_> = servers
+    .into_iter()
+    .filter_map(|server| {
+      let socket_addr = parse_dns_server_socket(&server)?;
+      let is_current = current.contains(&server);
+      Some(tauri::async_runtime::spawn(evaluate_dns_server(server, socket_addr, is_current)))
+    })
+    .collect();
+  let mut ranked = Vec::new();
+  for task in tasks {
+    if let Ok(item) = task.await {
+      ranked.push(item);
+    }
+  }
+  ranked.sort_by(|left, right| {
+    left
+      .score
+      .unwrap_or(f64::MAX)
+      .total_cmp(&right.score.unwrap_or(f64::MAX))
+  });
+  let fastest_ms = ranked
+    .iter()
+    .filter_map(|item| item.average_ms)
+    .min_by(|left, right| left.total_cmp(right));
+  for (index, item) in ranked.iter_mut().enumerate() {
+    item.rank = index + 1;
+    item.rationale = dns_recommendation_rationale(item, fastest_ms);
+  }
+  let recommended = ranked
+    .first()
+    .filter(|item| item.average_ms.is_some())
+    .map(|item| item.server.clone());
+  DnsRecommendationResult {
+    adapter: adapter_name,
+    error: if recommended.is_none() { Some(ErrorCode::LookupFailed) } else { None },
+    error_message: None,
+    recommended,
+    servers: ranked,
+  }
+}
+
+fn normalize_dns_name(name: &Name) -> String {
+  name.to_ascii().trim_end_matches('.').to_lowercase()
+}
+
+#[tauri::command]
+async fn trace_cname_chain(host: String, server: Option<String>) -> CnameChainResult {
+  let sanitized = sanitize_domain(&host).trim_end_matches('.').to_lowercase();
+  let server = server
+    .map(|value| value.trim().to_string())
+    .filter(|value| !value.is_empty())
+    .unwrap_or_else(|| DNS_SERVERS[0].to_string());
+  let mut result = CnameChainResult {
+    host: sanitized.clone(),
+    server: server.clone(),
+    chain: vec![],
+    addresses: vec![],
+    loop_detected: false,
+    error: None,
+    error_message: None,
+  };
+  if sanitized.is_empty() {
+    result.error = Some(ErrorCode::InvalidDomain);
+    return result;
+  }
+  let Some(server_addr) = parse_dns_server_socket(&server) else {
+    result.error = Some(ErrorCode::InvalidServer);
+    return result;
+  };
+
+  let mut current = sanitized;
+  let mut visited = vec![current.clone()];
+  while result.chain.len() < CNAME_CHAIN_MAX_HOPS {
+    let query = match build_dns_query(&current, RecordType::A, true) {
+      Ok(query) => query,
+      Err((code, message)) => {
+        result.error = Some(code);
+        result.error_message = message;
+        return result;
+      }
+    };
+    let response = match send_dns_query(server_addr, &query).await {
+      Ok(response) => response.message,
+      Err((code, message)) => {
+        result.error = Some(code);
+        result.error_message = message;
+        return result;
+      }
+    };
+    if response.response_code() != trust_dns_proto::op::ResponseCode::NoError {
+      result.error = Some(ErrorCode::LookupFailed);
+      result.error_message = Some(response_code_name(&response));
+      return result;
+    }
+
+    // Resolvers usually return the whole chain in one answer; walk it before asking again.
+    let start_name = current.clone();
+    while let Some((target, ttl)) = response.answers().iter().find_map(|record| match record.data() {
+      Some(RData::CNAME(cname)) if normalize_dns_name(record.name()) == current => {
+        Some((normalize_dns_name(&cname.0), record.ttl()))
+      }
+      _ => None,
+    }) {
+      result.chain.push(CnameHop {
+        name: current.clone(),
+        target: target.clone(),
+        record_type: "CNAME".to_string(),
+        ttl,
+      });
+      if visited.contains(&target) {
+        result.loop_detected = true;
+        return result;
+      }
+      visited.push(target.clone());
+      current = target;
+    }
+
+    for record in response.answers() {
+      if normalize_dns_name(record.name()) != current {
+        continue;
+      }
+      if let Some(RData::A(address)) = record.data() {
+        result.chain.push(CnameHop {
+          name: current.clone(),
+          target: address.to_string(),
+          record_type: "A".to_string(),
+          ttl: record.ttl(),
+        });
+        result.addresses.push(address.to_string());
+      }
+    }
+    if !result.addresses.is_empty() || current == start_name {
+      return result;
+    }
+  }
+  result
+}
+
+// Accepts "203.0.113.0/24" or a bare address, which gets the customary /24 or /56.
+fn parse_client_subnet(value: &str) -> Result<(std::net::IpAddr, u8), ErrorDetail> {
+  let invalid = || (ErrorCode::InvalidInput, Some(format!("Invalid client subnet: {}", value)));
+  let (address, prefix) = match value.trim().split_once('/') {
+    Some((address, prefix)) => (address, Some(prefix.parse::<u8>().map_err(|_| invalid())?)),
+    None => (value.trim(), None),
+  };
+  let address = address.parse::<std::net::IpAddr>().map_err(|_| invalid())?;
+  let (default_prefix, max_prefix) = if address.is_ipv4() { (24, 32) } else { (56, 128) };
+  let prefix = prefix.unwrap_or(default_prefix);
+  if prefix > max_prefix {
+    return Err(invalid());
+  }
+  Ok((address, prefix))
+}
+
+// Repeats the query with an ECS option and compares it to the plain answer (RFC 7871).
+async fn probe_client_subnet(
+  server: SocketAddr,
+  baseline: &Message,
+  baseline_reply: &Message,
+  subnet: (std::net::IpAddr, u8),
+) -> ClientSubnetProbe {
+  let mut probe = ClientSubnetProbe {
+    subnet: format!("{}/{}", subnet.0, subnet.1),
+    addresses_without: answer_addresses(baseline_reply),
+    addresses_with: vec![],
+    changed: false,
+    scope_prefix: None,
+    honored: false,
+    error: None,
+    error_message: None,
+  };
+  let mut query = baseline.clone();
+  query.set_id(next_dns_query_id());
+  let mut edns = baseline.extensions().clone().unwrap_or_default();
+  edns
+    .options_mut()
+    .insert(EdnsOption::Subnet(ClientSubnet::new(subnet.0, subnet.1, 0)));
+  query.set_edns(edns);
+  let response = match send_dns_query(server, &query).await {
+    Ok(response) => response,
+    Err((code, message)) => {
+      probe.error = Some(code);
+      probe.error_message = message;
+      return probe;
+    }
+  };
+  probe.addresses_with = answer_addresses(&response.message);
+  probe.changed = probe.addresses_with != probe.addresses_without;
+  // Option data is family (2 bytes), source prefix, scope prefix, address.
+  probe.scope_prefix = response
+    .message
+    .extensions()
+    .as_ref()
+    .and_then(|edns| edns.options().get(EdnsCode::Subnet))
+    .and_then(|option| Vec::<u8>::try_from(option).ok())
+    .and_then(|data| data.get(3).copied());
+  // A non-zero scope means the answer was tailored to the subnet.
+  probe.honored = probe.scope_prefix.is_some_and(|scope| scope > 0) || probe.changed;
+  probe
+}
+
+async fn probe_qname_minimization(server: SocketAddr) -> Option<bool> {
+  let query = build_dns_query(QNAME_MIN_TEST_DOMAIN, RecordType::TXT, true).ok()?;
+  let response = send_dns_query(server, &query).await.ok()?;
+  // The test zone answers "HOORAY - QNAME minimisation is enabled..." or "NO - ...".
+  let text: String = response
+    .message
+    .answers()
+    .iter()
+    .filter_map(|record| match record.data() {
+      Some(RData::TXT(txt)) => Some(txt.to_string()),
+      _ => None,
+    })
+    .collect();
+  if text.contains("HOORAY") {
+    Some(true)
+  } else if text.trim_start().starts_with("NO") {
+    Some(false)
+  } else {
+    None
+  }
+}
+
+#[tauri::command]
+async fn dns_query(
+  server: String,
+  domain: String,
+  record_type: Option<String>,
+  recursion_desired: Option<bool>,
+  check_qname_minimization: Option<bool>,
+  client_subnet: Option<String>,
+) -> DnsQueryResult {
+  let recursion_desired = recursion_desired.unwrap_or(true);
+  let sanitized = sanitize_domain(&domain);
+  let mut result = DnsQueryResult {
+    server: server.trim().to_string(),
+    domain: sanitized.clone(),
+    record_type: record_type.clone().unwrap_or_else(|| "A".to_string()).to_uppercase(),
+    protocol: "udp".to_string(),
+    recursion_desired,
+    recursion_available: false,
+    authoritative: false,
+    truncated: false,
+    response_code: String::new(),
+    answers: vec![],
+    authority: vec![],
+    referral: false,
+    response_time_ms: 0,
+    response_size: 0,
+    qname_minimization: None,
+    client_subnet: None,
+    error: None,
+    error_message: None,
+  };
+  if sanitized.is_empty() {
+    result.error = Some(ErrorCode::InvalidDomain);
+    return result;
+  }
+  let Some(socket_addr) = parse_dns_server_socket(&server) else {
+    result.error = Some(ErrorCode::InvalidServer);
+    return result;
+  };
+  let subnet = match client_subnet.as_deref().filter(|value| !value.trim().is_empty()).map(parse_client_subnet) {
+    Some(Ok(subnet)) => Some(subnet),
+    Some(Err((code, message))) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+    None => None,
+  };
+  let query = parse_record_type(record_type.as_deref())
+    .and_then(|parsed| build_dns_query(&sanitized, parsed, recursion_desired));
+  let message = match query {
+    Ok(message) => message,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+
+  match send_dns_query(socket_addr, &message).await {
+    Ok(response) => {
+      let reply = &response.message;
+      result.protocol = if response.over_tcp { "tcp" } else { "udp" }.to_string();
+      result.recursion_available = reply.recursion_available();
+      result.authoritative = reply.authoritative();
+      result.truncated = reply.truncated();
+      result.response_code = response_code_name(reply);
+      result.answers = format_dns_records(reply.answers());
+      result.authority = format_dns_records(reply.name_servers());
+      result.referral = is_dns_referral(reply);
+      result.response_time_ms = response.elapsed.as_millis();
+      result.response_size = response.size;
+      if check_qname_minimization.unwrap_or(false) {
+        result.qname_minimization = probe_qname_minimization(socket_addr).await;
+      }
+      if let Some(subnet) = subnet {
+        result.client_subnet = Some(probe_client_subnet(socket_addr, &message, reply, subnet).await);
+      }
+    }
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+    }
+  }
+  result
+}
+
+#[tauri::command]
+fn list_dns_adapters(force_refresh: Option<bool>, address_family: Option<String>) -> Vec<DnsAdapter> {
+  #[cfg(target_os = "windows")]
+  {
+    let Ok(family) = AddressFamily::parse(address_family.as_deref()) else {
+      return vec![];
+    };
+    list_family_dns_adapters(force_refresh.unwrap_or(false), family)
+  }
+
+  #[cfg(any(target_os = "linux", target_os = "macos"))]
+  {
+    let _ = force_refresh;
+    let Ok(family) = AddressFamily::parse(address_family.as_deref()) else {
+      return vec![];
+    };
+    family_dns_adapters(family)
+  }
+
+  #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+  {
+    let _ = (force_refresh, address_family);
+    vec![]
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn family_adapter_dns(adapter: &str, family: AddressFamily) -> Vec<String> {
+  list_family_dns_adapters(true, family)
+    .into_iter()
+    .find(|item| item.name == adapter)
+    .map(|item| item.dns)
+    .unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn list_family_dns_adapters(force_refresh: bool, family: AddressFamily) -> Vec<DnsAdapter> {
+  if !force_refresh {
+    if let Ok(guard) = dns_adapter_cache().lock() {
+      if let Some((cached_at, adapters)) = guard.get(&family) {
+        if now_millis().saturating_sub(*cached_at) <= DNS_ADAPTER_CACHE_TTL_MS {
+          return adapters.clone();
+        }
+      }
+    }
+  }
+
+  let query = "Get-DnsClientServerAddress -AddressFamily $family | ForEach-Object { $ip = Get-NetIPInterface -InterfaceIndex $_.InterfaceIndex -AddressFamily $family -ErrorAction SilentlyContinue; $client = Get-DnsClient -InterfaceIndex $_.InterfaceIndex -ErrorAction SilentlyContinue; $guid = (Get-NetAdapter -InterfaceIndex $_.InterfaceIndex -ErrorAction SilentlyContinue).InterfaceGuid; $static = if ($guid) { (Get-ItemProperty \"HKLM:\\SYSTEM\\CurrentControlSet\\Services\\$service\\Parameters\\Interfaces\\$guid\" -Name NameServer -ErrorAction SilentlyContinue).NameServer }; [pscustomobject]@{ InterfaceAlias = $_.InterfaceAlias; ServerAddresses = $_.ServerAddresses; Dhcp = [string]$ip.Dhcp; StaticDns = [bool]$static; ConnectionSpecificSuffix = $client.ConnectionSpecificSuffix } } | ConvertTo-Json -Depth 4 -Compress";
+  let command = format!(
+    "$family = '{}'; $service = '{}'; {}",
+    family.label(),
+    family.tcpip_service(),
+    query
+  );
+  let output = match run_powershell(&command) {
+    Ok(stdout) => stdout,
+    Err(_) => return vec![],
+  };
+  let adapters = parse_dns_adapters_from_output(&output);
+  if let Ok(mut guard) = dns_adapter_cache().lock() {
+    guard.insert(family, (now_millis(), adapters.clone()));
+  }
+  adapters
+}
+
+#[tauri::command]
+fn refresh_dns_adapters() -> Vec<DnsAdapter> {
+  #[cfg(target_os = "windows")]
+  clear_dns_adapter_cache();
+  list_dns_adapters(Some(true), None)
+}
+
+// DhcpNameServer is kept per interface even when static servers override it.
+#[cfg(target_os = "windows")]
+fn dhcp_dns_servers() -> Vec<(String, Vec<String>)> {
+  let command = "Get-NetAdapter | ForEach-Object { $dhcp = (Get-ItemProperty \"HKLM:\\SYSTEM\\CurrentControlSet\\Services\\Tcpip\\Parameters\\Interfaces\\$($_.InterfaceGuid)\" -Name DhcpNameServer -ErrorAction SilentlyContinue).DhcpNameServer; if ($dhcp) { \"$($_.Name)`t$dhcp\" } }";
+  let Ok(output) = run_powershell(command) else {
+    return vec![];
+  };
+  output
+    .lines()
+    .filter_map(|line| {
+      let (adapter, servers) = line.split_once('\t')?;
+      let servers: Vec<String> = servers
+        .split([',', ' '])
+        .map(|server| server.trim().to_string())
+        .filter(|server| !server.is_empty())
+        .collect();
+      Some((adapter.trim().to_string(), servers))
+    })
+    .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn dhcp_dns_servers() -> Vec<(String, Vec<String>)> {
+  vec![]
+}
+
+// The resolvers the OS actually sends queries to, in order of preference.
+fn effective_dns_servers() -> Vec<SocketAddr> {
+  let mut servers = Vec::new();
+  if let Ok((config, _)) = trust_dns_resolver::system_conf::read_system_conf() {
+    for name_server in config.name_servers() {
+      if !servers.contains(&name_server.socket_addr) {
+        servers.push(name_server.socket_addr);
+      }
+    }
+  }
+  servers
+}
+
+fn collect_system_resolvers() -> Vec<(String, Vec<String>)> {
+  let mut found: Vec<(String, Vec<String>)> = Vec::new();
+  let mut add = |server: String, source: String| match found.iter_mut().find(|(known, _)| *known == server) {
+    Some((_, sources)) => {
+      if !sources.contains(&source) {
+        sources.push(source);
+      }
+    }
+    None => found.push((server, vec![source])),
+  };
+  for addr in effective_dns_servers() {
+    let server = if addr.port() == 53 { addr.ip().to_string() } else { addr.to_string() };
+    add(server, "system".to_string());
+  }
+  for adapter in list_dns_adapters(None, None) {
+    for server in adapter.dns {
+      add(server, format!("adapter:{}", adapter.name));
+    }
+  }
+  for (adapter, servers) in dhcp_dns_servers() {
+    for server in servers {
+      add(server, format!("dhcp:{}", adapter));
+    }
+  }
+  found
+}
+
+#[tauri::command]
+async fn test_all_system_resolvers(domain: String) -> SystemResolversResponse {
+  let sanitized = sanitize_domain(&domain);
+  let mut response = SystemResolversResponse {
+    domain: sanitized.clone(),
+    results: vec![],
+    error: None,
+    error_message: None,
+  };
+  if sanitized.is_empty() {
+    response.error = Some(ErrorCode::InvalidDomain);
+    return response;
+  }
+  let resolvers = tauri::async_runtime::spawn_blocking(collect_system_resolvers)
+    .await
+    .unwrap_or_default();
+  if resolvers.is_empty() {
+    response.error = Some(ErrorCode::LookupFailed);
+    response.error_message = Some("No resolvers found in the system configuration".to_string());
+    return response;
+  }
+  let opts = build_resolver_opts(&DnsTestOptions::default());
+  let tasks: Vec<_> = resolvers
+    .into_iter()
+    .map(|(server, sources)| {
+      let domain = sanitized.clone();
+      tauri::async_runtime::spawn(async move {
+        SystemResolverResult {
+          sources,
+          result: test_single_dns_server(server, &domain, None, Protocol::Udp, None, opts).await,
+        }
+      })
+    })
+    .collect();
+  for task in tasks {
+    if let Ok(result) = task.await {
+      response.results.push(result);
+    }
+  }
+  response
+}
+
+#[cfg(target_os = "windows")]
+fn write_adapter_dns_servers(adapter: &str, servers: &[String]) -> DnsManagerResult {
+  let quoted: Vec<String> = servers
+    .iter()
+    .map(|server| format!("'{}'", ps_escape_single(server)))
+    .collect();
+  let command = format!(
+    "Set-DnsClientServerAddress -InterfaceAlias '{}' -ServerAddresses @({})",
+    ps_escape_single(adapter),
+    quoted.join(",")
+  );
+  match run_powershell(&command) {
+    Ok(_) => {
+      clear_dns_adapter_cache();
+      DnsManagerResult {
+        success: true,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: None,
+        error_message: None,
+      }
+    }
+    Err(error) => DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(ErrorCode::CommandFailed),
+      error_message: Some(error),
+    },
+  }
+}
+
+// Static servers live in the interface's NameServer registry value; an empty value
+// means the adapter takes DNS from DHCP.
+#[cfg(target_os = "windows")]
+fn capture_adapter_dns(adapter: &str, family: AddressFamily) -> Result<DnsBackup, ErrorDetail> {
+  let command = format!(
+    "$guid = (Get-NetAdapter -Name '{}' -ErrorAction Stop).InterfaceGuid; (Get-ItemProperty \"HKLM:\\SYSTEM\\CurrentControlSet\\Services\\{}\\Parameters\\Interfaces\\$guid\" -Name NameServer -ErrorAction SilentlyContinue).NameServer",
+    ps_escape_single(adapter),
+    family.tcpip_service()
+  );
+  let output = run_powershell(&command).map_err(|error| (ErrorCode::CommandFailed, Some(error)))?;
+  let servers: Vec<String> = output
+    .split([',', ' ', '\n', '\r'])
+    .map(|server| server.trim().to_string())
+    .filter(|server| !server.is_empty())
+    .collect();
+  Ok(DnsBackup {
+    adapter: adapter.to_string(),
+    family,
+    dhcp: servers.is_empty(),
+    servers,
+  })
+}
+
+#[cfg(target_os = "windows")]
+fn restore_adapter_dns(backup: &DnsBackup) -> DnsManagerResult {
+  if !backup.dhcp {
+    return write_adapter_dns_servers(&backup.adapter, &backup.servers);
+  }
+  // -ResetServerAddresses clears both families, so the other family's static servers are put back.
+  let kept = match capture_adapter_dns(&backup.adapter, backup.family.other()) {
+    Ok(other) => other.servers,
+    Err((code, message)) => {
+      return DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(code),
+        error_message: message,
+      }
+    }
+  };
+  let reset = reset_adapter_dns(backup.adapter.clone());
+  if !reset.success || kept.is_empty() {
+    return reset;
+  }
+  write_adapter_dns_servers(&backup.adapter, &kept)
+}
+
+// The backup is also kept on disk so a change that outlives the process (exit, restart or
+// crash before the timer fires) is still reverted on the next start.
+#[cfg(target_os = "windows")]
+fn pending_dns_revert_path(app: &AppHandle) -> PathBuf {
+  if let Some(dir) = app.path_resolver().app_config_dir() {
+    return dir.join("pending-dns-revert.json");
+  }
+  PathBuf::from("pending-dns-revert.json")
+}
+
+#[cfg(target_os = "windows")]
+fn write_pending_dns_backup(app: &AppHandle, backup: &DnsBackup) -> Result<(), ErrorDetail> {
+  let path = pending_dns_revert_path(app);
+  if let Some(parent) = path.parent() {
+    let _ = fs::create_dir_all(parent);
+  }
+  fs::write(path, serde_json::to_vec(backup).unwrap_or_default())
+    .map_err(|error| (ErrorCode::FileWriteFailed, Some(error.to_string())))
+}
+
+#[cfg(target_os = "windows")]
+fn clear_pending_dns_backup(app: &AppHandle) {
+  let _ = fs::remove_file(pending_dns_revert_path(app));
+}
+
+// Reverts an unconfirmed temporary change right away, whether it is pending in this process
+// or was left on disk by an earlier one. Returns None when there was nothing to revert.
+fn revert_pending_dns_change(app: &AppHandle) -> Option<DnsManagerResult> {
+  #[cfg(target_os = "windows")]
+  {
+    let state: State<AppState> = app.state();
+    let pending = state.pending_dns_revert.lock().ok().and_then(|mut guard| guard.take());
+    let backup = match pending {
+      Some(pending) => {
+        pending.handle.abort();
+        pending.backup
+      }
+      None => {
+        let raw = fs::read_to_string(pending_dns_revert_path(app)).ok()?;
+        serde_json::from_str::<DnsBackup>(&raw).ok()?
+      }
+    };
+    let result = restore_adapter_dns(&backup);
+    // A failed restore keeps the file so the next start tries again.
+    if result.success {
+      clear_pending_dns_backup(app);
+    }
+    Some(result)
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    let _ = app;
+    None
+  }
+}
+
+#[cfg(target_os = "windows")]
+async fn run_dns_revert(app: AppHandle, delay: Duration) {
+  tokio::time::sleep(delay).await;
+  let state: State<AppState> = app.state();
+  let pending = state.pending_dns_revert.lock().ok().and_then(|mut guard| guard.take());
+  let Some(pending) = pending else {
+    return;
+  };
+  let result = tauri::async_runtime::spawn_blocking(move || restore_adapter_dns(&pending.backup))
+    .await
+    .unwrap_or_else(|err| DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(ErrorCode::CommandFailed),
+      error_message: Some(err.to_string()),
+    });
+  if result.success {
+    clear_pending_dns_backup(&app);
+  }
+  let _ = app.emit_all("dns-change-reverted", result);
+}
+
+// Applies DNS servers and restores the previous configuration after the timeout
+// unless confirm_dns_change is called first.
+#[tauri::command]
+async fn set_adapter_dns_temporary(
+  app: AppHandle,
+  adapter_name: String,
+  servers: Vec<String>,
+  revert_after_seconds: Option<u64>,
+  address_family: Option<String>,
+) -> TemporaryDnsResult {
+  #[cfg(target_os = "windows")]
+  {
+    let failed = |code: ErrorCode, message: Option<String>| TemporaryDnsResult {
+      success: false,
+      revert_at: None,
+      error: Some(code),
+      error_message: message,
+    };
+    let adapter = adapter_name.trim().to_string();
+    let servers: Vec<String> = servers
+      .iter()
+      .map(|server| server.trim().to_string())
+      .filter(|server| !server.is_empty())
+      .collect();
+    if adapter.is_empty() || servers.is_empty() {
+      return failed(ErrorCode::InvalidInput, None);
+    }
+    let family = match AddressFamily::parse(address_family.as_deref()) {
+      Ok(family) => family,
+      Err((code, message)) => return failed(code, message),
+    };
+    for server in &servers {
+      match server.parse::<std::net::IpAddr>() {
+        Ok(ip) if family.matches(&ip) => {}
+        Ok(_) => {
+          let message = format!("{} is not an {} address", server, family.label());
+          return failed(ErrorCode::InvalidInput, Some(message));
+        }
+        Err(_) => return failed(ErrorCode::InvalidInput, Some(format!("{} is not an IP address", server))),
+      }
+    }
+    if servers.len() > MAX_ADAPTER_DNS_SERVERS {
+      return failed(
+        ErrorCode::TooManyServers,
+        Some(format!("At most {} DNS servers can be configured", MAX_ADAPTER_DNS_SERVERS)),
+      );
+    }
+    let delay_secs = revert_after_seconds
+      .unwrap_or(DNS_REVERT_DEFAULT_SECS)
+      .clamp(DNS_REVERT_MIN_SECS, DNS_REVERT_MAX_SECS);
+    let state: State<AppState> = app.state();
+    let _change = state.dns_change.lock().await;
+    // A second temporary change keeps the original backup so the revert still goes back
+    // to the configuration from before any test started.
+    let existing = {
+      let Ok(mut guard) = state.pending_dns_revert.lock() else {
+        return failed(ErrorCode::CommandFailed, None);
+      };
+      match guard.take() {
+        Some(pending) if pending.backup.adapter == adapter && pending.backup.family == family => {
+          pending.handle.abort();
+          Some(pending.backup)
+        }
+        Some(pending) => {
+          let message = format!("Confirm or wait for the pending change on {} first", pending.backup.adapter);
+          *guard = Some(pending);
+          return failed(ErrorCode::InvalidInput, Some(message));
+        }
+        None => None,
+      }
+    };
+    let had_pending = existing.is_some();
+    // PowerShell blocks, so the capture, write and any rollback run off the async workers.
+    let worker_app = app.clone();
+    let applied = tauri::async_runtime::spawn_blocking(move || {
+      let backup = match existing {
+        Some(backup) => backup,
+        None => capture_adapter_dns(&adapter, family)?,
+      };
+      // Written before the change is applied so a crash in between still leaves a way back.
+      if !had_pending {
+        write_pending_dns_backup(&worker_app, &backup)?;
+      }
+      let applied = write_adapter_dns_servers(&adapter, &servers);
+      if applied.success {
+        return Ok(backup);
+      }
+      // Its timer was already aborted, so revert an earlier temporary change right away.
+      if had_pending {
+        let outcome = restore_adapter_dns(&backup);
+        if outcome.success {
+          clear_pending_dns_backup(&worker_app);
+        }
+        let _ = worker_app.emit_all("dns-change-reverted", outcome);
+      } else {
+        clear_pending_dns_backup(&worker_app);
+      }
+      Err((applied.error.unwrap_or(ErrorCode::CommandFailed), applied.error_message))
+    })
+    .await
+    .unwrap_or_else(|error| Err((ErrorCode::CommandFailed, Some(error.to_string()))));
+    let backup = match applied {
+      Ok(backup) => backup,
+      Err((code, message)) => return failed(code, message),
+    };
+    let handle = tauri::async_runtime::spawn(run_dns_revert(app.clone(), Duration::from_secs(delay_secs)));
+    if let Ok(mut guard) = state.pending_dns_revert.lock() {
+      *guard = Some(PendingDnsRevert { backup, handle });
+    }
+    TemporaryDnsResult {
+      success: true,
+      revert_at: Some(now_millis() + delay_secs as u128 * 1000),
+      error: None,
+      error_message: None,
+    }
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    let _ = (app, adapter_name, servers, revert_after_seconds, address_family);
+    TemporaryDnsResult {
+      success: false,
+      revert_at: None,
+      error: Some(ErrorCode::UnsupportedPlatform),
+      error_message: None,
+    }
+  }
+}
+
+#[tauri::command]
+async fn confirm_dns_change(app: AppHandle) -> bool {
+  #[cfg(target_os = "windows")]
+  {
+    let state: State<AppState> = app.state();
+    let pending = state.pending_dns_revert.lock().ok().and_then(|mut guard| guard.take());
+    let Some(pending) = pending else {
+      return false;
+    };
+    pending.handle.abort();
+    tauri::async_runtime::spawn_blocking(move || clear_pending_dns_backup(&app))
+      .await
+      .is_ok()
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    let _ = app;
+    false
+  }
+}
+
+// PowerShell can report success while a VPN or NRPT rule keeps the old servers in use,
+// so check the adapter, the system resolver list and the new server itself.
+#[cfg(target_os = "windows")]
+async fn verify_adapter_dns(adapter: String, family: AddressFamily, expected: Vec<String>) -> Result<(), String> {
+  let name = adapter.clone();
+  let (configured, effective) = tauri::async_runtime::spawn_blocking(move || {
+    let configured = family_adapter_dns(&name, family);
+    let effective: Vec<String> = effective_dns_servers().iter().map(|addr| addr.ip().to_string()).collect();
+    (configured, effective)
+  })
+  .await
+  .map_err(|error| error.to_string())?;
+  if configured != expected {
+    return Err(format!("{} still reports {}", adapter, configured.join(", ")));
+  }
+  let primary = &expected[0];
+  if !effective.contains(primary) {
+    return Err(format!("{} is not among the resolvers in use ({})", primary, effective.join(", ")));
+  }
+  let opts = build_resolver_opts(&DnsTestOptions::default());
+  let probe = test_single_dns_server(primary.clone(), DNS_RECOMMEND_DOMAIN, None, Protocol::Udp, None, opts).await;
+  if !probe.status {
+    return Err(format!("{} did not answer a test query", primary));
+  }
+  Ok(())
+}
+
+// The blocking half of set_adapter_dns: reads the current list, merges and writes the new one.
+// Err carries the final result when there is nothing further to do.
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+fn write_requested_dns(
+  adapter: &str,
+  family: AddressFamily,
+  requested: Vec<String>,
+  append: bool,
+) -> Result<(DnsBackup, Vec<String>, DnsManagerResult), DnsManagerResult> {
+  let current = family_adapter_dns(adapter, family);
+  let mut merged: Vec<String> = if append { current.clone() } else { vec![] };
+  for server in requested {
+    if !merged.contains(&server) {
+      merged.push(server);
+    }
+  }
+  // Writing the same list again is a no-op; tell the UI instead of reporting a change.
+  if merged == current {
+    return Err(DnsManagerResult {
+      success: true,
+      no_change: true,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: None,
+      error_message: None,
+    });
+  }
+  if merged.len() > MAX_ADAPTER_DNS_SERVERS {
+    return Err(DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(ErrorCode::TooManyServers),
+      error_message: Some(format!("At most {} DNS servers can be configured", MAX_ADAPTER_DNS_SERVERS)),
+    });
+  }
+  let backup = match capture_adapter_dns(adapter, family) {
+    Ok(backup) => backup,
+    Err((code, message)) => {
+      return Err(DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(code),
+        error_message: message,
+      })
+    }
+  };
+  // resolvectl replaces the whole per-link list, so keep the other family's servers in it.
+  let mut servers: Vec<String> = backup
+    .servers
+    .iter()
+    .filter(|server| server.parse::<std::net::IpAddr>().is_ok_and(|ip| !family.matches(&ip)))
+    .cloned()
+    .collect();
+  servers.extend(merged.iter().cloned());
+  let result = write_adapter_dns_servers(adapter, &servers);
+  if !result.success {
+    return Err(result);
+  }
+  Ok((backup, merged, result))
+}
+
+#[tauri::command]
+async fn set_adapter_dns(
+  adapter_name: String,
+  primary_dns: String,
+  secondary_dns: Option<String>,
+  append: Option<bool>,
+  verify: Option<bool>,
+  address_family: Option<String>,
+) -> DnsManagerResult {
+  #[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+  {
+    let invalid = |message: Option<String>| DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(ErrorCode::InvalidInput),
+      error_message: message,
+    };
+    let adapter = adapter_name.trim();
+    let primary = primary_dns.trim();
+    if adapter.is_empty() || primary.is_empty() {
+      return invalid(None);
+    }
+    let family = match AddressFamily::parse(address_family.as_deref()) {
+      Ok(family) => family,
+      Err((_, message)) => return invalid(message),
+    };
+    let mut requested = vec![primary.to_string()];
+    if let Some(secondary) = secondary_dns {
+      let trimmed = secondary.trim();
+      if !trimmed.is_empty() {
+        requested.push(trimmed.to_string());
+      }
+    }
+    for server in &requested {
+      match server.parse::<std::net::IpAddr>() {
+        Ok(ip) if family.matches(&ip) => {}
+        Ok(_) => return invalid(Some(format!("{} is not an {} address", server, family.label()))),
+        Err(_) => return invalid(Some(format!("{} is not an IP address", server))),
+      }
+    }
+    // PowerShell, resolvectl and networksetup all block, so they run off the async workers.
+    let failed_task = |error: tauri::Error| DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(ErrorCode::CommandFailed),
+      error_message: Some(error.to_string()),
+    };
+    let (owned_adapter, append) = (adapter.to_string(), append.unwrap_or(false));
+    let written = tauri::async_runtime::spawn_blocking(move || {
+      write_requested_dns(&owned_adapter, family, requested, append)
+    })
+    .await;
+    let (backup, merged, mut result) = match written {
+      Ok(Ok(written)) => written,
+      Ok(Err(result)) => return result,
+      Err(error) => return failed_task(error),
+    };
+    let opts = build_resolver_opts(&DnsTestOptions::default());
+    let probe = test_single_dns_server(merged[0].clone(), DNS_RECOMMEND_DOMAIN, None, Protocol::Udp, None, opts).await;
+    if !probe.status {
+      let restored = tauri::async_runtime::spawn_blocking(move || restore_adapter_dns(&backup))
+        .await
+        .unwrap_or_else(failed_task);
+      let failure = format!("{} did not resolve {}", merged[0], DNS_RECOMMEND_DOMAIN);
+      let message = if restored.success {
+        format!("{failure}; the previous servers were restored")
+      } else {
+        format!(
+          "{failure} and restoring the previous servers failed: {}",
+          restored.error_message.unwrap_or_default()
+        )
+      };
+      return DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: restored.success,
+        backend: result.backend,
+        error: Some(probe.error.unwrap_or(ErrorCode::LookupFailed)),
+        error_message: Some(message),
+      };
+    }
+    if verify.unwrap_or(false) {
+      #[cfg(target_os = "windows")]
+      clear_dns_adapter_cache();
+      match verify_adapter_dns(adapter.to_string(), family, merged).await {
+        Ok(()) => result.applied_and_verified = true,
+        Err(message) => result.error_message = Some(message),
+      }
+    }
+    result
+  }
+
+  #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+  {
+    let _ = (adapter_name, primary_dns, secondary_dns, append, verify, address_family);
+    DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(ErrorCode::UnsupportedPlatform),
+      error_message: None,
+    }
+  }
+}
+
+// Both steps are needed: dscacheutil drops the directory cache, mDNSResponder holds the resolver cache.
+#[cfg(target_os = "macos")]
+fn flush_macos_dns_cache() -> Result<(), ErrorDetail> {
+  for (program, args) in [("dscacheutil", &["-flushcache"][..]), ("killall", &["-HUP", "mDNSResponder"][..])] {
+    let output = Command::new(program)
+      .args(args)
+      .output()
+      .map_err(|error| (ErrorCode::CommandFailed, Some(error.to_string())))?;
+    if !output.status.success() {
+      let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+      let code = if stderr.to_lowercase().contains("not permitted") {
+        ErrorCode::ElevationRequired
+      } else {
+        ErrorCode::CommandFailed
+      };
+      return Err((code, Some(format!("{}: {}", program, stderr))));
+    }
+  }
+  Ok(())
+}
+
+#[tauri::command]
+fn flush_dns_cache() -> DnsManagerResult {
+  #[cfg(target_os = "windows")]
+  let (backend, outcome) = {
+    let outcome = run_powershell("Clear-DnsClientCache")
+      .map(|_| ())
+      .map_err(|error| (ErrorCode::CommandFailed, Some(error)));
+    // The adapter listing is cached too; drop it so the next read reflects the flushed state.
+    clear_dns_adapter_cache();
+    (Some("Clear-DnsClientCache"), outcome)
+  };
+  #[cfg(target_os = "macos")]
+  let (backend, outcome) = (Some("dscacheutil"), flush_macos_dns_cache());
+  #[cfg(target_os = "linux")]
+  let (backend, outcome) = (Some("resolvectl"), run_resolvectl(&["flush-caches"]).map(|_| ()));
+  #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+  let (backend, outcome): (Option<&str>, Result<(), ErrorDetail>) =
+    (None, Err((ErrorCode::UnsupportedPlatform, None)));
+
+  let (error, error_message) = match outcome {
+    Ok(()) => (None, None),
+    Err((code, message)) => (Some(code), message),
+  };
+  DnsManagerResult {
+    success: error.is_none(),
+    no_change: false,
+    applied_and_verified: false,
+    rolled_back: false,
+    backend: backend.map(str::to_string),
+    error,
+    error_message,
+  }
+}
+
+#[tauri::command]
+fn reset_adapter_dns(adapter_name: String) -> DnsManagerResult {
+  #[cfg(target_os = "windows")]
+  {
+    let adapter = adapter_name.trim();
+    if adapter.is_empty() {
+      return DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(ErrorCode::InvalidInput),
+        error_message: None,
+      };
+    }
+    let command = format!(
+      "Set-DnsClientServerAddress -InterfaceAlias '{}' -ResetServerAddresses",
+      ps_escape_single(adapter)
+    );
+    match run_powershell(&command) {
+      Ok(_) => {
+        clear_dns_adapter_cache();
+        DnsManagerResult {
+          success: true,
+          no_change: false,
+          applied_and_verified: false,
+          rolled_back: false,
+          backend: None,
+          error: None,
+          error_message: None,
+        }
+      }
+      Err(error) => DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(ErrorCode::CommandFailed),
+        error_message: Some(error),
+      },
+    }
+  }
+
+  #[cfg(any(target_os = "linux", target_os = "macos"))]
+  {
+    let adapter = adapter_name.trim();
+    if adapter.is_empty() {
+      return DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(ErrorCode::InvalidInput),
+        error_message: None,
+      };
+    }
+    clear_adapter_dns(adapter)
+  }
+
+  #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+  {
+    let _ = adapter_name;
+    DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(ErrorCode::UnsupportedPlatform),
+      error_message: None,
+    }
+  }
+}
+
+// Newer systemd releases can describe every link as JSON; the field names are read loosely
+// because the schema has changed between versions.
+#[cfg(target_os = "linux")]
+fn parse_resolvectl_json(output: &str) -> Vec<DnsAdapter> {
+  let Ok(parsed) = serde_json::from_str::<serde_json::Value>(output) else {
+    return vec![];
+  };
+  let items = match parsed.get("links").or_else(|| parsed.get("interfaces")) {
+    Some(serde_json::Value::Array(items)) => items.clone(),
+    _ => match parsed {
+      serde_json::Value::Array(items) => items,
+      other => vec![other],
+    },
+  };
+  let strings = |value: Option<&serde_json::Value>, keys: &[&str]| -> Vec<String> {
+    value
+      .and_then(|value| value.as_array())
+      .map(|values| {
+        values
+          .iter()
+          .filter_map(|value| match value {
+            serde_json::Value::String(text) => Some(text.clone()),
+            serde_json::Value::Object(_) => keys
+              .iter()
+              .find_map(|key| value.get(*key).and_then(|field| field.as_str()))
+              .map(str::to_string),
+            _ => None,
+          })
+          .collect()
+      })
+      .unwrap_or_default()
+  };
+  items
+    .iter()
+    .filter_map(|item| {
+      let name = ["ifname", "name", "interface"]
+        .iter()
+        .find_map(|key| item.get(*key).and_then(|value| value.as_str()))?
+        .trim()
+        .to_string();
+      if name.is_empty() {
+        return None;
+      }
+      let servers = ["servers", "dnsServers", "dns"].iter().find_map(|key| item.get(*key));
+      let domains = ["searchDomains", "domains"].iter().find_map(|key| item.get(*key));
+      Some(DnsAdapter {
+        name,
+        dns: strings(servers, &["addressString", "address"])
+          .into_iter()
+          .map(|server| resolvectl_server_address(&server))
+          .collect(),
+        dhcp_enabled: false,
+        suffix: strings(domains, &["name", "domain"])
+          .into_iter()
+          .find(|domain| !domain.starts_with('~')),
+      })
+    })
+    .collect()
+}
+
+// `resolvectl dns` and `resolvectl domain` print "Link 2 (eth0): value value ..." per interface.
+#[cfg(target_os = "linux")]
+fn parse_resolvectl_links(output: &str) -> Vec<(String, Vec<String>)> {
+  output
+    .lines()
+    .filter_map(|line| {
+      let rest = line.trim().strip_prefix("Link ")?;
+      let (_, rest) = rest.split_once('(')?;
+      let (name, values) = rest.split_once("):")?;
+      Some((name.to_string(), values.split_whitespace().map(str::to_string).collect()))
+    })
+    .collect()
+}
+
+// Servers can carry a "#server-name" suffix used for DNS-over-TLS.
+#[cfg(target_os = "linux")]
+fn resolvectl_server_address(server: &str) -> String {
+  server.split('#').next().unwrap_or(server).trim().to_string()
+}
+
+#[cfg(target_os = "linux")]
+fn run_resolvectl(args: &[&str]) -> Result<String, ErrorDetail> {
+  let output = Command::new("resolvectl")
+    .args(args)
+    .output()
+    .map_err(|error| (ErrorCode::CommandFailed, Some(error.to_string())))?;
+  if output.status.success() {
+    return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+  }
+  let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+  let lowered = stderr.to_lowercase();
+  if ["access denied", "permission denied", "interactive authentication"]
+    .iter()
+    .any(|needle| lowered.contains(needle))
+  {
+    return Err((ErrorCode::ElevationRequired, Some(stderr)));
+  }
+  Err((ErrorCode::CommandFailed, Some(stderr)))
+}
+
+// None means systemd-resolved isn't running and /etc/resolv.conf is managed directly.
+#[cfg(target_os = "linux")]
+fn resolved_links() -> Option<Vec<DnsAdapter>> {
+  if let Ok(output) = run_resolvectl(&["status", "--json=short"]) {
+    let adapters = parse_resolvectl_json(&output);
+    if !adapters.is_empty() {
+      return Some(adapters);
+    }
+  }
+  // Older systemd releases have no JSON status, so fall back to the plain listings.
+  let servers = parse_resolvectl_links(&run_resolvectl(&["dns"]).ok()?);
+  let domains = run_resolvectl(&["domain"])
+    .map(|output| parse_resolvectl_links(&output))
+    .unwrap_or_default();
+  Some(
+    servers
+      .into_iter()
+      .map(|(name, dns)| DnsAdapter {
+        suffix: domains
+          .iter()
+          .find(|(link, _)| *link == name)
+          .and_then(|(_, values)| values.iter().find(|domain| !domain.starts_with('~')).cloned()),
+        name,
+        dns: dns.iter().map(|server| resolvectl_server_address(server)).collect(),
+        dhcp_enabled: false,
+      })
+      .collect(),
+  )
+}
+
+#[cfg(target_os = "linux")]
+fn read_resolv_conf() -> (Vec<String>, Option<String>) {
+  let contents = fs::read_to_string(RESOLV_CONF_PATH).unwrap_or_default();
+  let mut servers = Vec::new();
+  let mut suffix = None;
+  for line in contents.lines() {
+    let mut fields = line.split_whitespace();
+    match fields.next() {
+      Some("nameserver") => servers.extend(fields.next().map(str::to_string)),
+      Some("domain") | Some("search") if suffix.is_none() => suffix = fields.next().map(str::to_string),
+      _ => {}
+    }
+  }
+  (servers, suffix)
+}
+
+// Both families are listed together on Linux; the family only filters which servers are shown.
+#[cfg(target_os = "linux")]
+fn family_dns_adapters(family: AddressFamily) -> Vec<DnsAdapter> {
+  let mut adapters = resolved_links().unwrap_or_else(|| {
+    let (dns, suffix) = read_resolv_conf();
+    vec![DnsAdapter {
+      name: RESOLV_CONF_ADAPTER.to_string(),
+      dns,
+      dhcp_enabled: false,
+      suffix,
+    }]
+  });
+  for adapter in &mut adapters {
+    adapter
+      .dns
+      .retain(|server| server.parse::<std::net::IpAddr>().is_ok_and(|ip| family.matches(&ip)));
+  }
+  adapters.sort_by(|left, right| left.name.cmp(&right.name));
+  adapters
+}
+
+#[cfg(target_os = "linux")]
+fn linux_adapter_servers(adapter: &str) -> Result<Vec<String>, ErrorDetail> {
+  let adapters = resolved_links().unwrap_or_else(|| {
+    vec![DnsAdapter {
+      name: RESOLV_CONF_ADAPTER.to_string(),
+      dns: read_resolv_conf().0,
+      dhcp_enabled: false,
+      suffix: None,
+    }]
+  });
+  adapters
+    .into_iter()
+    .find(|item| item.name == adapter)
+    .map(|item| item.dns)
+    .ok_or((ErrorCode::InvalidInput, Some(format!("Unknown adapter {}", adapter))))
+}
+
+#[cfg(target_os = "linux")]
+fn family_adapter_dns(adapter: &str, family: AddressFamily) -> Vec<String> {
+  linux_adapter_servers(adapter)
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|server| server.parse::<std::net::IpAddr>().is_ok_and(|ip| family.matches(&ip)))
+    .collect()
+}
+
+// The snapshot holds both families since a write replaces the link's whole list.
+#[cfg(target_os = "linux")]
+fn capture_adapter_dns(adapter: &str, family: AddressFamily) -> Result<DnsBackup, ErrorDetail> {
+  let servers = linux_adapter_servers(adapter)?;
+  Ok(DnsBackup {
+    adapter: adapter.to_string(),
+    family,
+    dhcp: servers.is_empty(),
+    servers,
+  })
+}
+
+#[cfg(target_os = "linux")]
+fn linux_dns_result(backend: &str, outcome: Result<(), ErrorDetail>) -> DnsManagerResult {
+  let (error, error_message) = match outcome {
+    Ok(()) => (None, None),
+    Err((code, message)) => (Some(code), message),
+  };
+  DnsManagerResult {
+    success: error.is_none(),
+    no_change: false,
+    applied_and_verified: false,
+    rolled_back: false,
+    backend: Some(backend.to_string()),
+    error,
+    error_message,
+  }
+}
+
+// NetworkManager marks the resolv.conf it generates with this comment.
+#[cfg(target_os = "linux")]
+fn resolv_conf_is_network_manager(contents: &str) -> bool {
+  contents
+    .lines()
+    .any(|line| line.starts_with('#') && line.contains("NetworkManager"))
+}
+
+// A symlinked resolv.conf belongs to whichever service owns the target (systemd-resolved's stub,
+// NetworkManager, resolvconf), so it is left alone. A plain file is replaced atomically.
+#[cfg(target_os = "linux")]
+fn write_resolv_conf_servers(servers: &[String]) -> Result<(), ErrorDetail> {
+  let path = Path::new(RESOLV_CONF_PATH);
+  if let Ok(target) = fs::read_link(path) {
+    return Err((
+      ErrorCode::FileWriteFailed,
+      Some(format!(
+        "{} links to {}, which another service manages; change DNS there instead",
+        RESOLV_CONF_PATH,
+        target.display()
+      )),
+    ));
+  }
+  let contents = fs::read_to_string(path).unwrap_or_default();
+  let mut lines: Vec<String> = Vec::new();
+  let mut inserted = false;
+  for line in contents.lines() {
+    if line.split_whitespace().next() == Some("nameserver") {
+      // New servers take the place of the first existing entry to keep comments and options around them.
+      if !inserted {
+        lines.extend(servers.iter().map(|server| format!("nameserver {}", server)));
+        inserted = true;
+      }
+      continue;
+    }
+    lines.push(line.to_string());
+  }
+  if !inserted {
+    lines.extend(servers.iter().map(|server| format!("nameserver {}", server)));
+  }
+  let write_error = |error: std::io::Error| {
+    let code = if error.kind() == std::io::ErrorKind::PermissionDenied {
+      ErrorCode::ElevationRequired
+    } else {
+      ErrorCode::FileWriteFailed
+    };
+    (code, Some(error.to_string()))
+  };
+  // Written next to the original so the rename stays on one filesystem and readers never see half a file.
+  let staged = path.with_extension("conf.pulsenet");
+  fs::write(&staged, lines.join("\n") + "\n").map_err(write_error)?;
+  fs::rename(&staged, path).map_err(|error| {
+    let _ = fs::remove_file(&staged);
+    write_error(error)
+  })
+}
+
+#[cfg(target_os = "linux")]
+fn write_adapter_dns_servers(adapter: &str, servers: &[String]) -> DnsManagerResult {
+  if adapter == RESOLV_CONF_ADAPTER {
+    let managed = resolv_conf_is_network_manager(&fs::read_to_string(RESOLV_CONF_PATH).unwrap_or_default());
+    let mut result = linux_dns_result("resolv.conf", write_resolv_conf_servers(servers));
+    if result.success && managed {
+      let warning = "NetworkManager generated resolv.conf and will overwrite these servers on its next update";
+      result.error_message = Some(warning.to_string());
+    }
+    return result;
+  }
+  let mut args = vec!["dns", adapter];
+  args.extend(servers.iter().map(String::as_str));
+  linux_dns_result("resolvectl", run_resolvectl(&args).map(|_| ()))
+}
+
+#[cfg(target_os = "linux")]
+fn clear_adapter_dns(adapter: &str) -> DnsManagerResult {
+  if adapter == RESOLV_CONF_ADAPTER {
+    return linux_dns_result(
+      "resolv.conf",
+      Err((
+        ErrorCode::UnsupportedPlatform,
+        Some("resolv.conf has no automatic configuration to reset to".to_string()),
+      )),
+    );
+  }
+  // revert drops the per-link settings so the servers from networkd/NetworkManager apply again.
+  linux_dns_result("resolvectl", run_resolvectl(&["revert", adapter]).map(|_| ()))
+}
+
+// A command can succeed while resolved or NetworkManager puts back its own list, so read it again.
+#[cfg(target_os = "linux")]
+async fn verify_adapter_dns(adapter: String, family: AddressFamily, expected: Vec<String>) -> Result<(), String> {
+  let name = adapter.clone();
+  let configured = tauri::async_runtime::spawn_blocking(move || family_adapter_dns(&name, family))
+    .await
+    .map_err(|error| error.to_string())?;
+  if configured != expected {
+    return Err(format!("{} still reports {}", adapter, configured.join(", ")));
+  }
+  Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn restore_adapter_dns(backup: &DnsBackup) -> DnsManagerResult {
+  if backup.dhcp && backup.adapter != RESOLV_CONF_ADAPTER {
+    clear_adapter_dns(&backup.adapter)
+  } else {
+    write_adapter_dns_servers(&backup.adapter, &backup.servers)
+  }
+}
+
+// networksetup reports most failures on stdout with a zero exit status, so the text is checked too.
+#[cfg(target_os = "macos")]
+fn run_networksetup(args: &[&str]) -> Result<String, ErrorDetail> {
+  let output = Command::new("networksetup")
+    .args(args)
+    .output()
+    .map_err(|error| (ErrorCode::CommandFailed, Some(error.to_string())))?;
+  let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+  let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+  let combined = format!("{}{}", stdout, stderr).trim().to_string();
+  let lowered = combined.to_lowercase();
+  if ["requires admin", "must run this tool as root", "not authorized", "permission denied"]
+    .iter()
+    .any(|needle| lowered.contains(needle))
+  {
+    return Err((
+      ErrorCode::ElevationRequired,
+      Some(format!("networksetup needs administrator rights: {}", combined)),
+    ));
+  }
+  let failed = lowered.contains("** error") || lowered.contains("is not a recognized network service");
+  if !output.status.success() || failed {
+    return Err((ErrorCode::CommandFailed, Some(combined)));
+  }
+  Ok(stdout)
+}
+
+// The first line explains that an asterisk marks a disabled service.
+#[cfg(target_os = "macos")]
+fn network_services() -> Vec<String> {
+  run_networksetup(&["-listallnetworkservices"])
+    .unwrap_or_default()
+    .lines()
+    .skip(1)
+    .map(|line| line.trim_start_matches('*').trim().to_string())
+    .filter(|line| !line.is_empty())
+    .collect()
+}
+
+// "There aren't any ... set on <service>." means nothing is configured.
+#[cfg(target_os = "macos")]
+fn networksetup_list(flag: &str, service: &str) -> Result<Vec<String>, ErrorDetail> {
+  let output = run_networksetup(&[flag, service])?;
+  if output.contains("There aren't any") {
+    return Ok(vec![]);
+  }
+  Ok(output.split_whitespace().map(str::to_string).collect())
+}
+
+#[cfg(target_os = "macos")]
+fn family_dns_adapters(family: AddressFamily) -> Vec<DnsAdapter> {
+  let mut adapters: Vec<DnsAdapter> = network_services()
+    .into_iter()
+    .map(|name| {
+      let servers = networksetup_list("-getdnsservers", &name).unwrap_or_default();
+      DnsAdapter {
+        // With no manual servers the service uses whatever DHCP hands out.
+        dhcp_enabled: servers.is_empty(),
+        dns: servers
+          .into_iter()
+          .filter(|server| server.parse::<std::net::IpAddr>().is_ok_and(|ip| family.matches(&ip)))
+          .collect(),
+        suffix: networksetup_list("-getsearchdomains", &name)
+          .unwrap_or_default()
+          .into_iter()
+          .next(),
+        name,
+      }
+    })
+    .collect();
+  adapters.sort_by(|left, right| left.name.cmp(&right.name));
+  adapters
+}
+
+#[cfg(target_os = "macos")]
+fn family_adapter_dns(adapter: &str, family: AddressFamily) -> Vec<String> {
+  networksetup_list("-getdnsservers", adapter)
+    .unwrap_or_default()
+    .into_iter()
+    .filter(|server| server.parse::<std::net::IpAddr>().is_ok_and(|ip| family.matches(&ip)))
+    .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn capture_adapter_dns(adapter: &str, family: AddressFamily) -> Result<DnsBackup, ErrorDetail> {
+  let servers = networksetup_list("-getdnsservers", adapter)?;
+  Ok(DnsBackup {
+    adapter: adapter.to_string(),
+    family,
+    dhcp: servers.is_empty(),
+    servers,
+  })
+}
+
+#[cfg(target_os = "macos")]
+fn networksetup_result(outcome: Result<String, ErrorDetail>) -> DnsManagerResult {
+  let (error, error_message) = match outcome {
+    Ok(_) => (None, None),
+    Err((code, message)) => (Some(code), message),
+  };
+  DnsManagerResult {
+    success: error.is_none(),
+    no_change: false,
+    applied_and_verified: false,
+    rolled_back: false,
+    backend: Some("networksetup".to_string()),
+    error,
+    error_message,
+  }
+}
+
+#[cfg(target_os = "macos")]
+fn write_adapter_dns_servers(adapter: &str, servers: &[String]) -> DnsManagerResult {
+  let mut args = vec!["-setdnsservers", adapter];
+  args.extend(servers.iter().map(String::as_str));
+  networksetup_result(run_networksetup(&args))
+}
+
+// The literal "Empty" clears the manual list so the service falls back to DHCP.
+#[cfg(target_os = "macos")]
+fn clear_adapter_dns(adapter: &str) -> DnsManagerResult {
+  networksetup_result(run_networksetup(&["-setdnsservers", adapter, "Empty"]))
+}
+
+// networksetup can exit cleanly without touching the service, so read the list back.
+#[cfg(target_os = "macos")]
+async fn verify_adapter_dns(adapter: String, family: AddressFamily, expected: Vec<String>) -> Result<(), String> {
+  let name = adapter.clone();
+  let configured = tauri::async_runtime::spawn_blocking(move || family_adapter_dns(&name, family))
+    .await
+    .map_err(|error| error.to_string())?;
+  if configured != expected {
+    return Err(format!("{} still reports {}", adapter, configured.join(", ")));
+  }
+  Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn restore_adapter_dns(backup: &DnsBackup) -> DnsManagerResult {
+  if backup.dhcp {
+    clear_adapter_dns(&backup.adapter)
+  } else {
+    write_adapter_dns_servers(&backup.adapter, &backup.servers)
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn parse_adapter_metrics(output: &str) -> (Option<u32>, Option<u32>, bool) {
+  let parsed = match serde_json::from_str::<serde_json::Value>(output) {
+    Ok(value) => value,
+    Err(_) => return (None, None, false),
+  };
+  let items = if let Some(array) = parsed.as_array() {
+    array.clone()
+  } else {
+    vec![parsed]
+  };
+  let mut ipv4 = None;
+  let mut ipv6 = None;
+  let mut automatic = false;
+  for item in items {
+    let metric = item
+      .get("InterfaceMetric")
+      .and_then(|value| value.as_u64())
+      .map(|value| value as u32);
+    // ConvertTo-Json emits enums as numbers (2 = IPv4, 23 = IPv6; 1 = Enabled) unless
+    // they were already stringified upstream.
+    let family = item.get("AddressFamily").map(|value| value.to_string()).unwrap_or_default();
+    if family.contains("23") || family.contains("IPv6") {
+      ipv6 = metric;
+    } else {
+      ipv4 = metric;
+    }
+    let auto = item.get("AutomaticMetric").map(|value| value.to_string()).unwrap_or_default();
+    if auto == "1" || auto.contains("Enabled") {
+      automatic = true;
+    }
+  }
+  (ipv4, ipv6, automatic)
+}
+
+#[tauri::command]
+fn get_adapter_metric(adapter_name: String) -> AdapterMetric {
+  let adapter = adapter_name.trim().to_string();
+  #[cfg(target_os = "windows")]
+  {
+    if adapter.is_empty() {
+      return AdapterMetric {
+        adapter,
+        ipv4_metric: None,
+        ipv6_metric: None,
+        automatic_metric: false,
+        error: Some(ErrorCode::InvalidInput),
+        error_message: None,
+      };
+    }
+    let command = format!(
+      "Get-NetIPInterface -InterfaceAlias '{}' | Select-Object AddressFamily,InterfaceMetric,AutomaticMetric | ConvertTo-Json -Compress",
+      ps_escape_single(&adapter)
+    );
+    match run_powershell(&command) {
+      Ok(output) => {
+        let (ipv4_metric, ipv6_metric, automatic_metric) = parse_adapter_metrics(&output);
+        AdapterMetric {
+          adapter,
+          ipv4_metric,
+          ipv6_metric,
+          automatic_metric,
+          error: None,
+          error_message: None,
+        }
+      }
+      Err(error) => AdapterMetric {
+        adapter,
+        ipv4_metric: None,
+        ipv6_metric: None,
+        automatic_metric: false,
+        error: Some(ErrorCode::CommandFailed),
+        error_message: Some(error),
+      },
+    }
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    AdapterMetric {
+      adapter,
+      ipv4_metric: None,
+      ipv6_metric: None,
+      automatic_metric: false,
+      error: Some(ErrorCode::UnsupportedPlatform),
+      error_message: None,
+    }
+  }
+}
+
+#[tauri::command]
+fn set_adapter_metric(adapter_name: String, metric: u32) -> DnsManagerResult {
+  #[cfg(target_os = "windows")]
+  {
+    let adapter = adapter_name.trim();
+    if adapter.is_empty() || metric == 0 || metric > 9999 {
+      return DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(ErrorCode::InvalidInput),
+        error_message: None,
+      };
+    }
+    if !is_process_elevated() {
+      return DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(ErrorCode::ElevationRequired),
+        error_message: None,
+      };
+    }
+    let command = format!(
+      "Set-NetIPInterface -InterfaceAlias '{}' -InterfaceMetric {}",
+      ps_escape_single(adapter),
+      metric
+    );
+    match run_powershell(&command) {
+      Ok(_) => DnsManagerResult {
+        success: true,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: None,
+        error_message: None,
+      },
+      Err(error) => DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(ErrorCode::CommandFailed),
+        error_message: Some(error),
+      },
+    }
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    let _ = (adapter_name, metric);
+    DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(ErrorCode::UnsupportedPlatform),
+      error_message: None,
+    }
+  }
+}
+
+// The DoH cmdlets ship with the same builds (Windows 11 / Server 2022) that honour DoHPolicy.
+#[cfg(target_os = "windows")]
+fn system_doh_supported() -> bool {
+  run_powershell("[bool](Get-Command Get-DnsClientDohServerAddress -ErrorAction SilentlyContinue)")
+    .map(|output| output.eq_ignore_ascii_case("true"))
+    .unwrap_or(false)
+}
+
+#[tauri::command]
+fn get_system_doh_setting() -> SystemDohSetting {
+  #[cfg(target_os = "windows")]
+  {
+    if !system_doh_supported() {
+      return SystemDohSetting {
+        supported: false,
+        mode: None,
+        error: Some(ErrorCode::UnsupportedPlatform),
+        error_message: Some("This version of Windows has no system DNS-over-HTTPS setting".to_string()),
+      };
+    }
+    let command = format!(
+      "(Get-ItemProperty -Path '{}' -Name DoHPolicy -ErrorAction SilentlyContinue).DoHPolicy",
+      DOH_POLICY_KEY
+    );
+    match run_powershell(&command) {
+      Ok(output) => SystemDohSetting {
+        supported: true,
+        // DoHPolicy: 1 = prohibit, 2 = allow, 3 = require; absent means not configured.
+        mode: Some(
+          match output.trim() {
+            "1" => "off",
+            "2" => "allow",
+            "3" => "require",
+            _ => "not-configured",
+          }
+          .to_string(),
+        ),
+        error: None,
+        error_message: None,
+      },
+      Err(error) => SystemDohSetting {
+        supported: true,
+        mode: None,
+        error: Some(ErrorCode::CommandFailed),
+        error_message: Some(error),
+      },
+    }
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    SystemDohSetting {
+      supported: false,
+      mode: None,
+      error: Some(ErrorCode::UnsupportedPlatform),
+      error_message: None,
+    }
+  }
+}
+
+#[tauri::command]
+fn set_system_doh_setting(mode: String) -> DnsManagerResult {
+  #[cfg(target_os = "windows")]
+  {
+    let value = match mode.trim().to_lowercase().as_str() {
+      "off" => 1,
+      "allow" => 2,
+      "require" => 3,
+      _ => 0,
+    };
+    let failed = |code: ErrorCode, message: Option<String>| DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(code),
+      error_message: message,
+    };
+    if value == 0 {
+      return failed(ErrorCode::InvalidInput, None);
+    }
+    if !system_doh_supported() {
+      return failed(
+        ErrorCode::UnsupportedPlatform,
+        Some("This version of Windows has no system DNS-over-HTTPS setting".to_string()),
+      );
+    }
+    if !is_process_elevated() {
+      return failed(ErrorCode::ElevationRequired, None);
+    }
+    let command = format!(
+      "New-Item -Path '{key}' -Force | Out-Null; Set-ItemProperty -Path '{key}' -Name DoHPolicy -Type DWord -Value {value}; Clear-DnsClientCache",
+      key = DOH_POLICY_KEY,
+      value = value
+    );
+    match run_powershell(&command) {
+      Ok(_) => DnsManagerResult {
+        success: true,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: None,
+        error_message: None,
+      },
+      Err(error) => failed(ErrorCode::CommandFailed, Some(error)),
+    }
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    let _ = mode;
+    DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(ErrorCode::UnsupportedPlatform),
+      error_message: None,
+    }
+  }
+}
+
+#[tauri::command]
+fn get_network_category(adapter_name: String) -> NetworkCategoryResult {
+  let adapter = adapter_name.trim().to_string();
+  #[cfg(target_os = "windows")]
+  {
+    if adapter.is_empty() {
+      return NetworkCategoryResult {
+        adapter,
+        network_name: None,
+        category: None,
+        error: Some(ErrorCode::InvalidInput),
+        error_message: None,
+      };
+    }
+    let command = format!(
+      "Get-NetConnectionProfile -InterfaceAlias '{}' -ErrorAction Stop | Select-Object -First 1 Name,@{{n='Category';e={{$_.NetworkCategory.ToString()}}}} | ConvertTo-Json -Compress",
+      ps_escape_single(&adapter)
+    );
+    match run_powershell(&command) {
+      Ok(output) => {
+        let parsed = serde_json::from_str::<serde_json::Value>(&output).unwrap_or_default();
+        let text = |key: &str| {
+          parsed
+            .get(key)
+            .and_then(|value| value.as_str())
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+        };
+        let category = text("Category");
+        NetworkCategoryResult {
+          adapter,
+          network_name: text("Name"),
+          error: if category.is_none() { Some(ErrorCode::InvalidResponse) } else { None },
+          category,
+          error_message: None,
+        }
+      }
+      Err(error) => NetworkCategoryResult {
+        adapter,
+        network_name: None,
+        category: None,
+        error: Some(ErrorCode::CommandFailed),
+        error_message: Some(error),
+      },
+    }
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    NetworkCategoryResult {
+      adapter,
+      network_name: None,
+      category: None,
+      error: Some(ErrorCode::UnsupportedPlatform),
+      error_message: None,
+    }
+  }
+}
+
+#[tauri::command]
+fn set_network_category(adapter_name: String, category: String) -> DnsManagerResult {
+  #[cfg(target_os = "windows")]
+  {
+    let adapter = adapter_name.trim();
+    // DomainAuthenticated is assigned by Windows itself and can't be set manually.
+    let category = match category.trim().to_lowercase().as_str() {
+      "public" => "Public",
+      "private" => "Private",
+      _ => "",
+    };
+    if adapter.is_empty() || category.is_empty() {
+      return DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(ErrorCode::InvalidInput),
+        error_message: None,
+      };
+    }
+    if !is_process_elevated() {
+      return DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(ErrorCode::ElevationRequired),
+        error_message: None,
+      };
+    }
+    let command = format!(
+      "Set-NetConnectionProfile -InterfaceAlias '{}' -NetworkCategory {} -ErrorAction Stop",
+      ps_escape_single(adapter),
+      category
+    );
+    match run_powershell(&command) {
+      Ok(_) => DnsManagerResult {
+        success: true,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: None,
+        error_message: None,
+      },
+      Err(error) => DnsManagerResult {
+        success: false,
+        no_change: false,
+        applied_and_verified: false,
+        rolled_back: false,
+        backend: None,
+        error: Some(ErrorCode::CommandFailed),
+        error_message: Some(error),
+      },
+    }
+  }
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    let _ = (adapter_name, category);
+    DnsManagerResult {
+      success: false,
+      no_change: false,
+      applied_and_verified: false,
+      rolled_back: false,
+      backend: None,
+      error: Some(ErrorCode::UnsupportedPlatform),
+      error_message: None,
+    }
+  }
+}
+
+async fn measure_ping(client: &HttpClient, url: &str) -> (f64, f64) {
+  let mut samples = Vec::new();
+  for _ in 0..PING_SAMPLES {
+    let start = Instant::now();
+    let _ = client.get(url).send().await;
+    samples.push(start.elapsed().as_secs_f64() * 1000.0);
+  }
+  latency_stats(&samples)
+}
+
+fn latency_stats(samples: &[f64]) -> (f64, f64) {
+  let avg = samples.iter().sum::<f64>() / samples.len().max(1) as f64;
+  let mut jitter = 0.0;
+  if samples.len() > 1 {
+    let mut sum = 0.0;
+    for idx in 1..samples.len() {
+      sum += (samples[idx] - samples[idx - 1]).abs();
+    }
+    jitter = sum / (samples.len() - 1) as f64;
+  }
+  (avg, jitter)
+}
+
+fn median(values: &mut [f64]) -> f64 {
+  if values.is_empty() {
+    return 0.0;
+  }
+  values.sort_by(f64::total_cmp);
+  values[values.len() / 2]
+}
+
+async fn measure_latency_endpoint(client: HttpClient, name: &str, url: &str) -> LatencyEndpoint {
+  let mut endpoint = LatencyEndpoint {
+    name: name.to_string(),
+    url: url.to_string(),
+    latency_ms: None,
+    jitter_ms: None,
+    error: None,
+    error_message: None,
+  };
+  let mut samples = Vec::new();
+  for _ in 0..PING_SAMPLES {
+    let start = Instant::now();
+    match client.get(url).timeout(Duration::from_millis(LATENCY_ENDPOINT_TIMEOUT_MS)).send().await {
+      Ok(_) => samples.push(start.elapsed().as_secs_f64() * 1000.0),
+      Err(error) => {
+        endpoint.error = Some(if error.is_timeout() { ErrorCode::Timeout } else { ErrorCode::RequestFailed });
+        endpoint.error_message = Some(error.to_string());
+      }
+    }
+  }
+  // A few lost samples are fine; only an endpoint that never answered counts as failed.
+  if !samples.is_empty() {
+    let (avg, jitter) = latency_stats(&samples);
+    endpoint.latency_ms = Some(round2(avg));
+    endpoint.jitter_ms = Some(round2(jitter));
+    endpoint.error = None;
+    endpoint.error_message = None;
+  }
+  endpoint
+}
+
+// Median latency and jitter across LATENCY_ENDPOINTS, plus the per-endpoint breakdown.
+async fn measure_latency_multi(client: &HttpClient) -> (f64, f64, Vec<LatencyEndpoint>) {
+  let tasks: Vec<_> = LATENCY_ENDPOINTS
+    .iter()
+    .map(|(name, url)| tauri::async_runtime::spawn(measure_latency_endpoint(client.clone(), name, url)))
+    .collect();
+  let mut endpoints = Vec::with_capacity(tasks.len());
+  for task in tasks {
+    if let Ok(endpoint) = task.await {
+      endpoints.push(endpoint);
+    }
+  }
+  let mut latencies: Vec<f64> = endpoints.iter().filter_map(|endpoint| endpoint.latency_ms).collect();
+  let mut jitters: Vec<f64> = endpoints.iter().filter_map(|endpoint| endpoint.jitter_ms).collect();
+  (median(&mut latencies), median(&mut jitters), endpoints)
+}
+
+struct SpeedTestPlan {
+  lite: bool,
+  download_bytes: usize,
+  upload_bytes: usize,
+  // Caller picked the payload sizes, so servers must honour them exactly.
+  sized: bool,
+  // Seconds of connection setup to discount from each transfer when extrapolating.
+  setup_secs: f64,
+}
+
+fn speedtest_plan(
+  lite: bool,
+  latency_ms: f64,
+  download_bytes: Option<usize>,
+  upload_bytes: Option<usize>,
+) -> SpeedTestPlan {
+  let mut plan = if lite {
+    SpeedTestPlan {
+      lite,
+      download_bytes: LITE_DOWNLOAD_BYTES,
+      upload_bytes: LITE_UPLOAD_BYTES,
+      sized: false,
+      setup_secs: latency_ms / 1000.0,
+    }
+  } else {
+    SpeedTestPlan {
+      lite,
+      download_bytes: DOWNLOAD_BYTES,
+      upload_bytes: UPLOAD_BYTES,
+      sized: false,
+      setup_secs: 0.0,
+    }
+  };
+  let clamp = |bytes: usize| bytes.clamp(SPEEDTEST_MIN_PAYLOAD_BYTES, SPEEDTEST_MAX_PAYLOAD_BYTES);
+  if let Some(bytes) = download_bytes {
+    plan.download_bytes = clamp(bytes);
+    plan.sized = true;
+  }
+  if let Some(bytes) = upload_bytes {
+    plan.upload_bytes = clamp(bytes);
+    plan.sized = true;
+  }
+  plan
+}
+
+fn transfer_mbps(bytes: usize, elapsed: f64, setup_secs: f64) -> f64 {
+  // Never let the setup discount more than halve the measured time.
+  let duration = (elapsed - setup_secs).max(elapsed / 2.0);
+  if duration == 0.0 {
+    return 0.0;
+  }
+  (bytes as f64 * 8.0) / duration / 1_000_000.0
+}
+
+async fn fetch_object_timing(client: HttpClient, name: &str, url: &str) -> ObjectTiming {
+  let mut timing = ObjectTiming {
+    name: name.to_string(),
+    url: url.to_string(),
+    bytes: 0,
+    status_code: None,
+    ttfb_ms: None,
+    total_ms: None,
+    error: None,
+    error_message: None,
+  };
+  let start = Instant::now();
+  let response = client
+    .get(url)
+    .header("User-Agent", "PulseNet")
+    .timeout(Duration::from_secs(REALISTIC_OBJECT_TIMEOUT_SECS))
+    .send()
+    .await;
+  let response = match response {
+    Ok(response) => response,
+    Err(error) => {
+      timing.error = Some(if error.is_timeout() { ErrorCode::Timeout } else { ErrorCode::RequestFailed });
+      timing.error_message = Some(error.to_string());
+      return timing;
+    }
+  };
+  timing.ttfb_ms = Some(round2(start.elapsed().as_secs_f64() * 1000.0));
+  timing.status_code = Some(response.status().as_u16());
+  match response.bytes().await {
+    Ok(body) => {
+      timing.bytes = body.len();
+      timing.total_ms = Some(round2(start.elapsed().as_secs_f64() * 1000.0));
+    }
+    Err(error) => {
+      timing.error = Some(ErrorCode::DownloadFailed);
+      timing.error_message = Some(error.to_string());
+    }
+  }
+  timing
+}
+
+async fn run_realistic_throughput(client: HttpClient) -> RealisticThroughputResult {
+  let start = Instant::now();
+  // All objects start together, as a browser would open them in parallel.
+  let tasks: Vec<_> = REALISTIC_OBJECTS
+    .iter()
+    .map(|(name, url)| tauri::async_runtime::spawn(fetch_object_timing(client.clone(), name, url)))
+    .collect();
+  let mut objects = Vec::with_capacity(tasks.len());
+  for task in tasks {
+    if let Ok(timing) = task.await {
+      objects.push(timing);
+    }
+  }
+  let wall_secs = start.elapsed().as_secs_f64();
+  let total_bytes: usize = objects.iter().map(|object| object.bytes).sum();
+  let completed = objects.iter().filter(|object| object.error.is_none()).count();
+  RealisticThroughputResult {
+    aggregate_mbps: round2(transfer_mbps(total_bytes, wall_secs, 0.0)),
+    total_bytes,
+    wall_time_ms: round2(wall_secs * 1000.0),
+    completed,
+    error: if completed == 0 { Some(ErrorCode::DownloadFailed) } else { None },
+    error_message: None,
+    objects,
+  }
+}
+
+#[tauri::command]
+async fn realistic_throughput_test(
+  app: AppHandle,
+  operation_id: Option<u64>,
+) -> Result<RealisticThroughputResult, ErrorCode> {
+  let (client, _) = shared_http_client(&app.state());
+  run_cancellable(&app, operation_id, run_realistic_throughput(client)).await
+}
+
+// Drains the body chunk by chunk so the UI can draw a live gauge; the return value is the total received.
+async fn stream_download(
+  response: reqwest::Response,
+  expected: usize,
+  progress: Option<&AppHandle>,
+  provider: &str,
+) -> usize {
+  let expected = response.content_length().map(|length| length as usize).unwrap_or(expected).max(1);
+  let interval = Duration::from_millis(SPEEDTEST_PROGRESS_INTERVAL_MS);
+  let mut stream = response.bytes_stream();
+  let mut received = 0usize;
+  let mut window_bytes = 0usize;
+  let mut window_start = Instant::now();
+  let emit = |received: usize, window_bytes: usize, window_secs: f64| {
+    if let Some(app) = progress {
+      let mbps = if window_secs > 0.0 { (window_bytes as f64 * 8.0) / window_secs / 1_000_000.0 } else { 0.0 };
+      let _ = app.emit_all(
+        "speedtest-progress",
+        SpeedTestProgress {
+          provider: provider.to_string(),
+          phase: "download".to_string(),
+          mbps: round2(mbps),
+          percent: round2((received as f64 / expected as f64 * 100.0).min(100.0)),
+          bytes: received as u64,
+        },
+      );
+    }
+  };
+  while let Some(chunk) = stream.next().await {
+    let Ok(chunk) = chunk else {
+      break;
+    };
+    received += chunk.len();
+    window_bytes += chunk.len();
+    let window = window_start.elapsed();
+    if window >= interval {
+      emit(received, window_bytes, window.as_secs_f64());
+      window_bytes = 0;
+      window_start = Instant::now();
+    }
+  }
+  emit(received, window_bytes, window_start.elapsed().as_secs_f64());
+  received
+}
+
+async fn measure_download_cloudflare(
+  client: &HttpClient,
+  plan: &SpeedTestPlan,
+  progress: Option<&AppHandle>,
+) -> (f64, usize) {
+  let start = Instant::now();
+  let response = client
+    .get(format!("{}/__down?bytes={}", CLOUDFLARE_BASE, plan.download_bytes))
+    .timeout(Duration::from_secs(SPEEDTEST_TRANSFER_TIMEOUT_SECS))
+    .send()
+    .await;
+  let Ok(response) = response else {
+    return (0.0, 0);
+  };
+  let received = stream_download(response, plan.download_bytes, progress, "cloudflare").await;
+  (transfer_mbps(received, start.elapsed().as_secs_f64(), plan.setup_secs), received)
+}
+
+async fn measure_download_url(
+  client: &HttpClient,
+  plan: &SpeedTestPlan,
+  url: &str,
+  progress: Option<&AppHandle>,
+  provider: &str,
+) -> (f64, usize) {
+  let start = Instant::now();
+  let mut request = client.get(url).timeout(Duration::from_secs(SPEEDTEST_TRANSFER_TIMEOUT_SECS));
+  // Mirrors only host a 100 MB file and custom endpoints are unknown, so both are capped to the planned size.
+  if plan.lite || plan.sized || url != HETZNER_DOWNLOAD_URL {
+    request = request.header("Range", format!("bytes=0-{}", plan.download_bytes - 1));
+  }
+  let response = request.send().await;
+  let Ok(response) = response else {
+    return (0.0, 0);
+  };
+  let received = stream_download(response, plan.download_bytes, progress, provider).await;
+  (transfer_mbps(received, start.elapsed().as_secs_f64(), plan.setup_secs), received)
+}
+
+// hyper only polls the body once the connection is up and pulls each chunk after flushing the previous one,
+// so the clock starts at the first chunk and the count tracks bytes actually handed to the socket.
+async fn stream_upload(client: &HttpClient, url: &str, plan: &SpeedTestPlan) -> (f64, usize) {
+  let started: Arc<OnceLock<Instant>> = Arc::new(OnceLock::new());
+  let sent = Arc::new(AtomicUsize::new(0));
+  let total = plan.upload_bytes;
+  let chunks = (0..total).step_by(SPEEDTEST_UPLOAD_CHUNK_BYTES).map(move |offset| {
+    (total - offset).min(SPEEDTEST_UPLOAD_CHUNK_BYTES)
+  });
+  let body_started = started.clone();
+  let body_sent = sent.clone();
+  let body = futures_util::stream::iter(chunks).map(move |len| {
+    body_started.get_or_init(Instant::now);
+    body_sent.fetch_add(len, Ordering::Relaxed);
+    Ok::<_, std::io::Error>(&SPEEDTEST_UPLOAD_CHUNK[..len])
+  });
+  let response = client
+    .post(url)
+    .timeout(Duration::from_secs(SPEEDTEST_TRANSFER_TIMEOUT_SECS))
+    .header("Content-Length", total)
+    .body(reqwest::Body::wrap_stream(body))
+    .send()
+    .await;
+  let Some(start) = started.get().copied() else {
+    return (0.0, 0);
+  };
+  if response.is_err() {
+    return (0.0, 0);
+  }
+  let sent = sent.load(Ordering::Relaxed);
+  (transfer_mbps(sent, start.elapsed().as_secs_f64(), plan.setup_secs), sent)
+}
+
+async fn measure_upload_cloudflare(client: &HttpClient, plan: &SpeedTestPlan) -> (f64, usize) {
+  stream_upload(client, &format!("{}/__up", CLOUDFLARE_BASE), plan).await
+}
+
+fn extract_ip_from_trace(body: &str) -> Option<String> {
+  for line in body.lines() {
+    if let Some(value) = line.strip_prefix("ip=") {
+      let trimmed = value.trim();
+      if !trimmed.is_empty() {
+        return Some(trimmed.to_string());
+      }
+    }
+  }
+  None
+}
+
+fn extract_country_from_trace(body: &str) -> Option<String> {
+  for line in body.lines() {
+    if let Some(value) = line.strip_prefix("loc=") {
+      let trimmed = value.trim();
+      if !trimmed.is_empty() {
+        return Some(trimmed.to_string());
+      }
+    }
+  }
+  None
+}
+
+// ipwho.is answers quota and lookup failures with 200 and `success: false`, so that flag is checked first.
+fn extract_ipwhois_info(body: &str) -> Result<PublicIpInfo, ErrorDetail> {
+  let value = serde_json::from_str::<serde_json::Value>(body)
+    .map_err(|error| (ErrorCode::InvalidResponse, Some(error.to_string())))?;
+  if value.get("success").and_then(|item| item.as_bool()) == Some(false) {
+    let message = value.get("message").and_then(|item| item.as_str()).unwrap_or("lookup failed").to_string();
+    let code = if message.to_lowercase().contains("limit") {
+      ErrorCode::RateLimited
+    } else {
+      ErrorCode::RequestFailed
+    };
+    return Err((code, Some(message)));
+  }
+  let text = |item: Option<&serde_json::Value>| {
+    item
+      .and_then(|item| item.as_str())
+      .map(|item| item.trim().to_string())
+      .filter(|item| !item.is_empty())
+  };
+  let connection = value.get("connection");
+  Ok(PublicIpInfo {
+    ip: text(value.get("ip")),
+    country_code: text(value.get("country_code").or_else(|| value.get("countryCode"))),
+    country_name: text(value.get("country")),
+    city: text(value.get("city")),
+    region: text(value.get("region")),
+    isp: text(connection.and_then(|item| item.get("isp"))),
+    asn: connection.and_then(|item| item.get("asn")).and_then(|item| item.as_u64()),
+    org: text(connection.and_then(|item| item.get("org"))),
+    latitude: value.get("latitude").and_then(|item| item.as_f64()),
+    longitude: value.get("longitude").and_then(|item| item.as_f64()),
+    error: None,
+    error_message: None,
+  })
+}
+
+fn extract_ip_country_from_ipwhois(body: &str) -> (String, String) {
+  match extract_ipwhois_info(body) {
+    Ok(info) => (
+      info.ip.unwrap_or_else(|| "N/A".to_string()),
+      info.country_code.unwrap_or_else(|| "N/A".to_string()),
+    ),
+    Err(_) => ("N/A".to_string(), "N/A".to_string()),
+  }
+}
+
+#[derive(Default)]
+struct IpLocation {
+  city: Option<String>,
+  region: Option<String>,
+  latitude: Option<f64>,
+  longitude: Option<f64>,
+}
+
+struct PublicIpLookup {
+  ip: String,
+  country: String,
+  provider: String,
+  location: IpLocation,
+}
+
+fn extract_location_from_json(body: &str, region_key: &str) -> IpLocation {
+  let value = match serde_json::from_str::<serde_json::Value>(body) {
+    Ok(value) => value,
+    Err(_) => return IpLocation::default(),
+  };
+  let text = |key: &str| {
+    value
+      .get(key)
+      .and_then(|item| item.as_str())
+      .map(|item| item.trim().to_string())
+      .filter(|item| !item.is_empty())
+  };
+  let number = |key: &str| value.get(key).and_then(|item| item.as_f64());
+  // ipinfo.io reports coordinates as a single "lat,long" string.
+  let (latitude, longitude) = match text("loc").and_then(|loc| {
+    let (lat, long) = loc.split_once(',')?;
+    Some((lat.trim().parse().ok()?, long.trim().parse().ok()?))
+  }) {
+    Some((lat, long)) => (Some(lat), Some(long)),
+    None => (number("latitude"), number("longitude")),
+  };
+  IpLocation {
+    city: text("city"),
+    region: text(region_key),
+    latitude,
+    longitude,
+  }
+}
+
+fn extract_ip_country_from_json(body: &str, ip_key: &str, country_key: &str) -> (String, String) {
+  if let Ok(value) = serde_json::from_str::<serde_json::Value>(body) {
+    let field = |key: &str| {
+      value
+        .get(key)
+        .and_then(|item| item.as_str())
+        .unwrap_or("N/A")
+        .to_string()
+    };
+    return (field(ip_key), field(country_key));
+  }
+  ("N/A".to_string(), "N/A".to_string())
+}
+
+async fn lookup_public_ip(client: &HttpClient) -> Option<PublicIpLookup> {
+  for provider in PUBLIC_IP_PROVIDERS {
+    let url = match provider {
+      "ipwho.is" => IPWHOIS_URL.to_string(),
+      "ipinfo.io" => IPINFO_URL.to_string(),
+      "ifconfig.co" => IFCONFIG_URL.to_string(),
+      _ => format!("{}/cdn-cgi/trace", CLOUDFLARE_BASE),
+    };
+    let response = client
+      .get(url)
+      .header("User-Agent", "PulseNet")
+      .header("Accept", "application/json")
+      .timeout(Duration::from_millis(PUBLIC_IP_PROVIDER_TIMEOUT_MS))
+      .send()
+      .await;
+    let body = match response {
+      Ok(resp) if resp.status().is_success() => resp.text().await.unwrap_or_default(),
+      _ => continue,
+    };
+    let (ip, country) = match provider {
+      "ipwho.is" => extract_ip_country_from_ipwhois(&body),
+      "ipinfo.io" => extract_ip_country_from_json(&body, "ip", "country"),
+      "ifconfig.co" => extract_ip_country_from_json(&body, "ip", "country_iso"),
+      _ => (
+        extract_ip_from_trace(&body).unwrap_or_else(|| "N/A".to_string()),
+        extract_country_from_trace(&body).unwrap_or_else(|| "N/A".to_string()),
+      ),
+    };
+    let location = match provider {
+      "ipwho.is" | "ipinfo.io" => extract_location_from_json(&body, "region"),
+      "ifconfig.co" => extract_location_from_json(&body, "region_name"),
+      _ => IpLocation::default(),
+    };
+    if ip.parse::<std::net::IpAddr>().is_ok() {
+      return Some(PublicIpLookup {
+        ip,
+        country,
+        provider: provider.to_string(),
+        location,
+      });
+    }
+  }
+  None
+}
+
+fn split_public_ip_lookup(lookup: Option<PublicIpLookup>) -> (String, String, Option<String>) {
+  match lookup {
+    Some(found) => (found.ip, found.country, Some(found.provider)),
+    None => ("N/A".to_string(), "N/A".to_string(), None),
+  }
+}
+
+// RFC 6598 shared address space, reserved for carrier-grade NAT.
+fn is_shared_address_space(ip: &std::net::Ipv4Addr) -> bool {
+  let octets = ip.octets();
+  octets[0] == 100 && (64..=127).contains(&octets[1])
+}
+
+fn is_local_address(ip: &std::net::IpAddr) -> bool {
+  match ip {
+    std::net::IpAddr::V4(v4) => v4.is_private() || v4.is_loopback() || v4.is_link_local(),
+    // Loopback, unique local (fc00::/7) and link local (fe80::/10).
+    std::net::IpAddr::V6(v6) => {
+      v6.is_loopback() || (v6.segments()[0] & 0xfe00) == 0xfc00 || (v6.segments()[0] & 0xffc0) == 0xfe80
+    }
+  }
+}
+
+// CHAOS-class TXT version.bind is answered by dnsmasq, unbound and BIND with their version.
+async fn query_resolver_version(server: SocketAddr) -> Option<String> {
+  let mut query = build_dns_query("version.bind", RecordType::TXT, true).ok()?;
+  for question in query.queries_mut() {
+    question.set_query_class(DNSClass::CH);
+  }
+  let response = send_dns_query(server, &query).await.ok()?;
+  response.message.answers().iter().find_map(|record| match record.data() {
+    Some(RData::TXT(txt)) => Some(txt.to_string()),
+    _ => None,
+  })
+}
+
+async fn resolve_addresses(server: SocketAddr, domain: &str) -> Option<Vec<String>> {
+  let query = build_dns_query(domain, RecordType::A, true).ok()?;
+  let response = send_dns_query(server, &query).await.ok()?;
+  Some(answer_addresses(&response.message))
+}
+
+#[tauri::command]
+async fn detect_local_resolver() -> LocalResolverResult {
+  let mut result = LocalResolverResult {
+    resolver: None,
+    is_local: false,
+    local_forwarder: false,
+    software: None,
+    pi_hole: false,
+    blocks_ads: None,
+    evidence: vec![],
+    error: None,
+    error_message: None,
+  };
+  let servers = tauri::async_runtime::spawn_blocking(effective_dns_servers)
+    .await
+    .unwrap_or_default();
+  let Some(server) = servers.first().copied() else {
+    result.error = Some(ErrorCode::LookupFailed);
+    result.error_message = Some("No resolver found in the system configuration".to_string());
+    return result;
+  };
+  result.resolver = Some(server.ip().to_string());
+  result.is_local = is_local_address(&server.ip());
+  if !result.is_local {
+    result.evidence.push(format!("{} is a public address", server.ip()));
+    return result;
+  }
+  result.evidence.push(format!("{} is a private or loopback address", server.ip()));
+
+  let reference = parse_dns_server_socket(LOCAL_RESOLVER_REFERENCE);
+  let (software, pi_hole, local_block, reference_block) = tokio::join!(
+    query_resolver_version(server),
+    resolve_addresses(server, "pi.hole"),
+    resolve_addresses(server, LOCAL_RESOLVER_BLOCKLIST_DOMAIN),
+    async {
+      match reference {
+        Some(reference) => resolve_addresses(reference, LOCAL_RESOLVER_BLOCKLIST_DOMAIN).await,
+        None => None,
+      }
+    }
+  );
+  let answered = software.is_some() || local_block.is_some();
+  if let Some(software) = software {
+    result.evidence.push(format!("version.bind reports \"{}\"", software));
+    result.pi_hole |= software.to_lowercase().contains("pi-hole");
+    result.software = Some(software);
+  }
+  if pi_hole.is_some_and(|addresses| !addresses.is_empty()) {
+    result.pi_hole = true;
+    result.evidence.push("pi.hole resolves".to_string());
+  }
+  // Compare with a public resolver so an ordinary NXDOMAIN isn't mistaken for filtering.
+  if let (Some(local), Some(public)) = (local_block, reference_block) {
+    let sinkholed = local.is_empty()
+      || local
+        .iter()
+        .all(|address| address == "0.0.0.0" || address == "::" || address.starts_with("127."));
+    let blocks = sinkholed && !public.is_empty();
+    if blocks {
+      result.evidence.push(format!("{} is blocked", LOCAL_RESOLVER_BLOCKLIST_DOMAIN));
+    }
+    result.blocks_ads = Some(blocks);
+  }
+  // Loopback resolvers are typically systemd-resolved or dnscrypt-proxy, still a local forwarder.
+  result.local_forwarder = answered;
+  if !answered {
+    result.evidence.push(format!("{} did not answer queries", server.ip()));
+  }
+  result
+}
+
+#[tauri::command]
+async fn detect_cgnat() -> CgnatResult {
+  let mut result = CgnatResult {
+    verdict: "unknown".to_string(),
+    local_address: None,
+    gateway: None,
+    public_ip: None,
+    evidence: vec![],
+    error: None,
+    error_message: None,
+  };
+  let local = local_address_towards(std::net::IpAddr::V4(std::net::Ipv4Addr::new(1, 1, 1, 1)));
+  let gateway = tauri::async_runtime::spawn_blocking(|| {
+    read_default_routes()
+      .into_iter()
+      .find(|route| route.family == "ipv4")
+      .map(|route| route.gateway)
+  })
+  .await
+  .ok()
+  .flatten();
+  // Direct and IPv4-only, so the public address is comparable with the local one rather than a proxy's.
+  let client = family_pinned_client(false, None);
+  let public = lookup_public_ip(&client).await.map(|found| found.ip);
+  result.local_address = local.map(|ip| ip.to_string());
+  result.gateway = gateway.clone();
+  result.public_ip = public.clone();
+
+  let local_v4 = match local {
+    Some(std::net::IpAddr::V4(ip)) => Some(ip),
+    _ => None,
+  };
+  let gateway_v4 = gateway.as_deref().and_then(|value| value.parse::<std::net::Ipv4Addr>().ok());
+  let public_v4 = public.as_deref().and_then(|value| value.parse::<std::net::Ipv4Addr>().ok());
+  if local_v4.is_none() {
+    result.evidence.push("No IPv4 route to the internet was found".to_string());
+  }
+  if public_v4.is_none() {
+    result.evidence.push("The public IPv4 address could not be determined".to_string());
+  }
+
+  let local_shared = local_v4.as_ref().is_some_and(is_shared_address_space);
+  let gateway_shared = gateway_v4.as_ref().is_some_and(is_shared_address_space);
+  if local_shared {
+    result.evidence.push(format!(
+      "This device holds {} from the 100.64.0.0/10 carrier NAT range",
+      local_v4.map(|ip| ip.to_string()).unwrap_or_default()
+    ));
+  }
+  if gateway_shared {
+    result.evidence.push(format!(
+      "The default gateway {} is in the 100.64.0.0/10 carrier NAT range",
+      gateway.clone().unwrap_or_default()
+    ));
+  }
+  if let Some(ip) = gateway_v4.filter(|ip| ip.is_private()) {
+    result.evidence.push(format!("The default gateway {} is a private address", ip));
+  }
+
+  result.verdict = match (local_v4, public_v4) {
+    _ if local_shared || gateway_shared => "cgnat",
+    (Some(local), Some(public)) if local == public => {
+      result.evidence.push("The device holds the public address directly, so there is no NAT".to_string());
+      "no-nat"
+    }
+    (Some(local), Some(public)) => {
+      result.evidence.push(format!("Traffic from {} leaves the network as {}", local, public));
+      if local.is_private() {
+        // The router's WAN address isn't visible from here, so a second NAT can't be ruled out.
+        result
+          .evidence
+          .push("Behind a home router; if its WAN address differs from the public IP, the ISP adds CGNAT".to_string());
+      }
+      "nat"
+    }
+    _ => "unknown",
+  }
+  .to_string();
+  if result.verdict == "unknown" && local_v4.is_none() && public_v4.is_none() {
+    result.error = Some(ErrorCode::RequestFailed);
+  }
+  result
+}
+
+#[tauri::command]
+async fn get_public_network_info(app: AppHandle) -> PublicNetworkInfo {
+  let (client, _) = shared_http_client(&app.state());
+  match lookup_public_ip(&client).await {
+    Some(found) => PublicNetworkInfo {
+      ip: found.ip,
+      country: found.country,
+      provider: Some(found.provider),
+      city: found.location.city,
+      region: found.location.region,
+      latitude: found.location.latitude,
+      longitude: found.location.longitude,
+      error: None,
+      error_message: None,
+    },
+    None => PublicNetworkInfo {
+      ip: "N/A".to_string(),
+      country: "N/A".to_string(),
+      provider: None,
+      city: None,
+      region: None,
+      latitude: None,
+      longitude: None,
+      error: Some(ErrorCode::RequestFailed),
+      error_message: Some("Failed to fetch network info".to_string()),
+    },
+  }
+}
+
+#[tauri::command]
+async fn get_public_ip_info(app: AppHandle) -> PublicIpInfo {
+  let state: State<AppState> = app.state();
+  let (client, _) = shared_http_client(&state);
+  let failure = |error: ErrorCode, error_message: Option<String>| PublicIpInfo {
+    error: Some(error),
+    error_message,
+    ..Default::default()
+  };
+  let response = match client
+    .get(IPWHOIS_URL)
+    .header("Accept", "application/json")
+    .timeout(Duration::from_millis(PUBLIC_IP_PROVIDER_TIMEOUT_MS))
+    .send()
+    .await
+  {
+    Ok(response) => response,
+    Err(error) => {
+      let code = if error.is_timeout() { ErrorCode::Timeout } else { ErrorCode::RequestFailed };
+      return failure(code, Some(error.to_string()));
+    }
+  };
+  if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+    return failure(ErrorCode::RateLimited, Some("ipwho.is rate limit reached; try again later".to_string()));
+  }
+  let body = response.text().await.unwrap_or_default();
+  match extract_ipwhois_info(&body) {
+    Ok(info) if info.ip.is_some() => info,
+    Ok(_) => failure(ErrorCode::InvalidResponse, Some("ipwho.is returned no address".to_string())),
+    Err((code, message)) => failure(code, message),
+  }
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = if year >= 0 { year } else { year - 399 } / 400;
+  let year_of_era = year - era * 400;
+  let month_index = (month + 9) % 12;
+  let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  era * 146097 + day_of_era - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+  let days = days + 719468;
+  let era = if days >= 0 { days } else { days - 146096 } / 146097;
+  let day_of_era = days - era * 146097;
+  let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+  let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+  (year, month, day)
+}
+
+fn format_utc_timestamp(millis: u128) -> String {
+  let seconds = (millis / 1000) as i64;
+  let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+  let clock = seconds.rem_euclid(86400);
+  format!(
+    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+    year,
+    month,
+    day,
+    clock / 3600,
+    clock % 3600 / 60,
+    clock % 60,
+    millis % 1000
+  )
+}
+
+fn parse_http_date(value: &str) -> Option<i64> {
+  // IMF-fixdate, e.g. "Sun, 06 Nov 1994 08:49:37 GMT".
+  let parts: Vec<&str> = value.split_whitespace().collect();
+  if parts.len() != 6 || parts[5] != "GMT" {
+    return None;
+  }
+  let day = parts[1].parse::<i64>().ok()?;
+  let month = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"]
+    .iter()
+    .position(|name| *name == parts[2])? as i64
+    + 1;
+  let year = parts[3].parse::<i64>().ok()?;
+  let clock: Vec<i64> = parts[4]
+    .split(':')
+    .map(|part| part.parse::<i64>())
+    .collect::<Result<Vec<i64>, _>>()
+    .ok()?;
+  if clock.len() != 3 {
+    return None;
+  }
+  Some(days_from_civil(year, month, day) * 86400 + clock[0] * 3600 + clock[1] * 60 + clock[2])
+}
+
+#[tauri::command]
+async fn check_time_sync(app: AppHandle) -> TimeSyncResult {
+  let (client, _) = shared_http_client(&app.state());
+  let source = format!("{}/cdn-cgi/trace", CLOUDFLARE_BASE);
+  let sent_at = now_millis() as i64;
+  let start = Instant::now();
+  let response = client
+    .head(&source)
+    .header("User-Agent", "PulseNet")
+    .timeout(Duration::from_secs(5))
+    .send()
+    .await;
+  let round_trip_ms = start.elapsed().as_secs_f64() * 1000.0;
+  let failed = |code: ErrorCode, message: Option<String>| TimeSyncResult {
+    offset_ms: 0,
+    round_trip_ms: (round_trip_ms * 100.0).round() / 100.0,
+    server_time: String::new(),
+    source: source.clone(),
+    warning: None,
+    error: Some(code),
+    error_message: message,
+  };
+  let response = match response {
+    Ok(response) => response,
+    Err(error) => return failed(ErrorCode::RequestFailed, Some(error.to_string())),
+  };
+  let server_time = response
+    .headers()
+    .get("date")
+    .and_then(|value| value.to_str().ok())
+    .unwrap_or("")
+    .to_string();
+  let server_secs = match parse_http_date(&server_time) {
+    Some(secs) => secs,
+    None => return failed(ErrorCode::InvalidResponse, Some("missing or malformed Date header".to_string())),
+  };
+
+  // The Date header has one-second resolution and is stamped somewhere inside the
+  // round trip, so compare it against the midpoint of the request.
+  let local_ms = sent_at + (round_trip_ms / 2.0) as i64;
+  let offset_ms = server_secs * 1000 + 500 - local_ms;
+  let warning = if offset_ms.abs() > TIME_SYNC_WARN_MS {
+    Some(format!(
+      "System clock is off by about {} seconds; DNSSEC and TLS checks may fail",
+      offset_ms.abs() / 1000
+    ))
+  } else {
+    None
+  };
+
+  TimeSyncResult {
+    offset_ms,
+    round_trip_ms: (round_trip_ms * 100.0).round() / 100.0,
+    server_time,
+    source: source.clone(),
+    warning,
+    error: None,
+    error_message: None,
+  }
+}
+
+fn unix_seconds_now() -> f64 {
+  std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .map(|duration| duration.as_secs_f64())
+    .unwrap_or(0.0)
+}
+
+fn ntp_timestamp(bytes: &[u8]) -> f64 {
+  let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64;
+  let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as f64;
+  seconds + fraction / 4_294_967_296.0 - NTP_UNIX_EPOCH_DELTA
+}
+
+fn encode_ntp_timestamp(unix_seconds: f64) -> [u8; 8] {
+  let ntp = unix_seconds + NTP_UNIX_EPOCH_DELTA;
+  let seconds = ntp.trunc() as u32;
+  let fraction = (ntp.fract() * 4_294_967_296.0) as u32;
+  let mut bytes = [0u8; 8];
+  bytes[..4].copy_from_slice(&seconds.to_be_bytes());
+  bytes[4..].copy_from_slice(&fraction.to_be_bytes());
+  bytes
+}
+
+// Returns (offset seconds, round-trip delay seconds, stratum, server transmit time).
+async fn sntp_query(addr: SocketAddr) -> Result<(f64, f64, u8, f64), ErrorDetail> {
+  let bind = if addr.is_ipv6() { "[::]:0" } else { "0.0.0.0:0" };
+  let socket = tokio::net::UdpSocket::bind(bind)
+    .await
+    .map_err(|error| (ErrorCode::RequestFailed, Some(error.to_string())))?;
+  let mut request = [0u8; 48];
+  // LI = 0, version 4, mode 3 (client).
+  request[0] = 0x23;
+  let t1 = unix_seconds_now();
+  let origin = encode_ntp_timestamp(t1);
+  request[40..48].copy_from_slice(&origin);
+  socket
+    .send_to(&request, addr)
+    .await
+    .map_err(|error| (ErrorCode::RequestFailed, Some(error.to_string())))?;
+  let mut buffer = [0u8; 128];
+  let wait = Duration::from_millis(NTP_TIMEOUT_MS);
+  let started = Instant::now();
+  while let Some(remaining) = wait.checked_sub(started.elapsed()) {
+    let (len, from) = match timeout(remaining, socket.recv_from(&mut buffer)).await {
+      Ok(Ok(received)) => received,
+      Ok(Err(error)) => return Err((ErrorCode::RequestFailed, Some(error.to_string()))),
+      Err(_) => break,
+    };
+    let t4 = unix_seconds_now();
+    // Ignore stray packets and replies that don't echo our transmit timestamp.
+    if from.ip() != addr.ip() || len < 48 || buffer[0] & 0x07 != 4 || buffer[24..32] != origin {
+      continue;
+    }
+    let stratum = buffer[1];
+    if stratum == 0 {
+      return Err((ErrorCode::InvalidResponse, Some("server sent a kiss-of-death reply".to_string())));
+    }
+    let t2 = ntp_timestamp(&buffer[32..40]);
+    let t3 = ntp_timestamp(&buffer[40..48]);
+    let offset = ((t2 - t1) + (t3 - t4)) / 2.0;
+    let delay = (t4 - t1) - (t3 - t2);
+    return Ok((offset, delay.max(0.0), stratum, t3));
+  }
+  Err((ErrorCode::Timeout, None))
+}
+
+#[tauri::command]
+async fn check_ntp_offset(server: Option<String>) -> NtpOffsetResult {
+  let server = server
+    .map(|value| value.trim().to_string())
+    .filter(|value| !value.is_empty())
+    .unwrap_or_else(|| NTP_DEFAULT_SERVER.to_string());
+  let mut result = NtpOffsetResult {
+    server: server.clone(),
+    offset_ms: None,
+    delay_ms: None,
+    stratum: None,
+    server_time: None,
+    exceeds_threshold: false,
+    warning: None,
+    error: None,
+    error_message: None,
+  };
+  let target = if server.contains(':') && server.parse::<std::net::IpAddr>().is_err() {
+    server.clone()
+  } else if let Ok(ip) = server.parse::<std::net::IpAddr>() {
+    SocketAddr::new(ip, 123).to_string()
+  } else {
+    format!("{}:123", server)
+  };
+  let addr = match lookup_host(target).await.ok().and_then(|mut addrs| addrs.next()) {
+    Some(addr) => addr,
+    None => {
+      result.error = Some(ErrorCode::ResolveFailed);
+      return result;
+    }
+  };
+  match sntp_query(addr).await {
+    Ok((offset, delay, stratum, server_seconds)) => {
+      let offset_ms = offset * 1000.0;
+      result.offset_ms = Some((offset_ms * 100.0).round() / 100.0);
+      result.delay_ms = Some((delay * 100_000.0).round() / 100.0);
+      result.stratum = Some(stratum);
+      result.server_time = Some(format_utc_timestamp((server_seconds * 1000.0).max(0.0) as u128));
+      result.exceeds_threshold = offset_ms.abs() > NTP_OFFSET_WARN_MS;
+      if result.exceeds_threshold {
+        result.warning = Some(format!(
+          "System clock is off by {:.1} seconds; DNSSEC and TLS checks may fail",
+          offset_ms.abs() / 1000.0
+        ));
+      }
+    }
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+    }
+  }
+  result
+}
+
+async fn measure_tcp_connect(addr: SocketAddr, wait: Duration) -> Result<f64, ErrorDetail> {
+  let start = Instant::now();
+  match timeout(wait, tokio::net::TcpStream::connect(addr)).await {
+    Ok(Ok(_stream)) => Ok(start.elapsed().as_secs_f64() * 1000.0),
+    Ok(Err(error)) => Err((ErrorCode::RequestFailed, Some(error.to_string()))),
+    Err(_) => Err((ErrorCode::Timeout, None)),
+  }
+}
+
+#[tauri::command]
+async fn scan_ports(
+  app: AppHandle,
+  host: String,
+  ports: Vec<u16>,
+  timeout_ms: Option<u64>,
+  operation_id: Option<u64>,
+) -> Result<PortScanResult, ErrorCode> {
+  run_cancellable(&app, operation_id, run_port_scan(host, ports, timeout_ms)).await
+}
+
+async fn run_port_scan(host: String, ports: Vec<u16>, timeout_ms: Option<u64>) -> PortScanResult {
+  let host = host.trim().to_string();
+  let mut result = PortScanResult {
+    host: host.clone(),
+    target: None,
+    ports: vec![],
+    timed_out: false,
+    error: None,
+    error_message: None,
+  };
+  let mut ports: Vec<u16> = ports.into_iter().filter(|port| *port != 0).collect();
+  ports.sort_unstable();
+  ports.dedup();
+  if host.is_empty() || ports.is_empty() {
+    result.error = Some(ErrorCode::InvalidInput);
+    return result;
+  }
+  let addr = match resolve_ping_target(&host).await {
+    Ok(addr) => addr,
+    Err((code, message)) => {
+      result.error = Some(code);
+      result.error_message = message;
+      return result;
+    }
+  };
+  result.target = Some(addr.ip().to_string());
+
+  let wait = Duration::from_millis(timeout_ms.unwrap_or(PORT_SCAN_DEFAULT_TIMEOUT_MS).clamp(100, 10_000));
+  let permits = Arc::new(tokio::sync::Semaphore::new(PORT_SCAN_MAX_IN_FLIGHT));
+  let mut probes = tokio::task::JoinSet::new();
+  for port in ports.iter().copied() {
+    let permits = permits.clone();
+    // Copy the resolved address so an IPv6 scope id survives.
+    let mut target = addr;
+    target.set_port(port);
+    probes.spawn(async move {
+      let _permit = permits.acquire_owned().await;
+      (port, measure_tcp_connect(target, wait).await.ok().map(round2))
+    });
+  }
+  let deadline = tokio::time::Instant::now() + Duration::from_millis(PORT_SCAN_DEADLINE_MS);
+  // Ports that finished, with their connect time when open.
+  let mut finished: HashMap<u16, Option<f64>> = HashMap::new();
+  loop {
+    match tokio::time::timeout_at(deadline, probes.join_next()).await {
+      Ok(Some(Ok((port, latency)))) => {
+        finished.insert(port, latency);
+      }
+      Ok(Some(Err(_))) => {}
+      Ok(None) => break,
+      Err(_) => {
+        result.timed_out = true;
+        probes.abort_all();
+        break;
+      }
+    }
+  }
+  result.ports = ports
+    .into_iter()
+    .map(|port| PortStatus {
+      port,
+      open: finished.get(&port).map(Option::is_some),
+      latency_ms: finished.get(&port).copied().flatten(),
+    })
+    .collect();
+  result
+}
+
+// Like measure_tcp_connect, but also reports how long a failed attempt took.
+async fn timed_family_connect(addr: Option<SocketAddr>) -> Option<(Result<f64, ErrorCode>, f64)> {
+  let addr = addr?;
+  let start = Instant::now();
+  let outcome = measure_tcp_connect(addr, Duration::from_millis(HAPPY_EYEBALLS_CONNECT_TIMEOUT_MS))
+    .await
+    .map(round2)
+    .map_err(|(code, _)| code);
+  Some((outcome, round2(start.elapsed().as_secs_f64() * 1000.0)))
+}
+
+#[tauri::command]
+async fn test_happy_eyeballs(host: String, port: Option<u16>) -> HappyEyeballsResult {
+  let port = port.unwrap_or(HAPPY_EYEBALLS_DEFAULT_PORT);
+  let mut result = HappyEyeballsResult {
+    host: host.trim().to_string(),
+    port,
+    ipv4_address: None,
+    ipv6_address: None,
+    ipv4_connect_ms: None,
+    ipv6_connect_ms: None,
+    ipv4_error: None,
+    ipv6_error: None,
+    preferred_family: None,
+    winner: None,
+    margin_ms: None,
+    fallback_penalty_ms: None,
+    verdict: "unknown".to_string(),
+    error: None,
+    error_message: None,
+  };
+  if result.host.is_empty() {
+    result.error = Some(ErrorCode::InvalidInput);
+    return result;
+  }
+  let addrs: Vec<SocketAddr> = match timeout(
+    Duration::from_millis(DNS_TIMEOUT_MS),
+    lookup_host((result.host.clone(), port)),
+  )
+  .await
+  {
+    Ok(Ok(addrs)) => addrs.collect(),
+    Ok(Err(error)) => {
+      result.error = Some(ErrorCode::ResolveFailed);
+      result.error_message = Some(error.to_string());
+      return result;
+    }
+    Err(_) => {
+      result.error = Some(ErrorCode::Timeout);
+      return result;
+    }
+  };
+  // The resolver returns addresses in the order the OS will try them (RFC 6724).
+  result.preferred_family = addrs.first().map(|addr| address_family(&addr.ip()));
+  let ipv4 = addrs.iter().copied().find(|addr| addr.is_ipv4());
+  let ipv6 = addrs.iter().copied().find(|addr| addr.is_ipv6());
+  result.ipv4_address = ipv4.map(|addr| addr.ip().to_string());
+  result.ipv6_address = ipv6.map(|addr| addr.ip().to_string());
+  if ipv4.is_none() && ipv6.is_none() {
+    result.error = Some(ErrorCode::ResolveFailed);
+    return result;
+  }
+
+  let (v4_attempt, v6_attempt) = tokio::join!(timed_family_connect(ipv4), timed_family_connect(ipv6));
+  let mut v4_failed_after = None;
+  let mut v6_failed_after = None;
+  if let Some((outcome, elapsed)) = v4_attempt {
+    match outcome {
+      Ok(ms) => result.ipv4_connect_ms = Some(ms),
+      Err(code) => {
+        result.ipv4_error = Some(code);
+        v4_failed_after = Some(elapsed);
+      }
+    }
+  }
+  if let Some((outcome, elapsed)) = v6_attempt {
+    match outcome {
+      Ok(ms) => result.ipv6_connect_ms = Some(ms),
+      Err(code) => {
+        result.ipv6_error = Some(code);
+        v6_failed_after = Some(elapsed);
+      }
+    }
+  }
+  // Time a client that tries the OS-preferred family first would lose before falling back.
+  result.fallback_penalty_ms = match result.preferred_family.as_deref() {
+    Some("ipv6") => v6_failed_after,
+    _ => v4_failed_after,
+  };
+
+  result.verdict = match (result.ipv4_connect_ms, result.ipv6_connect_ms) {
+    (None, None) => {
+      result.error = Some(ErrorCode::RequestFailed);
+      "unreachable"
+    }
+    (Some(_), None) if ipv6.is_none() => "ipv4-only",
+    (None, Some(_)) if ipv4.is_none() => "ipv6-only",
+    (Some(_), None) => {
+      result.winner = Some("ipv4".to_string());
+      // Only costly when the OS tries the dead family first.
+      if result.preferred_family.as_deref() == Some("ipv6") {
+        "ipv6-broken"
+      } else {
+        "optimal"
+      }
+    }
+    (None, Some(_)) => {
+      result.winner = Some("ipv6".to_string());
+      if result.preferred_family.as_deref() == Some("ipv4") {
+        "ipv4-broken"
+      } else {
+        "optimal"
+      }
+    }
+    (Some(v4_ms), Some(v6_ms)) => {
+      let winner = if v6_ms <= v4_ms { "ipv6" } else { "ipv4" };
+      result.winner = Some(winner.to_string());
+      result.margin_ms = Some(round2((v4_ms - v6_ms).abs()));
+      let preferred_loses_by = match result.preferred_family.as_deref() {
+        Some("ipv6") => v6_ms - v4_ms,
+        _ => v4_ms - v6_ms,
+      };
+      if preferred_loses_by > HAPPY_EYEBALLS_TOLERANCE_MS {
+        "preferred-slower"
+      } else {
+        "optimal"
+      }
+    }
+  }
+  .to_string();
+  result
+}
+
+fn estimate_tcp_loss_percent(throughput_mbps: f64, rtt_ms: f64) -> f64 {
+  // Mathis et al.: throughput <= (MSS / RTT) * (C / sqrt(p)). Solving for p gives an
+  // upper bound on the loss rate that could explain the observed throughput.
+  if throughput_mbps <= 0.0 || rtt_ms <= 0.0 {
+    return 0.0;
+  }
+  let throughput_bytes = throughput_mbps * 1_000_000.0 / 8.0;
+  let rtt_secs = rtt_ms / 1000.0;
+  let ratio = TCP_ASSUMED_MSS_BYTES * 1.22 / (rtt_secs * throughput_bytes);
+  (ratio * ratio * 100.0).min(100.0)
+}
+
+fn tcp_quality_grade(loss_percent: f64) -> &'static str {
+  if loss_percent < 0.1 {
+    "excellent"
+  } else if loss_percent < 0.5 {
+    "good"
+  } else if loss_percent < 2.0 {
+    "fair"
+  } else {
+    "poor"
+  }
+}
+
+#[tauri::command]
+async fn tcp_quality_test(
+  app: AppHandle,
+  host: Option<String>,
+  port: Option<u16>,
+  operation_id: Option<u64>,
+) -> Result<TcpQualityResult, ErrorCode> {
+  let (client, _) = shared_http_client(&app.state());
+  run_cancellable(&app, operation_id, measure_tcp_quality(client, host, port)).await
+}
+
+async fn measure_tcp_quality(client: HttpClient, host: Option<String>, port: Option<u16>) -> TcpQualityResult {
+  let host = host
+    .map(|value| sanitize_domain(&value))
+    .filter(|value| !value.is_empty())
+    .unwrap_or_else(|| TCP_QUALITY_HOST.to_string());
+  let port = port.unwrap_or(443);
+  let mut result = TcpQualityResult {
+    host: host.clone(),
+    port,
+    connect_ms: 0.0,
+    bytes_transferred: 0,
+    throughput_mbps: 0.0,
+    estimated_loss_percent: 0.0,
+    grade: String::new(),
+    error: None,
+    error_message: None,
+  };
+  let addr = match lookup_host(format!("{}:{}", host, port)).await {
+    Ok(mut addrs) => match addrs.next() {
+      Some(addr) => addr,
+      None => {
+        result.error = Some(ErrorCode::ResolveFailed);
+        return result;
+      }
+    },
+    Err(error) => {
+      result.error = Some(ErrorCode::ResolveFailed);
+      result.error_message = Some(error.to_string());
+      return result;
+    }
+  };
+
+  let mut connect_samples = Vec::new();
+  for _ in 0..TCP_QUALITY_CONNECT_SAMPLES {
+    match measure_tcp_connect(addr, Duration::from_secs(3)).await {
+      Ok(elapsed) => connect_samples.push(elapsed),
+      Err((code, message)) => {
+        result.error = Some(code);
+        result.error_message = message;
+      }
+    }
+  }
+  if connect_samples.is_empty() {
+    return result;
+  }
+  result.error = None;
+  result.error_message = None;
+  let connect_ms = connect_samples.iter().cloned().fold(f64::MAX, f64::min);
+  result.connect_ms = round2(connect_ms);
+
+  let scheme = if port == 80 { "http" } else { "https" };
+  let path = if host == TCP_QUALITY_HOST {
+    format!("/__down?bytes={}", TCP_QUALITY_BYTES)
+  } else {
+    "/".to_string()
+  };
+  let response = client
+    .get(format!("{}://{}:{}{}", scheme, host, port, path))
+    .header("User-Agent", "PulseNet")
+    .timeout(Duration::from_secs(30))
+    .send()
+    .await;
+  let mut response = match response {
+    Ok(response) => response,
+    Err(error) => {
+      result.error = Some(ErrorCode::RequestFailed);
+      result.error_message = Some(error.to_string());
+      return result;
+    }
+  };
+  // The clock starts at the first body chunk so the TLS handshake and the server's
+  // time to first byte stay out of the throughput the loss estimate is based on.
+  let mut start = None;
+  let mut transferred = 0usize;
+  while let Ok(Some(chunk)) = response.chunk().await {
+    if start.is_none() {
+      start = Some(Instant::now());
+      continue;
+    }
+    transferred += chunk.len();
+    if transferred >= TCP_QUALITY_BYTES {
+      break;
+    }
+  }
+  let Some(start) = start.filter(|_| transferred >= TCP_QUALITY_MIN_BYTES) else {
+    result.bytes_transferred = transferred;
+    result.error = Some(ErrorCode::InvalidResponse);
+    result.error_message = Some("Response too small to measure throughput".to_string());
+    return result;
+  };
+  let throughput = bytes_to_mbps(transferred as u64, start.elapsed().as_secs_f64());
+  let loss = estimate_tcp_loss_percent(throughput, connect_ms);
+  result.bytes_transferred = transferred;
+  result.throughput_mbps = throughput;
+  result.estimated_loss_percent = (loss * 1000.0).round() / 1000.0;
+  result.grade = tcp_quality_grade(loss).to_string();
+  result
+}
+
+#[cfg(target_os = "windows")]
+fn read_default_routes() -> Vec<DefaultRoute> {
+  let command = "Get-NetRoute -DestinationPrefix '0.0.0.0/0','::/0' -ErrorAction SilentlyContinue | ForEach-Object { [PSCustomObject]@{ NextHop = $_.NextHop; InterfaceAlias = $_.InterfaceAlias; Metric = [int]$_.RouteMetric + [int]$_.InterfaceMetric; Prefix = $_.DestinationPrefix } } | ConvertTo-Json -Compress";
+  let output = match run_powershell(command) {
+    Ok(stdout) => stdout,
+    Err(_) => return vec![],
+  };
+  let parsed = match serde_json::from_str::<serde_json::Value>(&output) {
+    Ok(value) => value,
+    Err(_) => return vec![],
+  };
+  let items = if let Some(array) = parsed.as_array() {
+    array.clone()
+  } else {
+    vec![parsed]
+  };
+  let mut routes: Vec<DefaultRoute> = items
+    .iter()
+    .filter_map(|item| {
+      let gateway = item.get("NextHop").and_then(|value| value.as_str())?.trim().to_string();
+      if gateway.is_empty() || gateway == "0.0.0.0" || gateway == "::" {
+        return None;
+      }
+      let prefix = item.get("Prefix").and_then(|value| value.as_str()).unwrap_or("");
+      Some(DefaultRoute {
+        gateway,
+        interface: item
+          .get("InterfaceAlias")
+          .and_then(|value| value.as_str())
+          .unwrap_or("")
+          .to_string(),
+        metric: item.get("Metric").and_then(|value| value.as_u64()).unwrap_or(0) as u32,
+        family: if prefix.contains(':') { "ipv6" } else { "ipv4" }.to_string(),
+      })
+    })
+    .collect();
+  routes.sort_by_key(|route| route.metric);
+  routes
+}
+
+#[cfg(target_os = "linux")]
+fn read_default_routes() -> Vec<DefaultRoute> {
+  let mut routes = Vec::new();
+  for (flag, family) in [("-4", "ipv4"), ("-6", "ipv6")] {
+    let output = match Command::new("ip").args([flag, "route", "show", "default"]).output() {
+      Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+      Err(_) => continue,
+    };
+    for line in output.lines() {
+      // default via 192.168.1.1 dev wlan0 proto dhcp metric 600
+      let fields: Vec<&str> = line.split_whitespace().collect();
+      let value_after = |key: &str| {
+        fields
+          .iter()
+          .position(|field| *field == key)
+          .and_then(|index| fields.get(index + 1))
+          .map(|value| value.to_string())
+      };
+      let Some(gateway) = value_after("via") else {
+        continue;
+      };
+      routes.push(DefaultRoute {
+        gateway,
+        interface: value_after("dev").unwrap_or_default(),
+        metric: value_after("metric").and_then(|value| value.parse().ok()).unwrap_or(0),
+        family: family.to_string(),
+      });
+    }
+  }
+  routes.sort_by_key(|route| route.metric);
+  routes
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn read_default_routes() -> Vec<DefaultRoute> {
+  let mut routes = Vec::new();
+  for (flag, family) in [("inet", "ipv4"), ("inet6", "ipv6")] {
+    let output = match Command::new("netstat").args(["-rn", "-f", flag]).output() {
+      Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+      Err(_) => continue,
+    };
+    for line in output.lines() {
+      // default            192.168.1.1        UGScg                 en0
+      let fields: Vec<&str> = line.split_whitespace().collect();
+      if fields.len() < 4 || fields[0] != "default" || fields[1].starts_with("link#") {
+        continue;
+      }
+      routes.push(DefaultRoute {
+        gateway: fields[1].split('%').next().unwrap_or("").to_string(),
+        interface: fields[3].to_string(),
+        metric: 0,
+        family: family.to_string(),
+      });
+    }
+  }
+  routes
+}
+
+fn default_gateway() -> Option<String> {
+  let routes = read_default_routes();
+  routes
+    .iter()
+    .find(|route| route.family == "ipv4")
+    .or_else(|| routes.first())
+    .map(|route| route.gateway.clone())
+}
+
+#[tauri::command]
+async fn detect_multiple_default_routes() -> MultipleDefaultRoutesResult {
+  let routes = tauri::async_runtime::spawn_blocking(read_default_routes)
+    .await
+    .unwrap_or_default();
+  if routes.is_empty() {
+    return MultipleDefaultRoutesResult {
+      routes,
+      multiple_ipv4: false,
+      multiple_ipv6: false,
+      equal_metric: false,
+      preferred_interface: None,
+      warning: None,
+      error: Some(ErrorCode::GatewayNotFound),
+      error_message: None,
+    };
+  }
+  let interfaces_for = |family: &str| {
+    let mut interfaces: Vec<&str> = routes
+      .iter()
+      .filter(|route| route.family == family)
+      .map(|route| route.interface.as_str())
+      .collect();
+    interfaces.sort();
+    interfaces.dedup();
+    interfaces.len()
+  };
+  let multiple_ipv4 = interfaces_for("ipv4") > 1;
+  let multiple_ipv6 = interfaces_for("ipv6") > 1;
+  // netstat on macOS doesn't report metrics, so ties can't be detected there.
+  let metrics_known = cfg!(any(target_os = "windows", target_os = "linux"));
+  let equal_metric = metrics_known && ["ipv4", "ipv6"].iter().any(|family| {
+    let family_routes: Vec<&DefaultRoute> = routes.iter().filter(|route| route.family == *family).collect();
+    family_routes.len() > 1
+      && family_routes
+        .iter()
+        .skip(1)
+        .any(|route| route.metric == family_routes[0].metric && route.interface != family_routes[0].interface)
+  });
+  let preferred_interface = routes
+    .iter()
+    .find(|route| route.family == "ipv4")
+    .or_else(|| routes.first())
+    .map(|route| route.interface.clone());
+  let warning = if equal_metric {
+    Some("Several interfaces have default routes with the same metric; traffic may switch between them unpredictably".to_string())
+  } else if multiple_ipv4 || multiple_ipv6 {
+    Some(format!(
+      "More than one interface has a default route; {} is preferred and the others act as fallbacks",
+      preferred_interface.clone().unwrap_or_default()
+    ))
+  } else {
+    None
+  };
+  MultipleDefaultRoutesResult {
+    routes,
+    multiple_ipv4,
+    multiple_ipv6,
+    equal_metric,
+    preferred_interface,
+    warning,
+    error: None,
+    error_message: None,
+  }
+}
+
+fn stun_transaction_id() -> [u8; 12] {
+  let mut id = [0u8; 12];
+  if getrandom::getrandom(&mut id).is_err() {
+    // Still unique per request even without an entropy source.
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    id[..8].copy_from_slice(&(now_millis() as u64).to_be_bytes());
+    id[8..].copy_from_slice(&(COUNTER.fetch_add(1, Ordering::Relaxed) as u32).to_be_bytes());
+  }
+  id
+}
+
+fn build_stun_request(transaction_id: &[u8; 12], change_ip_and_port: bool) -> Vec<u8> {
+  let attributes_len: u16 = if change_ip_and_port { 8 } else { 0 };
+  let mut request = Vec::with_capacity(20 + attributes_len as usize);
+  request.extend_from_slice(&0x0001u16.to_be_bytes());
+  request.extend_from_slice(&attributes_len.to_be_bytes());
+  request.extend_from_slice(&STUN_MAGIC_COOKIE.to_be_bytes());
+  request.extend_from_slice(transaction_id);
+  if change_ip_and_port {
+    // CHANGE-REQUEST (RFC 5780) asking the server to answer from another IP and port.
+    request.extend_from_slice(&0x0003u16.to_be_bytes());
+    request.extend_from_slice(&4u16.to_be_bytes());
+    request.extend_from_slice(&0x0000_0006u32.to_be_bytes());
+  }
+  request
+}
+
+fn parse_stun_address(value: &[u8], xored: bool, transaction_id: &[u8; 12]) -> Option<SocketAddr> {
+  if value.len() < 8 {
+    return None;
+  }
+  let mut port = u16::from_be_bytes([value[2], value[3]]);
+  let address = match value[1] {
+    0x01 => {
+      let mut octets = [value[4], value[5], value[6], value[7]];
+      if xored {
+        port ^= (STUN_MAGIC_COOKIE >> 16) as u16;
+        for (octet, key) in octets.iter_mut().zip(STUN_MAGIC_COOKIE.to_be_bytes()) {
+          *octet ^= key;
+        }
+      }
+      std::net::IpAddr::from(octets)
+    }
+    0x02 if value.len() >= 20 => {
+      let mut octets = [0u8; 16];
+      octets.copy_from_slice(&value[4..20]);
+      if xored {
+        port ^= (STUN_MAGIC_COOKIE >> 16) as u16;
+        let key: Vec<u8> = STUN_MAGIC_COOKIE.to_be_bytes().iter().chain(transaction_id).copied().collect();
+        for (octet, key) in octets.iter_mut().zip(key) {
+          *octet ^= key;
+        }
+      }
+      std::net::IpAddr::from(octets)
+    }
+    _ => return None,
+  };
+  Some(SocketAddr::new(address, port))
+}
+
+// Returns the mapped address and, when the server supports RFC 5780, its alternate address.
+fn parse_stun_response(buffer: &[u8], transaction_id: &[u8; 12]) -> Option<(SocketAddr, Option<SocketAddr>)> {
+  if buffer.len() < 20
+    || u16::from_be_bytes([buffer[0], buffer[1]]) != 0x0101
+    || buffer[4..8] != STUN_MAGIC_COOKIE.to_be_bytes()
+    || &buffer[8..20] != transaction_id
+  {
+    return None;
+  }
+  let end = (20 + u16::from_be_bytes([buffer[2], buffer[3]]) as usize).min(buffer.len());
+  let mut offset = 20;
+  let mut mapped = None;
+  let mut other = None;
+  while offset + 4 <= end {
+    let kind = u16::from_be_bytes([buffer[offset], buffer[offset + 1]]);
+    let len = u16::from_be_bytes([buffer[offset + 2], buffer[offset + 3]]) as usize;
+    let value = buffer.get(offset + 4..offset + 4 + len)?;
+    match kind {
+      // Prefer XOR-MAPPED-ADDRESS; some NATs rewrite the plain MAPPED-ADDRESS payload.
+      0x0020 => mapped = parse_stun_address(value, true, transaction_id).or(mapped),
+      0x0001 if mapped.is_none() => mapped = parse_stun_address(value, false, transaction_id),
+      // OTHER-ADDRESS, or CHANGED-ADDRESS from servers still speaking RFC 3489.
+      0x802C | 0x0005 => other = parse_stun_address(value, false, transaction_id).or(other),
+      _ => {}
+    }
+    offset += 4 + len.div_ceil(4) * 4;
+  }
+  mapped.map(|mapped| (mapped, other))
+}
+
+// Yields the mapped address, the address the reply came from and the server's OTHER-ADDRESS.
+async fn stun_binding(
+  socket: &tokio::net::UdpSocket,
+  server: SocketAddr,
+  change_ip_and_port: bool,
+) -> Option<(SocketAddr, SocketAddr, Option<SocketAddr>)> {
+  let transaction_id = stun_transaction_id();
+  let request = build_stun_request(&transaction_id, change_ip_and_port);
+  socket.send_to(&request, server).await.ok()?;
+  let mut buffer = [0u8; 512];
+  let wait = Duration::from_millis(STUN_TIMEOUT_MS);
+  let started = Instant::now();
+  while let Some(remaining) = wait.checked_sub(started.elapsed()) {
+    let (len, from) = timeout(remaining, socket.recv_from(&mut buffer)).await.ok()?.ok()?;
+    if let Some((mapped, other)) = parse_stun_response(&buffer[..len], &transaction_id) {
+      return Some((mapped, from, other));
+    }
+  }
+  None
+}
+
+async fn resolve_stun_server(host: &str) -> Option<SocketAddr> {
+  lookup_host(host).await.ok()?.find(|addr| addr.is_ipv4())
+}
+
+#[tauri::command]
+async fn check_nat_type() -> NatTypeResult {
+  let mut result = NatTypeResult {
+    nat_type: "unknown".to_string(),
+    mapped_address: None,
+    local_address: None,
+    mapping_behavior: "unknown".to_string(),
+    filtering_behavior: "unknown".to_string(),
+    reasoning: vec![],
+    error: None,
+    error_message: None,
+  };
+  let socket = match tokio::net::UdpSocket::bind("0.0.0.0:0").await {
+    Ok(socket) => socket,
+    Err(error) => {
+      result.error = Some(ErrorCode::RequestFailed);
+      result.error_message = Some(error.to_string());
+      return result;
+    }
+  };
+
+  let mut mappings: Vec<(SocketAddr, SocketAddr, Option<SocketAddr>)> = Vec::new();
+  for host in STUN_SERVERS {
+    let Some(server) = resolve_stun_server(host).await else {
+      continue;
+    };
+    // Two different server IPs are enough to tell mapping behavior apart.
+    if mappings.iter().any(|(known, _, _)| known.ip() == server.ip()) {
+      continue;
+    }
+    if let Some((mapped, _, other)) = stun_binding(&socket, server, false).await {
+      mappings.push((server, mapped, other));
+      if mappings.len() == 2 {
+        break;
+      }
+    }
+  }
+  let Some(&(first_server, first_mapped, _)) = mappings.first() else {
+    result.nat_type = "blocked".to_string();
+    result.error = Some(ErrorCode::Timeout);
+    result.reasoning.push("No STUN server answered; outbound UDP appears to be blocked.".to_string());
+    return result;
+  };
+  result.mapped_address = Some(first_mapped.to_string());
+
+  // The OS picks the outgoing interface for a connected socket, which gives the real local IP.
+  if let Ok(probe) = std::net::UdpSocket::bind("0.0.0.0:0") {
+    if probe.connect(first_server).is_ok() {
+      if let (Ok(local), Ok(bound)) = (probe.local_addr(), socket.local_addr()) {
+        result.local_address = Some(SocketAddr::new(local.ip(), bound.port()).to_string());
+      }
+    }
+  }
+  if result.local_address.as_deref() == Some(first_mapped.to_string().as_str()) {
+    result.nat_type = "open".to_string();
+    result.mapping_behavior = "none".to_string();
+    result.filtering_behavior = "none".to_string();
+    result.reasoning.push("Mapped address matches the local address, so there is no NAT in the path.".to_string());
+    return result;
+  }
+
+  match mappings.get(1) {
+    Some((_, second_mapped, _)) if *second_mapped == first_mapped => {
+      result.mapping_behavior = "endpoint-independent".to_string();
+      result.reasoning.push("The same external port was used for two different servers (cone NAT).".to_string());
+    }
+    Some((_, second_mapped, _)) => {
+      result.mapping_behavior = "address-dependent".to_string();
+      result.nat_type = "strict".to_string();
+      result.reasoning.push(format!(
+        "The external mapping changed between servers ({} vs {}), which is symmetric NAT.",
+        first_mapped, second_mapped
+      ));
+      return result;
+    }
+    None => {
+      result.reasoning.push("Only one STUN server answered, so mapping behavior could not be compared.".to_string());
+    }
+  }
+
+  // A CHANGE-REQUEST only means something to servers that advertise an alternate address;
+  // the others answer from the same address and would make every NAT look restrictive.
+  let mut filtering_server = mappings.iter().find(|(_, _, other)| other.is_some()).map(|(server, _, _)| *server);
+  if filtering_server.is_none() {
+    if let Some(server) = resolve_stun_server(STUN_RFC5780_SERVER).await {
+      if let Some((_, _, Some(_))) = stun_binding(&socket, server, false).await {
+        filtering_server = Some(server);
+      }
+    }
+  }
+  let Some(filtering_server) = filtering_server else {
+    result.reasoning.push("No reachable STUN server supports RFC 5780, so filtering behavior is unknown.".to_string());
+    return result;
+  };
+  match stun_binding(&socket, filtering_server, true).await {
+    Some((_, from, _)) if from.ip() != filtering_server.ip() => {
+      result.filtering_behavior = "endpoint-independent".to_string();
+      result.nat_type = "open".to_string();
+      result.reasoning.push("A reply from an unsolicited address got through, so inbound filtering is permissive (full cone).".to_string());
+    }
+    _ => {
+      result.filtering_behavior = "address-dependent".to_string();
+      result.nat_type = "moderate".to_string();
+      result.reasoning.push("Replies from other addresses were not received; inbound traffic needs an outbound packet first.".to_string());
+    }
+  }
+  result
+}
+
+fn has_ipv6_route() -> bool {
+  // Connecting a UDP socket sends nothing but fails when there is no IPv6 route.
+  std::net::UdpSocket::bind("[::]:0")
+    .and_then(|socket| socket.connect(IPV6_ROUTE_PROBE))
+    .is_ok()
+}
+
+fn address_family(ip: &std::net::IpAddr) -> String {
+  match ip {
+    std::net::IpAddr::V4(_) => "ipv4".to_string(),
+    std::net::IpAddr::V6(v6) if v6.to_ipv4_mapped().is_some() => "ipv4".to_string(),
+    std::net::IpAddr::V6(_) => "ipv6".to_string(),
+  }
+}
+
+#[tauri::command]
+async fn check_ipv6_usage(app: AppHandle) -> Ipv6UsageResult {
+  let url = format!("{}/cdn-cgi/trace", CLOUDFLARE_BASE);
+  let host = CLOUDFLARE_BASE.trim_start_matches("https://");
+  let endpoint_has_ipv6 = lookup_host((host, 443))
+    .await
+    .map(|mut addrs| addrs.any(|addr| addr.is_ipv6()))
+    .unwrap_or(false);
+  let mut result = Ipv6UsageResult {
+    used_family: None,
+    peer_address: None,
+    client_address: None,
+    ipv6_configured: tauri::async_runtime::spawn_blocking(has_ipv6_route)
+      .await
+      .unwrap_or(false),
+    endpoint_has_ipv6,
+    error: None,
+    error_message: None,
+  };
+
+  let (client, _) = shared_http_client(&app.state());
+  let response = match client
+    .get(url)
+    .header("User-Agent", "PulseNet")
+    .timeout(Duration::from_secs(5))
+    .send()
+    .await
+  {
+    Ok(response) => response,
+    Err(error) => {
+      result.error = Some(ErrorCode::RequestFailed);
+      result.error_message = Some(error.to_string());
+      return result;
+    }
+  };
+  if let Some(peer) = response.remote_addr() {
+    result.peer_address = Some(peer.ip().to_string());
+    result.used_family = Some(address_family(&peer.ip()));
+  }
+  // The server's view of our address is the most reliable signal of the family actually used.
+  let body = response.text().await.unwrap_or_default();
+  if let Some(client) = extract_ip_from_trace(&body) {
+    if let Ok(ip) = client.parse::<std::net::IpAddr>() {
+      result.used_family = Some(address_family(&ip));
+    }
+    result.client_address = Some(client);
+  }
+  result
+}
+
+fn classify_interface_kind(name: &str) -> &'static str {
+  let lower = name.to_lowercase();
+  if lower.contains("wi-fi") || lower.contains("wifi") || lower.contains("wlan") || lower.contains("wireless") || lower.contains("airport") {
+    "wifi"
+  } else if lower.contains("wwan") || lower.contains("cellular") || lower.contains("mobile") || lower.contains("gsm") || lower.contains("iphone") {
+    "cellular"
+  } else if lower.contains("ethernet") || lower.contains("thunderbolt") || lower.starts_with("eth") || lower.starts_with("en") {
+    "ethernet"
+  } else {
+    "unknown"
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn read_connection_type() -> ConnectionType {
+  let interface = read_default_routes().into_iter().next().map(|route| route.interface);
+  let mut result = ConnectionType {
+    interface: interface.clone(),
+    kind: "unknown".to_string(),
+    ssid: None,
+    error: None,
+    error_message: None,
+  };
+  let Some(interface) = interface else {
+    result.error = Some(ErrorCode::GatewayNotFound);
+    return result;
+  };
+
+  let command = format!(
+    "Get-NetAdapter -Name '{}' | Select-Object PhysicalMediaType,InterfaceDescription | ConvertTo-Json -Compress",
+    ps_escape_single(&interface)
+  );
+  match run_powershell(&command) {
+    Ok(stdout) => {
+      let parsed = serde_json::from_str::<serde_json::Value>(&stdout).unwrap_or_default();
+      let media = parsed.get("PhysicalMediaType").and_then(|value| value.as_str()).unwrap_or("");
+      let description = parsed.get("InterfaceDescription").and_then(|value| value.as_str()).unwrap_or("");
+      // PhysicalMediaType is e.g. "Native 802.11", "802.3", "Wireless WAN".
+      result.kind = if media.contains("802.11") {
+        "wifi"
+      } else if media.contains("Wireless WAN") || media.contains("Mobile") {
+        "cellular"
+      } else if media.contains("802.3") {
+        "ethernet"
+      } else {
+        classify_interface_kind(description)
+      }
+      .to_string();
+    }
+    Err(message) => {
+      result.error = Some(ErrorCode::CommandFailed);
+      result.error_message = Some(message);
+      return result;
+    }
+  }
+
+  if result.kind == "wifi" {
+    if let Ok(output) = hidden_command("netsh").args(["wlan", "show", "interfaces"]).output() {
+      let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+      result.ssid = stdout.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if key.trim() == "SSID" {
+          Some(value.trim().to_string())
+        } else {
+          None
+        }
+      });
+    }
+  }
+  result
+}
+
+#[cfg(target_os = "linux")]
+fn read_connection_type() -> ConnectionType {
+  let interface = read_default_routes().into_iter().next().map(|route| route.interface);
+  let mut result = ConnectionType {
+    interface: interface.clone(),
+    kind: "unknown".to_string(),
+    ssid: None,
+    error: None,
+    error_message: None,
+  };
+  let Some(interface) = interface else {
+    result.error = Some(ErrorCode::GatewayNotFound);
+    return result;
+  };
+
+  // nmcli -t prints "DEVICE:TYPE:CONNECTION", e.g. "wlan0:wifi:Home".
+  let nmcli = Command::new("nmcli")
+    .args(["-t", "-f", "DEVICE,TYPE,CONNECTION", "device"])
+    .output()
+    .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+    .unwrap_or_default();
+  let device = nmcli.lines().find_map(|line| {
+    let mut parts = line.splitn(3, ':');
+    if parts.next()? != interface {
+      return None;
+    }
+    Some((parts.next()?.to_string(), parts.next().unwrap_or("").to_string()))
+  });
+  result.kind = match device.as_ref().map(|(kind, _)| kind.as_str()) {
+    Some("wifi") => "wifi",
+    Some("ethernet") => "ethernet",
+    Some("gsm") | Some("cdma") => "cellular",
+    _ if Path::new(&format!("/sys/class/net/{}/wireless", interface)).exists() => "wifi",
+    _ => classify_interface_kind(&interface),
+  }
+  .to_string();
+
+  if result.kind == "wifi" {
+    result.ssid = Command::new("iwgetid")
+      .args([interface.as_str(), "-r"])
+      .output()
+      .ok()
+      .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+      .filter(|ssid| !ssid.is_empty())
+      .or_else(|| device.map(|(_, connection)| connection).filter(|connection| !connection.is_empty()));
+  }
+  result
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn read_connection_type() -> ConnectionType {
+  let interface = read_default_routes().into_iter().next().map(|route| route.interface);
+  let mut result = ConnectionType {
+    interface: interface.clone(),
+    kind: "unknown".to_string(),
+    ssid: None,
+    error: None,
+    error_message: None,
+  };
+  let Some(interface) = interface else {
+    result.error = Some(ErrorCode::GatewayNotFound);
+    return result;
+  };
+
+  // networksetup lists "Hardware Port: Wi-Fi" followed by "Device: en0".
+  let ports = Command::new("networksetup")
+    .arg("-listallhardwareports")
+    .output()
+    .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+    .unwrap_or_default();
+  let mut port_name = None;
+  let mut current_port = "";
+  for line in ports.lines() {
+    if let Some(port) = line.strip_prefix("Hardware Port:") {
+      current_port = port.trim();
+    } else if let Some(device) = line.strip_prefix("Device:") {
+      if device.trim() == interface {
+        port_name = Some(current_port.to_string());
+        break;
+      }
+    }
+  }
+  result.kind = classify_interface_kind(port_name.as_deref().unwrap_or(&interface)).to_string();
+
+  if result.kind == "wifi" {
+    result.ssid = Command::new("networksetup")
+      .args(["-getairportnetwork", interface.as_str()])
+      .output()
+      .ok()
+      .and_then(|output| {
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        stdout
+          .split_once("Network:")
+          .map(|(_, ssid)| ssid.trim().to_string())
+      })
+      .filter(|ssid| !ssid.is_empty());
+  }
+  result
+}
+
+#[tauri::command]
+async fn get_connection_type() -> ConnectionType {
+  tauri::async_runtime::spawn_blocking(read_connection_type)
+    .await
+    .unwrap_or_else(|err| ConnectionType {
+      interface: None,
+      kind: "unknown".to_string(),
+      ssid: None,
+      error: Some(ErrorCode::CommandFailed),
+      error_message: Some(err.to_string()),
+    })
+}
+
+#[tauri::command]
+async fn test_lan_baseline() -> LanBaselineResult {
+  let loopback = ping_average("127.0.0.1", LAN_BASELINE_SAMPLES).await;
+  let gateway = tauri::async_runtime::spawn_blocking(default_gateway)
+    .await
+    .unwrap_or(None);
+  let mut result = LanBaselineResult {
+    loopback_latency_ms: loopback.latency_ms,
+    gateway: gateway.clone(),
+    gateway_latency_ms: None,
+    gateway_packets_received: 0,
+    gateway_http: false,
+    gateway_http_response_ms: None,
+    gateway_http_mbps: None,
+    error: None,
+    error_message: None,
+  };
+  let Some(gateway) = gateway else {
+    result.error = Some(ErrorCode::GatewayNotFound);
+    return result;
+  };
+
+  let gateway_ping = ping_average(&gateway, LAN_BASELINE_SAMPLES).await;
+  result.gateway_latency_ms = gateway_ping.latency_ms;
+  result.gateway_packets_received = gateway_ping.received;
+  if gateway_ping.latency_ms.is_none() {
+    result.error = gateway_ping.error;
+    result.error_message = gateway_ping.error_message;
+  }
+
+  // Most routers serve an admin page; timing it gives a rough LAN HTTP baseline.
+  let host = if gateway.contains(':') {
+    format!("[{}]", gateway)
+  } else {
+    gateway.clone()
+  };
+  // Direct on purpose: the gateway is on the LAN, where a proxy would only add its own hop.
+  let client = HttpClient::new();
+  let start = Instant::now();
+  if let Ok(response) = client
+    .get(format!("http://{}/", host))
+    .timeout(Duration::from_secs(3))
+    .send()
+    .await
+  {
+    let response_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let bytes = response.bytes().await.unwrap_or_default();
+    result.gateway_http = true;
+    result.gateway_http_response_ms = Some(round2(response_ms));
+    result.gateway_http_mbps = Some(bytes_to_mbps(bytes.len() as u64, start.elapsed().as_secs_f64()));
+  }
+  result
+}
+
+#[tauri::command]
+async fn detect_transparent_proxy() -> TransparentProxyResult {
+  // Direct on purpose: a configured proxy would show up as the very interception being looked for.
+  let client = HttpClient::new();
+  let wait = Duration::from_millis(SERVICE_CHECK_TIMEOUT_MS);
+  let user_agent = format!("PulseNet/{}", env!("CARGO_PKG_VERSION"));
+  let mut result = TransparentProxyResult {
+    proxy_detected: false,
+    evidence: vec![],
+    error: None,
+    error_message: None,
+  };
+
+  // Plain HTTP is where transparent proxies operate; the echo shows what actually reached the server.
+  match client
+    .get(format!("http://{}/headers", PROXY_ECHO_HOST))
+    .header("User-Agent", &user_agent)
+    .timeout(wait)
+    .send()
+    .await
+  {
+    // A failing echo says nothing about proxies either way, so it is reported as an error.
+    Ok(response) if !response.status().is_success() => {
+      result.error = Some(ErrorCode::RequestFailed);
+      result.error_message = Some(format!("Echo endpoint returned HTTP {}", response.status().as_u16()));
+    }
+    Ok(response) => {
+      let via_headers: Vec<String> = PROXY_RESPONSE_HEADERS
+        .iter()
+        .filter_map(|name| {
+          let value = response.headers().get(*name)?.to_str().ok()?;
+          Some(format!("Response carried a {} header: {}", name, value))
+        })
+        .collect();
+      let echoed: Option<HashMap<String, String>> = response
+        .json::<serde_json::Value>()
+        .await
+        .ok()
+        .and_then(|body| {
+          body.get("headers").and_then(|headers| headers.as_object()).map(|headers| {
+            headers
+              .iter()
+              .map(|(key, value)| (key.to_lowercase(), value.as_str().unwrap_or("").to_string()))
+              .collect()
+          })
+        });
+      match echoed {
+        Some(echoed) => {
+          result.evidence.extend(via_headers);
+          for name in PROXY_REQUEST_HEADERS {
+            if let Some(value) = echoed.get(name) {
+              result.evidence.push(format!("Request gained a {} header in transit: {}", name, value));
+            }
+          }
+          if let Some(agent) = echoed.get("user-agent") {
+            if *agent != user_agent {
+              result.evidence.push(format!("User-Agent was rewritten in transit to \"{}\"", agent));
+            }
+          }
+        }
+        None => {
+          result.error = Some(ErrorCode::InvalidResponse);
+          result.error_message = Some("Echo endpoint did not return its JSON header echo".to_string());
+        }
+      }
+    }
+    Err(error) => {
+      result.error = Some(ErrorCode::RequestFailed);
+      result.error_message = Some(error.to_string());
+    }
+  }
+
+  // A certificate error over HTTPS while TCP works points at TLS interception with a private CA.
+  if let Err(error) = client
+    .head(format!("https://{}/", PROXY_ECHO_HOST))
+    .timeout(wait)
+    .send()
+    .await
+  {
+    let message = format!("{:?}", error).to_lowercase();
+    if message.contains("certificate") || message.contains("unknownissuer") {
+      result.evidence.push(format!(
+        "TLS certificate for {} is not signed by a public CA (possible HTTPS interception)",
+        PROXY_ECHO_HOST
+      ));
+    }
+  }
+
+  result.proxy_detected = !result.evidence.is_empty();
+  if result.proxy_detected {
+    result.error = None;
+    result.error_message = None;
+  }
+  result
+}
+
+async fn check_service(client: HttpClient, name: String, url: String) -> ServiceStatus {
+  let mut status = ServiceStatus {
+    name,
+    url: url.clone(),
+    up: false,
+    method: "head".to_string(),
+    status_code: None,
+    latency_ms: None,
+    error: None,
+    error_message: None,
+  };
+  let wait = Duration::from_millis(SERVICE_CHECK_TIMEOUT_MS);
+  let start = Instant::now();
+  match client.head(&url).header("User-Agent", "PulseNet").timeout(wait).send().await {
+    Ok(response) => {
+      // Any HTTP answer (even 403/405) proves the service is reachable.
+      status.up = true;
+      status.status_code = Some(response.status().as_u16());
+      status.latency_ms = Some(round2(start.elapsed().as_secs_f64() * 1000.0));
+      return status;
+    }
+    Err(error) => {
+      status.error = Some(if error.is_timeout() { ErrorCode::Timeout } else { ErrorCode::RequestFailed });
+      status.error_message = Some(error.to_string());
+    }
+  }
+
+  // HTTP can fail for reasons unrelated to reachability (TLS interception, proxies), so try a plain connect.
+  let host = url.trim_start_matches("https://").trim_start_matches("http://");
+  let Some(addr) = lookup_host((host, 443)).await.ok().and_then(|mut addrs| addrs.next()) else {
+    status.error = Some(ErrorCode::ResolveFailed);
+    return status;
+  };
+  status.method = "tcp".to_string();
+  if let Ok(latency) = measure_tcp_connect(addr, wait).await {
+    status.up = true;
+    status.latency_ms = Some(round2(latency));
+    status.error = None;
+    status.error_message = None;
+  }
+  status
+}
+
+#[tauri::command]
+async fn check_service_reachability(app: AppHandle) -> ServiceReachabilityResult {
+  let (client, _) = shared_http_client(&app.state());
+  let tasks: Vec<_> = SERVICE_CHECKS
+    .iter()
+    .map(|(name, url)| tauri::async_runtime::spawn(check_service(client.clone(), name.to_string(), url.to_string())))
+    .collect();
+  let mut services = Vec::new();
+  for task in tasks {
+    if let Ok(status) = task.await {
+      services.push(status);
+    }
+  }
+  let up_count = services.iter().filter(|service| service.up).count();
+  let down_count = services.len() - up_count;
+  let verdict = if down_count == 0 {
+    "all-up"
+  } else if up_count == 0 {
+    "local-problem"
+  } else if down_count * 2 >= services.len() {
+    "widespread-outage"
+  } else {
+    "service-problem"
+  };
+  ServiceReachabilityResult {
+    services,
+    up_count,
+    down_count,
+    verdict: verdict.to_string(),
+  }
+}
+
+fn transfer_seconds(size_bytes: u64, mbps: f64) -> Option<f64> {
+  if mbps.is_finite() && mbps > 0.0 {
+    Some(round2(size_bytes as f64 * 8.0 / (mbps * 1_000_000.0)))
+  } else {
+    None
+  }
+}
+
+fn build_transfer_estimates(download_mbps: f64, upload_mbps: f64) -> Vec<TransferEstimate> {
+  TRANSFER_ESTIMATE_SIZES
+    .iter()
+    .map(|(label, size_bytes)| TransferEstimate {
+      label: label.to_string(),
+      size_bytes: *size_bytes,
+      download_seconds: transfer_seconds(*size_bytes, download_mbps),
+      upload_seconds: transfer_seconds(*size_bytes, upload_mbps),
+    })
+    .collect()
+}
+
+#[tauri::command]
+fn estimate_transfer_times(download_mbps: f64, upload_mbps: Option<f64>) -> Vec<TransferEstimate> {
+  build_transfer_estimates(download_mbps, upload_mbps.unwrap_or(0.0))
+}
+
+fn speedtest_history_path(app: &AppHandle) -> PathBuf {
+  if let Some(dir) = app.path_resolver().app_data_dir() {
+    return dir.join("speedtest-history.json");
+  }
+  PathBuf::from("speedtest-history.json")
+}
+
+fn read_speedtest_history(app: &AppHandle) -> Vec<StoredSpeedTest> {
+  fs::read_to_string(speedtest_history_path(app))
+    .ok()
+    .and_then(|raw| serde_json::from_str(&raw).ok())
+    .unwrap_or_default()
+}
+
+fn append_speedtest_history(app: &AppHandle, provider: &str, result: &SpeedTestResult) {
+  if result.error.is_some() || result.download_mbps <= 0.0 {
+    return;
+  }
+  let mut history = read_speedtest_history(app);
+  history.push(StoredSpeedTest {
+    timestamp: now_millis(),
+    provider: provider.to_string(),
+    mode: result.mode.clone(),
+    download_mbps: result.download_mbps,
+    upload_mbps: result.upload_mbps,
+    latency_ms: result.latency_ms,
+    jitter_ms: result.jitter_ms,
+  });
+  if history.len() > SPEEDTEST_HISTORY_MAX_ENTRIES {
+    history.drain(..history.len() - SPEEDTEST_HISTORY_MAX_ENTRIES);
+  }
+  let path = speedtest_history_path(app);
+  if let Some(parent) = path.parent() {
+    let _ = fs::create_dir_all(parent);
+  }
+  let _ = fs::write(path, serde_json::to_vec(&history).unwrap_or_default());
+}
+
+#[tauri::command]
+fn get_speedtest_history(app: AppHandle) -> Vec<StoredSpeedTest> {
+  read_speedtest_history(&app)
+}
+
+#[tauri::command]
+fn clear_speedtest_history(app: AppHandle) -> bool {
+  let path = speedtest_history_path(&app);
+  !path.exists() || fs::remove_file(path).is_ok()
+}
+
+// Binding to the unspecified address of one family keeps every connection on that family.
+// Behind a proxy only the hop to the proxy is pinned.
+fn family_pinned_client(ipv6: bool, proxy: Option<&str>) -> HttpClient {
+  let local = if ipv6 {
+    std::net::IpAddr::V6(std::net::Ipv6Addr::UNSPECIFIED)
+  } else {
+    std::net::IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED)
+  };
+  let mut builder = HttpClient::builder().local_address(local);
+  if let Some(proxy) = proxy.and_then(|proxy| reqwest::Proxy::all(proxy).ok()) {
+    builder = builder.proxy(proxy);
+  }
+  builder.build().unwrap_or_default()
+}
+
+async fn run_family_speedtest(ipv6: bool, lite: bool, proxy: Option<&str>) -> Result<SpeedTestResult, ErrorCode> {
+  let client = family_pinned_client(ipv6, proxy);
+  // measure_ping swallows errors, so check the family can reach the server at all first.
+  client
+    .get(format!("{}/__ping", CLOUDFLARE_BASE))
+    .timeout(Duration::from_secs(5))
+    .send()
+    .await
+    .map_err(|error| if error.is_timeout() { ErrorCode::Timeout } else { ErrorCode::RequestFailed })?;
+  let mut result = run_speedtest_cloudflare_with(&client, lite, None, None, None).await;
+  result.via_proxy = proxy.is_some();
+  Ok(result)
+}
+
+async fn run_speedtest_dual_stack(lite: bool, proxy: Option<String>) -> DualStackSpeedTestResult {
+  // Sequential runs so the two families don't compete for the same link.
+  let ipv4 = run_family_speedtest(false, lite, proxy.as_deref()).await;
+  let ipv6 = run_family_speedtest(true, lite, proxy.as_deref()).await;
+  let mut result = DualStackSpeedTestResult {
+    ipv4: None,
+    ipv6: None,
+    ipv4_error: ipv4.as_ref().err().copied(),
+    ipv6_error: ipv6.as_ref().err().copied(),
+    download_delta_percent: None,
+    upload_delta_percent: None,
+    latency_delta_ms: None,
+    verdict: "unknown".to_string(),
+    error: None,
+    error_message: None,
+  };
+  result.verdict = match (&ipv4, &ipv6) {
+    (Ok(v4), Ok(v6)) => {
+      result.download_delta_percent = percent_delta(v6.download_mbps, Some(v4.download_mbps));
+      result.upload_delta_percent = percent_delta(v6.upload_mbps, Some(v4.upload_mbps));
+      result.latency_delta_ms = Some(round2(v6.latency_ms - v4.latency_ms));
+      let download_delta = result.download_delta_percent.unwrap_or(0.0);
+      if download_delta <= -SPEEDTEST_DEGRADED_THROUGHPUT_PERCENT {
+        "ipv6-slower"
+      } else if download_delta >= SPEEDTEST_DEGRADED_THROUGHPUT_PERCENT {
+        "ipv4-slower"
+      } else {
+        "comparable"
+      }
+    }
+    (Ok(_), Err(_)) => "ipv4-only",
+    (Err(_), Ok(_)) => "ipv6-only",
+    (Err(code), Err(_)) => {
+      result.error = Some(*code);
+      "unreachable"
+    }
+  }
+  .to_string();
+  result.ipv4 = ipv4.ok();
+  result.ipv6 = ipv6.ok();
+  result
+}
+
+// Results stay out of the speedtest history; a forced-family run isn't a normal baseline.
+#[tauri::command]
+async fn speedtest_dual_stack(
+  app: AppHandle,
+  operation_id: Option<u64>,
+  lite: Option<bool>,
+) -> Result<DualStackSpeedTestResult, ErrorCode> {
+  let proxy = shared_proxy(&app.state());
+  run_cancellable(&app, operation_id, run_speedtest_dual_stack(lite.unwrap_or(false), proxy)).await
+}
+
+fn percent_delta(current: f64, average: Option<f64>) -> Option<f64> {
+  let average = average.filter(|value| *value > 0.0)?;
+  Some(((current - average) / average * 1000.0).round() / 10.0)
+}
+
+// Compares the most recent stored speedtest against the earlier runs inside the window.
+#[tauri::command]
+fn compare_to_history(app: AppHandle, window_days: Option<u64>) -> HistoryComparison {
+  let window_days = window_days.unwrap_or(SPEEDTEST_HISTORY_DEFAULT_DAYS).max(1);
+  let mut history = read_speedtest_history(&app);
+  let latest = history.pop();
+  let since = latest
+    .as_ref()
+    .map(|entry| entry.timestamp)
+    .unwrap_or_else(now_millis)
+    .saturating_sub(window_days as u128 * 86_400_000);
+  // Lite and full runs, or different providers, aren't comparable, so only like runs form the baseline.
+  let baseline: Vec<&StoredSpeedTest> = history
+    .iter()
+    .filter(|entry| entry.timestamp >= since)
+    .filter(|entry| {
+      latest
+        .as_ref()
+        .map(|latest| entry.provider == latest.provider && entry.mode == latest.mode)
+        .unwrap_or(true)
+    })
+    .collect();
+  let average = |value: fn(&StoredSpeedTest) -> f64| {
+    if baseline.is_empty() {
+      return None;
+    }
+    let mean = baseline.iter().map(|entry| value(entry)).sum::<f64>() / baseline.len() as f64;
+    Some((mean * 100.0).round() / 100.0)
+  };
+  let average_download_mbps = average(|entry| entry.download_mbps);
+  let average_upload_mbps = average(|entry| entry.upload_mbps);
+  let average_latency_ms = average(|entry| entry.latency_ms);
+  let best_download_mbps = baseline.iter().map(|entry| entry.download_mbps).reduce(f64::max);
+  let best_upload_mbps = baseline.iter().map(|entry| entry.upload_mbps).reduce(f64::max);
+  let best_latency_ms = baseline
+    .iter()
+    .map(|entry| entry.latency_ms)
+    .filter(|value| *value > 0.0)
+    .reduce(f64::min);
+  let (download_delta_percent, upload_delta_percent, latency_delta_percent) = match latest.as_ref() {
+    Some(entry) => (
+      percent_delta(entry.download_mbps, average_download_mbps),
+      percent_delta(entry.upload_mbps, average_upload_mbps),
+      percent_delta(entry.latency_ms, average_latency_ms),
+    ),
+    None => (None, None, None),
+  };
+  let degraded = download_delta_percent.is_some_and(|delta| delta <= -SPEEDTEST_DEGRADED_THROUGHPUT_PERCENT)
+    || upload_delta_percent.is_some_and(|delta| delta <= -SPEEDTEST_DEGRADED_THROUGHPUT_PERCENT)
+    || latency_delta_percent.is_some_and(|delta| delta >= SPEEDTEST_DEGRADED_LATENCY_PERCENT);
+  HistoryComparison {
+    samples: baseline.len(),
+    window_days,
+    latest,
+    average_download_mbps,
+    average_upload_mbps,
+    average_latency_ms,
+    best_download_mbps,
+    best_upload_mbps,
+    best_latency_ms,
+    download_delta_percent,
+    upload_delta_percent,
+    latency_delta_percent,
+    degraded,
+    error: None,
+    error_message: None,
+  }
+}
+
+#[tauri::command]
+async fn speedtest_cloudflare(
+  app: AppHandle,
+  operation_id: Option<u64>,
+  lite: Option<bool>,
+  download_bytes: Option<usize>,
+  upload_bytes: Option<usize>,
+) -> Result<SpeedTestResult, ErrorCode> {
+  let options = SpeedTestOptions { lite, server: None, download_bytes, upload_bytes, upload_url: None };
+  speedtest(app, operation_id, "cloudflare".to_string(), None, Some(options)).await
+}
+
+async fn run_speedtest_cloudflare_with(
+  client: &HttpClient,
+  lite: bool,
+  download_bytes: Option<usize>,
+  upload_bytes: Option<usize>,
+  progress: Option<&AppHandle>,
+) -> SpeedTestResult {
+  let (latency, jitter, latency_endpoints) = measure_latency_multi(client).await;
+  // Setup time is paid to Cloudflare's servers, so the other endpoints' round trips don't belong in it.
+  let cloudflare_latency = latency_endpoints
+    .iter()
+    .find(|endpoint| endpoint.url == CLOUDFLARE_PING_URL)
+    .and_then(|endpoint| endpoint.latency_ms)
+    .unwrap_or(latency);
+  let plan = speedtest_plan(lite, cloudflare_latency, download_bytes, upload_bytes);
+  let (download, downloaded) = measure_download_cloudflare(client, &plan, progress).await;
+  let (upload, uploaded) = measure_upload_cloudflare(client, &plan).await;
+  let (ip, country, ip_provider) = split_public_ip_lookup(lookup_public_ip(client).await);
+  let connection_type = get_connection_type().await;
+
+  SpeedTestResult {
+    download_mbps: (download * 100.0).round() / 100.0,
+    upload_mbps: (upload * 100.0).round() / 100.0,
+    latency_ms: (latency * 100.0).round() / 100.0,
+    jitter_ms: (jitter * 100.0).round() / 100.0,
+    ip,
+    country,
+    ip_provider,
+    connection_type: Some(connection_type),
+    transfer_estimates: build_transfer_estimates(download, upload),
+    mode: if plan.lite { "lite" } else { "full" }.to_string(),
+    estimated: plan.lite,
+    margin_percent: if plan.lite { Some(LITE_MARGIN_PERCENT) } else { None },
+    data_used_bytes: (downloaded + uploaded) as u64,
+    download_bytes: downloaded as u64,
+    upload_bytes: uploaded as u64,
+    latency_endpoints: Some(latency_endpoints),
+    via_proxy: false,
+    error: None,
+    error_message: None,
+  }
+}
+
+async fn probe_speedtest_server(client: HttpClient, index: usize) -> SpeedTestServer {
+  let (id, provider, name, ping_url, _) = SPEEDTEST_SERVERS[index];
+  let mut server = SpeedTestServer {
+    id: id.to_string(),
+    provider: provider.to_string(),
+    name: name.to_string(),
+    latency_ms: None,
+    jitter_ms: None,
+    error: None,
+    error_message: None,
+  };
+  // One request first so dead mirrors fail fast instead of costing PING_SAMPLES timeouts.
+  let reachable = client
+    .head(ping_url)
+    .timeout(Duration::from_millis(SPEEDTEST_SERVER_PROBE_TIMEOUT_MS))
+    .send()
+    .await;
+  if let Err(error) = reachable {
+    server.error = Some(if error.is_timeout() { ErrorCode::Timeout } else { ErrorCode::RequestFailed });
+    server.error_message = Some(error.to_string());
+    return server;
+  }
+  let (latency, jitter) = measure_ping(&client, ping_url).await;
+  server.latency_ms = Some(round2(latency));
+  server.jitter_ms = Some(round2(jitter));
+  server
+}
+
+#[tauri::command]
+async fn list_speedtest_servers(app: AppHandle) -> Vec<SpeedTestServer> {
+  let state: State<AppState> = app.state();
+  let (client, _) = shared_http_client(&state);
+  let tasks: Vec<_> = (0..SPEEDTEST_SERVERS.len())
+    .map(|index| tauri::async_runtime::spawn(probe_speedtest_server(client.clone(), index)))
+    .collect();
+  let mut servers = Vec::with_capacity(tasks.len());
+  for task in tasks {
+    if let Ok(server) = task.await {
+      servers.push(server);
+    }
+  }
+  // Unreachable servers sink to the bottom.
+  servers.sort_by(|a, b| match (a.latency_ms, b.latency_ms) {
+    (Some(a), Some(b)) => a.total_cmp(&b),
+    (Some(_), None) => std::cmp::Ordering::Less,
+    (None, Some(_)) => std::cmp::Ordering::Greater,
+    (None, None) => std::cmp::Ordering::Equal,
+  });
+  servers
+}
+
+#[tauri::command]
+async fn speedtest_hetzner(
+  app: AppHandle,
+  operation_id: Option<u64>,
+  lite: Option<bool>,
+  server: Option<String>,
+  download_bytes: Option<usize>,
+  upload_bytes: Option<usize>,
+) -> Result<SpeedTestResult, ErrorCode> {
+  let options = SpeedTestOptions { lite, server, download_bytes, upload_bytes, upload_url: None };
+  speedtest(app, operation_id, "hetzner".to_string(), None, Some(options)).await
+}
+
+fn custom_speedtest_url(url: &str) -> Result<reqwest::Url, ErrorCode> {
+  let parsed = reqwest::Url::parse(url.trim()).map_err(|_| ErrorCode::InvalidServer)?;
+  if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+    return Err(ErrorCode::InvalidServer);
+  }
+  Ok(parsed)
+}
+
+fn speedtest_endpoints(
+  provider: &str,
+  custom_url: Option<&str>,
+  options: &SpeedTestOptions,
+) -> Result<SpeedTestEndpoints, ErrorCode> {
+  match provider {
+    "hetzner" => {
+      let (ping_url, download_url) = match options.server.as_deref().map(str::trim).filter(|id| !id.is_empty()) {
+        Some(id) => match SPEEDTEST_SERVERS.iter().find(|entry| entry.0 == id && entry.1 == "hetzner") {
+          Some((_, _, _, ping_url, Some(download_url))) => (*ping_url, *download_url),
+          _ => return Err(ErrorCode::InvalidServer),
+        },
+        None => ("https://www.gstatic.com/generate_204", HETZNER_DOWNLOAD_URL),
+      };
+      Ok(SpeedTestEndpoints {
+        ping_url: ping_url.to_string(),
+        download_url: download_url.to_string(),
+        upload_url: HETZNER_UPLOAD_URL.to_string(),
+      })
+    }
+    "custom" => {
+      let download_url = custom_speedtest_url(custom_url.ok_or(ErrorCode::InvalidInput)?)?;
+      let upload_url = match options.upload_url.as_deref() {
+        Some(url) => custom_speedtest_url(url)?,
+        None => download_url.clone(),
+      };
+      // Pinging the payload URL would download it once per sample, so ping the server root instead.
+      let ping_url = download_url.join("/").unwrap_or_else(|_| download_url.clone());
+      Ok(SpeedTestEndpoints {
+        ping_url: ping_url.to_string(),
+        download_url: download_url.to_string(),
+        upload_url: upload_url.to_string(),
+      })
+    }
+    _ => Err(ErrorCode::InvalidInput),
+  }
+}
+
+#[tauri::command]
+async fn speedtest(
+  app: AppHandle,
+  operation_id: Option<u64>,
+  provider: String,
+  custom_url: Option<String>,
+  options: Option<SpeedTestOptions>,
+) -> Result<SpeedTestResult, ErrorCode> {
+  let options = options.unwrap_or_default();
+  let provider = provider.trim().to_lowercase();
+  let lite = options.lite.unwrap_or(false);
+  let state: State<AppState> = app.state();
+  let (client, via_proxy) = shared_http_client(&state);
+  let mut result = if provider == "cloudflare" {
+    run_cancellable(
+      &app,
+      operation_id,
+      run_speedtest_cloudflare_with(&client, lite, options.download_bytes, options.upload_bytes, Some(&app)),
+    )
+    .await?
+  } else {
+    let endpoints = speedtest_endpoints(&provider, custom_url.as_deref(), &options)?;
+    run_cancellable(
+      &app,
+      operation_id,
+      run_speedtest_http(&client, &app, &provider, lite, endpoints, options.download_bytes, options.upload_bytes),
+    )
+    .await?
+  };
+  result.via_proxy = via_proxy;
+  append_speedtest_history(&app, &provider, &result);
+  Ok(result)
+}
+
+async fn run_speedtest_http(
+  client: &HttpClient,
+  app: &AppHandle,
+  provider: &str,
+  lite: bool,
+  endpoints: SpeedTestEndpoints,
+  download_bytes: Option<usize>,
+  upload_bytes: Option<usize>,
+) -> SpeedTestResult {
+  let (latency, jitter) = measure_ping(client, &endpoints.ping_url).await;
+  let plan = speedtest_plan(lite, latency, download_bytes, upload_bytes);
+  let (download, downloaded) = measure_download_url(client, &plan, &endpoints.download_url, Some(app), provider).await;
+  let (upload, uploaded) = stream_upload(client, &endpoints.upload_url, &plan).await;
+  let (ip, country, ip_provider) = split_public_ip_lookup(lookup_public_ip(client).await);
+  let connection_type = get_connection_type().await;
+
+  SpeedTestResult {
+    download_mbps: (download * 100.0).round() / 100.0,
+    upload_mbps: (upload * 100.0).round() / 100.0,
+    latency_ms: (latency * 100.0).round() / 100.0,
+    jitter_ms: (jitter * 100.0).round() / 100.0,
+    ip,
+    country,
+    ip_provider,
+    connection_type: Some(connection_type),
+    transfer_estimates: build_transfer_estimates(download, upload),
+    mode: if plan.lite { "lite" } else { "full" }.to_string(),
+    estimated: plan.lite,
+    margin_percent: if plan.lite { Some(LITE_MARGIN_PERCENT) } else { None },
+    data_used_bytes: (downloaded + uploaded) as u64,
+    download_bytes: downloaded as u64,
+    upload_bytes: uploaded as u64,
+    latency_endpoints: None,
+    via_proxy: false,
+    error: None,
+    error_message: None,
+  }
+}
+
+#[tauri::command]
+fn start_throughput_monitor(app: AppHandle, state: State<AppState>, interval_ms: Option<u64>) -> bool {
+  let interval = Duration::from_millis(
+    interval_ms
+      .unwrap_or(THROUGHPUT_INTERVAL_MS)
+      .max(THROUGHPUT_MIN_INTERVAL_MS),
+  );
+  let mut guard = match state.throughput_monitor.lock() {
+    Ok(guard) => guard,
+    Err(_) => return false,
+  };
+  if let Some(handle) = guard.take() {
+    handle.abort();
+  }
+  *guard = Some(tauri::async_runtime::spawn(run_throughput_monitor(app, interval)));
+  true
+}
+
+#[tauri::command]
+fn stop_throughput_monitor(state: State<AppState>) -> bool {
+  match state.throughput_monitor.lock() {
+    Ok(mut guard) => match guard.take() {
+      Some(handle) => {
+        handle.abort();
+        true
+      }
+      None => false,
+    },
+    Err(_) => false,
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn read_network_interfaces() -> Vec<NetworkInterfaceInfo> {
+  let command = "Get-NetAdapter | Select-Object Name,InterfaceDescription,MacAddress,Status | ConvertTo-Json -Compress";
+  let output = match run_powershell(command) {
+    Ok(stdout) => stdout,
+    Err(_) => return vec![],
+  };
+  let parsed = match serde_json::from_str::<serde_json::Value>(&output) {
+    Ok(value) => value,
+    Err(_) => return vec![],
+  };
+  let items = if let Some(array) = parsed.as_array() {
+    array.clone()
+  } else {
+    vec![parsed]
+  };
+  let text = |item: &serde_json::Value, key: &str| {
+    item
+      .get(key)
+      .and_then(|value| value.as_str())
+      .unwrap_or("")
+      .trim()
+      .to_string()
+  };
+  items
+    .iter()
+    .filter(|item| !text(item, "Name").is_empty())
+    .map(|item| NetworkInterfaceInfo {
+      name: text(item, "Name"),
+      description: Some(text(item, "InterfaceDescription")).filter(|value| !value.is_empty()),
+      mac: text(item, "MacAddress").replace('-', ":"),
+      status: text(item, "Status"),
+    })
+    .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn read_network_interfaces() -> Vec<NetworkInterfaceInfo> {
+  let entries = match fs::read_dir("/sys/class/net") {
+    Ok(entries) => entries,
+    Err(_) => return vec![],
+  };
+  let mut interfaces: Vec<NetworkInterfaceInfo> = entries
+    .flatten()
+    .map(|entry| {
+      let path = entry.path();
+      let read = |file: &str| {
+        fs::read_to_string(path.join(file))
+          .map(|value| value.trim().to_string())
+          .unwrap_or_default()
+      };
+      NetworkInterfaceInfo {
+        name: entry.file_name().to_string_lossy().to_string(),
+        description: None,
+        mac: read("address"),
+        status: read("operstate"),
+      }
+    })
+    .collect();
+  interfaces.sort_by(|left, right| left.name.cmp(&right.name));
+  interfaces
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn read_network_interfaces() -> Vec<NetworkInterfaceInfo> {
+  let output = match Command::new("ifconfig").output() {
+    Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+    Err(_) => return vec![],
+  };
+  let mut interfaces: Vec<NetworkInterfaceInfo> = Vec::new();
+  for line in output.lines() {
+    if !line.starts_with(char::is_whitespace) {
+      if let Some((name, _)) = line.split_once(':') {
+        interfaces.push(NetworkInterfaceInfo {
+          name: name.to_string(),
+          description: None,
+          mac: String::new(),
+          status: String::new(),
+        });
+      }
+      continue;
+    }
+    let Some(current) = interfaces.last_mut() else {
+      continue;
+    };
+    let trimmed = line.trim();
+    if let Some(mac) = trimmed.strip_prefix("ether ") {
+      current.mac = mac.trim().to_string();
+    } else if let Some(status) = trimmed.strip_prefix("status: ") {
+      current.status = status.trim().to_string();
+    }
+  }
+  interfaces
+}
+
+#[cfg(not(target_os = "windows"))]
+fn split_endpoint(endpoint: &str) -> (String, u16) {
+  let Some((address, port)) = endpoint.rsplit_once(':') else {
+    return (endpoint.to_string(), 0);
+  };
+  let address = address.trim_start_matches('[').trim_end_matches(']');
+  // Drop zone/interface suffixes such as "fe80::1%en0" or "192.168.1.5%wlan0".
+  let address = address.split('%').next().unwrap_or(address);
+  (address.to_string(), port.parse().unwrap_or(0))
+}
+
+#[cfg(target_os = "windows")]
+fn read_active_connections() -> Result<Vec<ActiveConnection>, ErrorDetail> {
+  let command = "$names = @{}; Get-Process | ForEach-Object { $names[[int]$_.Id] = $_.ProcessName }; \
+    $tcp = Get-NetTCPConnection -ErrorAction SilentlyContinue | ForEach-Object { [PSCustomObject]@{ Protocol = 'TCP'; LocalAddress = $_.LocalAddress; LocalPort = [int]$_.LocalPort; RemoteAddress = $_.RemoteAddress; RemotePort = [int]$_.RemotePort; State = [string]$_.State; Pid = [int]$_.OwningProcess; Process = $names[[int]$_.OwningProcess] } }; \
+    $udp = Get-NetUDPEndpoint -ErrorAction SilentlyContinue | ForEach-Object { [PSCustomObject]@{ Protocol = 'UDP'; LocalAddress = $_.LocalAddress; LocalPort = [int]$_.LocalPort; RemoteAddress = ''; RemotePort = 0; State = ''; Pid = [int]$_.OwningProcess; Process = $names[[int]$_.OwningProcess] } }; \
+    @($tcp) + @($udp) | ConvertTo-Json -Compress";
+  let output = run_powershell(command).map_err(|message| (ErrorCode::CommandFailed, Some(message)))?;
+  if output.trim().is_empty() {
+    return Ok(vec![]);
+  }
+  let parsed = serde_json::from_str::<serde_json::Value>(&output)
+    .map_err(|error| (ErrorCode::InvalidResponse, Some(error.to_string())))?;
+  let items = if let Some(array) = parsed.as_array() {
+    array.clone()
+  } else {
+    vec![parsed]
+  };
+  let text = |item: &serde_json::Value, key: &str| {
+    item
+      .get(key)
+      .and_then(|value| value.as_str())
+      .unwrap_or("")
+      .trim()
+      .to_string()
+  };
+  let number = |item: &serde_json::Value, key: &str| item.get(key).and_then(|value| value.as_u64()).unwrap_or(0);
+  Ok(
+    items
+      .iter()
+      .map(|item| ActiveConnection {
+        protocol: text(item, "Protocol"),
+        local_address: text(item, "LocalAddress"),
+        local_port: number(item, "LocalPort") as u16,
+        remote_address: text(item, "RemoteAddress"),
+        remote_port: number(item, "RemotePort") as u16,
+        state: text(item, "State"),
+        pid: item.get("Pid").and_then(|value| value.as_u64()).map(|pid| pid as u32),
+        process_name: Some(text(item, "Process")).filter(|value| !value.is_empty()),
+      })
+      .collect(),
+  )
+}
+
+#[cfg(target_os = "linux")]
+fn read_active_connections() -> Result<Vec<ActiveConnection>, ErrorDetail> {
+  let output = Command::new("ss")
+    .args(["-tunapH"])
+    .output()
+    .map_err(|error| (ErrorCode::CommandFailed, Some(error.to_string())))?;
+  let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+  let mut connections = Vec::new();
+  for line in stdout.lines() {
+    // tcp ESTAB 0 0 192.168.1.5:43210 1.2.3.4:443 users:(("firefox",pid=1234,fd=55))
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 6 {
+      continue;
+    }
+    let (local_address, local_port) = split_endpoint(fields[4]);
+    let (remote_address, remote_port) = split_endpoint(fields[5]);
+    let process = fields.get(6).copied().unwrap_or("");
+    let process_name = process
+      .split_once("((\"")
+      .and_then(|(_, rest)| rest.split_once('"'))
+      .map(|(name, _)| name.to_string());
+    let pid = process
+      .split_once("pid=")
+      .and_then(|(_, rest)| rest.split(|c: char| !c.is_ascii_digit()).next())
+      .and_then(|pid| pid.parse().ok());
+    connections.push(ActiveConnection {
+      protocol: fields[0].to_uppercase(),
+      local_address,
+      local_port,
+      remote_address,
+      remote_port,
+      state: fields[1].to_string(),
+      pid,
+      process_name,
+    });
+  }
+  Ok(connections)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn read_active_connections() -> Result<Vec<ActiveConnection>, ErrorDetail> {
+  let output = Command::new("lsof")
+    .args(["-i", "-n", "-P"])
+    .output()
+    .map_err(|error| (ErrorCode::CommandFailed, Some(error.to_string())))?;
+  let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+  let mut connections = Vec::new();
+  for line in stdout.lines().skip(1) {
+    // Safari 512 user 20u IPv4 0x1 0t0 TCP 192.168.1.5:50000->17.1.1.1:443 (ESTABLISHED)
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 9 {
+      continue;
+    }
+    let (local, remote) = fields[8].split_once("->").unwrap_or((fields[8], ""));
+    let (local_address, local_port) = split_endpoint(local);
+    let (remote_address, remote_port) = if remote.is_empty() {
+      (String::new(), 0)
+    } else {
+      split_endpoint(remote)
+    };
+    connections.push(ActiveConnection {
+      protocol: fields[7].to_string(),
+      local_address,
+      local_port,
+      remote_address,
+      remote_port,
+      state: fields
+        .get(9)
+        .map(|state| state.trim_matches(|c| c == '(' || c == ')').to_string())
+        .unwrap_or_default(),
+      pid: fields[1].parse().ok(),
+      process_name: Some(fields[0].replace("\\x20", " ")),
+    });
+  }
+  Ok(connections)
+}
+
+#[tauri::command]
+async fn list_active_connections() -> ActiveConnectionsResult {
+  let outcome = tauri::async_runtime::spawn_blocking(read_active_connections)
+    .await
+    .unwrap_or_else(|err| Err((ErrorCode::CommandFailed, Some(err.to_string()))));
+  match outcome {
+    Ok(connections) => ActiveConnectionsResult {
+      connections,
+      error: None,
+      error_message: None,
+    },
+    Err((code, message)) => ActiveConnectionsResult {
+      connections: vec![],
+      error: Some(code),
+      error_message: message,
+    },
+  }
+}
+
+fn normalize_mac(mac: &str) -> Option<String> {
+  let octets: Vec<String> = mac
+    .split([':', '-'])
+    .map(|octet| format!("{:0>2}", octet.to_lowercase()))
+    .collect();
+  if octets.len() != 6 || octets.iter().any(|octet| octet.len() != 2 || !octet.chars().all(|c| c.is_ascii_hexdigit())) {
+    return None;
+  }
+  Some(octets.join(":"))
+}
+
+#[cfg(target_os = "windows")]
+fn read_arp_table() -> Result<Vec<ArpEntry>, ErrorDetail> {
+  let output = hidden_command("arp")
+    .arg("-a")
+    .output()
+    .map_err(|error| (ErrorCode::CommandFailed, Some(error.to_string())))?;
+  let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+  let mut entries = Vec::new();
+  let mut interface = None;
+  for line in stdout.lines() {
+    // Interface: 192.168.1.5 --- 0xb
+    //   192.168.1.1           aa-bb-cc-dd-ee-ff     dynamic
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if line.trim_start().starts_with("Interface:") {
+      interface = fields.get(1).map(|value| value.to_string());
+      continue;
+    }
+    if fields.len() != 3 || fields[0].parse::<std::net::IpAddr>().is_err() {
+      continue;
+    }
+    entries.push(ArpEntry {
+      ip: fields[0].to_string(),
+      mac: normalize_mac(fields[1]),
+      interface: interface.clone(),
+      state: fields[2].to_lowercase(),
+    });
+  }
+  Ok(entries)
+}
+
+#[cfg(target_os = "linux")]
+fn read_arp_table() -> Result<Vec<ArpEntry>, ErrorDetail> {
+  let output = Command::new("ip")
+    .arg("neigh")
+    .output()
+    .map_err(|error| (ErrorCode::CommandFailed, Some(error.to_string())))?;
+  let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+  let mut entries = Vec::new();
+  for line in stdout.lines() {
+    // 192.168.1.1 dev wlan0 lladdr aa:bb:cc:dd:ee:ff REACHABLE
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let Some(ip) = fields.first() else {
+      continue;
+    };
+    let value_after = |key: &str| {
+      fields
+        .iter()
+        .position(|field| *field == key)
+        .and_then(|index| fields.get(index + 1))
+        .map(|value| value.to_string())
+    };
+    entries.push(ArpEntry {
+      ip: ip.to_string(),
+      mac: value_after("lladdr").and_then(|mac| normalize_mac(&mac)),
+      interface: value_after("dev"),
+      state: fields.last().map(|state| state.to_lowercase()).unwrap_or_default(),
+    });
+  }
+  Ok(entries)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn read_arp_table() -> Result<Vec<ArpEntry>, ErrorDetail> {
+  let output = Command::new("arp")
+    .args(["-a", "-n"])
+    .output()
+    .map_err(|error| (ErrorCode::CommandFailed, Some(error.to_string())))?;
+  let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+  let mut entries = Vec::new();
+  for line in stdout.lines() {
+    // ? (192.168.1.1) at aa:bb:cc:dd:ee:ff on en0 ifscope [ethernet]
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let Some(ip) = fields.get(1).map(|value| value.trim_matches(|c| c == '(' || c == ')')) else {
+      continue;
+    };
+    let mac = fields.get(3).copied().unwrap_or("");
+    let value_after = |key: &str| {
+      fields
+        .iter()
+        .position(|field| *field == key)
+        .and_then(|index| fields.get(index + 1))
+        .map(|value| value.to_string())
+    };
+    entries.push(ArpEntry {
+      ip: ip.to_string(),
+      mac: normalize_mac(mac),
+      interface: value_after("on"),
+      state: if mac == "(incomplete)" {
+        "incomplete"
+      } else if line.contains("permanent") {
+        "permanent"
+      } else {
+        "dynamic"
+      }
+      .to_string(),
+    });
+  }
+  Ok(entries)
+}
+
+#[tauri::command]
+async fn get_arp_table() -> ArpTableResult {
+  let outcome = tauri::async_runtime::spawn_blocking(read_arp_table)
+    .await
+    .unwrap_or_else(|err| Err((ErrorCode::CommandFailed, Some(err.to_string()))));
+  match outcome {
+    Ok(entries) => ArpTableResult {
+      entries,
+      error: None,
+      error_message: None,
+    },
+    Err((code, message)) => ArpTableResult {
+      entries: vec![],
+      error: Some(code),
+      error_message: message,
+    },
+  }
+}
+
+#[cfg(target_os = "windows")]
+fn interface_ipv4(interface: &str) -> Option<String> {
+  let command = format!(
+    "Get-NetIPAddress -InterfaceAlias '{}' -AddressFamily IPv4 -ErrorAction SilentlyContinue | Select-Object -First 1 -ExpandProperty IPAddress",
+    ps_escape_single(interface)
+  );
+  run_powershell(&command)
+    .ok()
+    .map(|output| output.trim().to_string())
+    .filter(|address| address.parse::<std::net::Ipv4Addr>().is_ok())
+}
+
+#[cfg(target_os = "linux")]
+fn interface_ipv4(interface: &str) -> Option<String> {
+  let output = Command::new("ip").args(["-4", "-o", "addr", "show", "dev", interface]).output().ok()?;
+  // 5: wg0    inet 10.8.0.2/24 scope global wg0
+  String::from_utf8_lossy(&output.stdout)
+    .split_whitespace()
+    .skip_while(|field| *field != "inet")
+    .nth(1)
+    .and_then(|cidr| cidr.split('/').next())
+    .map(|address| address.to_string())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn interface_ipv4(interface: &str) -> Option<String> {
+  let output = Command::new("ipconfig").args(["getifaddr", interface]).output().ok()?;
+  Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    .filter(|address| address.parse::<std::net::Ipv4Addr>().is_ok())
+}
+
+// Sends a single echo with the don't-fragment bit set, sourced from the given interface if any.
+#[cfg(target_os = "windows")]
+fn df_ping(target: &str, _interface: Option<&str>, source: Option<&str>, payload: u32) -> bool {
+  let mut command = hidden_command("ping");
+  command.args(["-n", "1", "-w", "1500", "-f", "-l", &payload.to_string()]);
+  if let Some(source) = source {
+    command.args(["-S", source]);
+  }
+  command
+    .arg(target)
+    .output()
+    .map(|output| output.status.success() && String::from_utf8_lossy(&output.stdout).contains("TTL="))
+    .unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+fn df_ping(target: &str, interface: Option<&str>, _source: Option<&str>, payload: u32) -> bool {
+  let mut command = Command::new("ping");
+  command.args(["-c", "1", "-W", "2", "-M", "do", "-s", &payload.to_string()]);
+  if let Some(interface) = interface {
+    command.args(["-I", interface]);
+  }
+  command
+    .arg(target)
+    .output()
+    .map(|output| output.status.success())
+    .unwrap_or(false)
+}
 
-  let payload = vec![0u8; 32];
-  let result = timeout(Duration::from_secs(2), pinger.ping(PingSequence(0), &payload)).await;
-  match result {
-    Ok(Ok((_packet, rtt))) => PingResponse {
-      alive: true,
-      time: Some(rtt.as_secs_f64() * 1000.0),
-      error: None,
-    },
-    Ok(Err(error)) => PingResponse {
-      alive: false,
-      time: None,
-      error: Some(error.to_string()),
-    },
-    Err(_) => PingResponse {
-      alive: false,
-      time: None,
-      error: Some("timeout".to_string()),
-    },
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn df_ping(target: &str, _interface: Option<&str>, source: Option<&str>, payload: u32) -> bool {
+  let mut command = Command::new("ping");
+  command.args(["-c", "1", "-t", "2", "-D", "-s", &payload.to_string()]);
+  if let Some(source) = source {
+    command.args(["-S", source]);
   }
+  command
+    .arg(target)
+    .output()
+    .map(|output| output.status.success())
+    .unwrap_or(false)
 }
 
-#[tauri::command]
-fn get_app_version(app: tauri::AppHandle) -> String {
-  app.package_info().version.to_string()
+// The target lands in the system ping's argv, so anything that could be read as an option is refused.
+fn ping_cli_target(input: &str) -> Option<String> {
+  let trimmed = input.trim();
+  if let Ok(ip) = trimmed.parse::<std::net::IpAddr>() {
+    return Some(ip.to_string());
+  }
+  let domain = sanitize_domain(trimmed);
+  let valid = !domain.is_empty()
+    && !domain.starts_with('-')
+    && domain.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+  valid.then_some(domain)
 }
 
-#[tauri::command]
-fn get_username() -> String {
-  env::var("USERNAME")
-    .or_else(|_| env::var("USER"))
-    .unwrap_or_else(|_| "User".to_string())
+fn probe_payload(target: &str, interface: Option<&str>, source: Option<&str>, payload: u32) -> bool {
+  // One retry so a single lost packet isn't mistaken for an MTU limit.
+  df_ping(target, interface, source, payload) || df_ping(target, interface, source, payload)
 }
 
-#[tauri::command]
-fn get_auto_launch(_app: tauri::AppHandle) -> bool {
-  #[cfg(target_os = "windows")]
-  {
-    return is_auto_launch_enabled();
+// Binary-searches the largest DF payload that gets through, given the smallest one already did.
+fn largest_df_payload(
+  target: &str,
+  interface: Option<&str>,
+  source: Option<&str>,
+  cancel: &AtomicBool,
+) -> (bool, u32) {
+  if probe_payload(target, interface, source, MTU_PROBE_MAX_PAYLOAD) {
+    return (true, MTU_PROBE_MAX_PAYLOAD);
   }
+  let (mut low, mut high) = (MTU_PROBE_SMALL_PAYLOAD, MTU_PROBE_MAX_PAYLOAD);
+  while high - low > 1 && !cancel.load(Ordering::SeqCst) {
+    let mid = (low + high) / 2;
+    if probe_payload(target, interface, source, mid) {
+      low = mid;
+    } else {
+      high = mid;
+    }
+  }
+  (false, low)
+}
 
-  #[cfg(not(target_os = "windows"))]
-  {
-    read_auto_launch_pref(&_app).unwrap_or(false)
+fn measure_vpn_mtu(interface: String, target: String, cancel: &AtomicBool) -> VpnMtuResult {
+  let mut result = VpnMtuResult {
+    interface: interface.clone(),
+    source_address: None,
+    target: target.clone(),
+    small_payload_ok: false,
+    large_payload_ok: false,
+    mtu: None,
+    recommended_mss: None,
+    error: None,
+    error_message: None,
+  };
+  let Some(source) = interface_ipv4(&interface) else {
+    result.error = Some(ErrorCode::InvalidInput);
+    result.error_message = Some(format!("No IPv4 address found on interface {}", interface));
+    return result;
+  };
+  result.source_address = Some(source.clone());
+  result.small_payload_ok = probe_payload(&target, Some(&interface), Some(&source), MTU_PROBE_SMALL_PAYLOAD);
+  if !result.small_payload_ok {
+    result.error = Some(ErrorCode::PingFailed);
+    result.error_message = Some(format!("{} is not reachable through {}", target, interface));
+    return result;
   }
+  let (large_payload_ok, max_payload) = largest_df_payload(&target, Some(&interface), Some(&source), cancel);
+  result.large_payload_ok = large_payload_ok;
+  let mtu = max_payload + MTU_PROBE_HEADER_BYTES;
+  result.mtu = Some(mtu);
+  // TCP MSS = MTU - IPv4 header (20) - TCP header (20).
+  result.recommended_mss = Some(mtu - 40);
+  result
 }
 
 #[tauri::command]
-fn set_auto_launch(app: tauri::AppHandle, enabled: bool) -> bool {
-  write_auto_launch_pref(&app, enabled);
-  #[cfg(target_os = "windows")]
-  {
-    return set_auto_launch_enabled(enabled);
-  }
+async fn test_vpn_mtu(
+  app: AppHandle,
+  interface: String,
+  target: Option<String>,
+  operation_id: Option<u64>,
+) -> Result<VpnMtuResult, ErrorCode> {
+  let cancel = operation_cancel_flag(&app, operation_id);
+  run_cancellable(&app, operation_id, run_vpn_mtu_test(interface, target, cancel)).await
+}
 
-  #[cfg(not(target_os = "windows"))]
-  {
-    let launcher = auto_launcher();
-    let _ = if enabled { launcher.enable() } else { launcher.disable() };
-    launcher.is_enabled().unwrap_or(false)
+async fn run_vpn_mtu_test(interface: String, target: Option<String>, cancel: Arc<AtomicBool>) -> VpnMtuResult {
+  let interface = interface.trim().to_string();
+  let target = target
+    .map(|value| value.trim().to_string())
+    .filter(|value| !value.is_empty())
+    .unwrap_or_else(|| MTU_DEFAULT_TARGET.to_string());
+  let checked_target = ping_cli_target(&target);
+  let error = if interface.is_empty() || interface.starts_with('-') {
+    Some(ErrorCode::InvalidInput)
+  } else if checked_target.is_none() {
+    Some(ErrorCode::InvalidDomain)
+  } else {
+    None
+  };
+  let (Some(target), None) = (checked_target, error) else {
+    return VpnMtuResult {
+      interface,
+      source_address: None,
+      target,
+      small_payload_ok: false,
+      large_payload_ok: false,
+      mtu: None,
+      recommended_mss: None,
+      error,
+      error_message: None,
+    };
+  };
+  let fallback = (interface.clone(), target.clone());
+  tauri::async_runtime::spawn_blocking(move || measure_vpn_mtu(interface, target, &cancel))
+    .await
+    .unwrap_or_else(|err| VpnMtuResult {
+      interface: fallback.0,
+      source_address: None,
+      target: fallback.1,
+      small_payload_ok: false,
+      large_payload_ok: false,
+      mtu: None,
+      recommended_mss: None,
+      error: Some(ErrorCode::CommandFailed),
+      error_message: Some(err.to_string()),
+    })
+}
+
+// Each reply is one UDP datagram of known size (learned over TCP), so a reply that fits
+// an ordinary MTU but never arrives marks the inbound limit without any large request.
+async fn probe_inbound_mtu(server: SocketAddr) -> Vec<InboundMtuProbe> {
+  let mut probes = Vec::with_capacity(INBOUND_MTU_DNS_PROBES.len());
+  for (domain, record_type) in INBOUND_MTU_DNS_PROBES {
+    let mut probe = InboundMtuProbe {
+      query: format!("{} {}", domain, record_type),
+      response_bytes: None,
+      udp_ok: false,
+    };
+    let Ok(mut query) = build_dns_query(domain, record_type, true) else {
+      probes.push(probe);
+      continue;
+    };
+    if let Some(edns) = query.extensions_mut() {
+      edns.set_max_payload(DNS_FRAGMENTATION_EDNS_PAYLOAD);
+      edns.set_dnssec_ok(true);
+    }
+    let (udp, tcp) = tokio::join!(send_dns_message(server, &query, false), send_dns_message(server, &query, true));
+    probe.response_bytes = tcp.ok().map(|response| response.size);
+    probe.udp_ok = matches!(udp, Ok(response) if !response.message.truncated());
+    probes.push(probe);
   }
+  probes
 }
 
 #[tauri::command]
-fn get_close_action(state: State<AppState>) -> String {
-  state
-    .close_action
-    .lock()
-    .map(|guard| guard.clone())
-    .unwrap_or_else(|_| "ask".to_string())
+async fn test_asymmetric_mtu(
+  app: AppHandle,
+  host: Option<String>,
+  dns_server: Option<String>,
+  operation_id: Option<u64>,
+) -> Result<AsymmetricMtuResult, ErrorCode> {
+  let cancel = operation_cancel_flag(&app, operation_id);
+  run_cancellable(&app, operation_id, run_asymmetric_mtu_test(host, dns_server, cancel)).await
 }
 
-#[tauri::command]
-fn set_close_action(state: State<AppState>, action: String) -> String {
-  if ["hide", "exit", "ask"].contains(&action.as_str()) {
-    if let Ok(mut guard) = state.close_action.lock() {
-      *guard = action;
+async fn run_asymmetric_mtu_test(
+  host: Option<String>,
+  dns_server: Option<String>,
+  cancel: Arc<AtomicBool>,
+) -> AsymmetricMtuResult {
+  let host = host
+    .map(|value| value.trim().to_string())
+    .filter(|value| !value.is_empty())
+    .unwrap_or_else(|| MTU_DEFAULT_TARGET.to_string());
+  let dns_server = dns_server
+    .map(|value| value.trim().to_string())
+    .filter(|value| !value.is_empty())
+    .unwrap_or_else(|| MTU_DEFAULT_TARGET.to_string());
+  let mut result = AsymmetricMtuResult {
+    host: host.clone(),
+    dns_server: dns_server.clone(),
+    outbound_mtu: None,
+    inbound_mtu_at_least: None,
+    inbound_mtu_below: None,
+    probes: vec![],
+    verdict: "unknown".to_string(),
+    error: None,
+    error_message: None,
+  };
+  let Some(target) = ping_cli_target(&host) else {
+    result.error = Some(ErrorCode::InvalidDomain);
+    return result;
+  };
+  let Some(server_addr) = parse_dns_server_socket(&dns_server) else {
+    result.error = Some(ErrorCode::InvalidServer);
+    return result;
+  };
+
+  // Outbound: the DF bit forbids fragmenting the large request on the way out.
+  let outbound = tauri::async_runtime::spawn_blocking(move || {
+    if !probe_payload(&target, None, None, MTU_PROBE_SMALL_PAYLOAD) {
+      return None;
     }
+    Some(largest_df_payload(&target, None, None, &cancel).1 + MTU_PROBE_HEADER_BYTES)
+  });
+  result.probes = probe_inbound_mtu(server_addr).await;
+  result.outbound_mtu = outbound.await.ok().flatten();
+  if result.outbound_mtu.is_none() {
+    result.error = Some(ErrorCode::PingFailed);
+    result.error_message = Some(format!("{} did not answer even small pings", host));
   }
-  state
-    .close_action
-    .lock()
-    .map(|guard| guard.clone())
-    .unwrap_or_else(|_| "ask".to_string())
+
+  // Only replies that fit one unfragmented datagram say anything about the MTU.
+  let single_datagram = |probe: &&InboundMtuProbe| {
+    probe.response_bytes.is_some_and(|size| size <= UDP_UNFRAGMENTED_MAX_BYTES)
+  };
+  if !result.probes.iter().any(|probe| probe.udp_ok) {
+    result.verdict = "inbound-udp-blocked".to_string();
+    return result;
+  }
+  result.inbound_mtu_at_least = result
+    .probes
+    .iter()
+    .filter(single_datagram)
+    .filter(|probe| probe.udp_ok)
+    .filter_map(|probe| probe.response_bytes)
+    .max()
+    .map(|size| size as u32 + UDP_OVERHEAD_BYTES);
+  result.inbound_mtu_below = result
+    .probes
+    .iter()
+    .filter(single_datagram)
+    .filter(|probe| !probe.udp_ok)
+    .filter_map(|probe| probe.response_bytes)
+    .min()
+    .map(|size| size as u32 + UDP_OVERHEAD_BYTES)
+    .filter(|below| !matches!(result.inbound_mtu_at_least, Some(at_least) if *below <= at_least));
+
+  result.verdict = match (result.outbound_mtu, result.inbound_mtu_below) {
+    (Some(outbound), Some(below)) if below <= outbound => "inbound-smaller",
+    (_, Some(_)) => "inbound-limited",
+    (Some(_), None) => "no-asymmetry-detected",
+    (None, None) => "unknown",
+  }
+  .to_string();
+  result
 }
 
-#[tauri::command]
-fn perform_close_action(action: String, window: Window) -> bool {
-  match action.as_str() {
-    "exit" => {
-      window.app_handle().exit(0);
-    }
-    "hide" => {
-      let _ = window.hide();
+// Minimal DER walker: returns (tag, content start, content end) of the element at `pos`.
+fn der_element(data: &[u8], pos: usize) -> Option<(u8, usize, usize)> {
+  let tag = *data.get(pos)?;
+  let first = *data.get(pos + 1)? as usize;
+  let (length, header) = if first < 0x80 {
+    (first, 2)
+  } else {
+    let count = first & 0x7f;
+    if count == 0 || count > 4 {
+      return None;
     }
-    "minimize" => {
-      let _ = window.minimize();
+    let mut length = 0usize;
+    for index in 0..count {
+      length = (length << 8) | *data.get(pos + 2 + index)? as usize;
     }
-    _ => {}
+    (length, 2 + count)
+  };
+  let start = pos + header;
+  let end = start.checked_add(length)?;
+  if end > data.len() {
+    return None;
   }
-  true
+  Some((tag, start, end))
 }
 
-#[tauri::command]
-async fn test_dns_servers(domain: String) -> DnsResponse {
-  test_dns_servers_with_custom(domain, None).await
+fn der_children(data: &[u8], start: usize, end: usize) -> Vec<(u8, usize, usize)> {
+  let mut children = Vec::new();
+  let mut pos = start;
+  while pos < end {
+    let Some(child) = der_element(data, pos) else {
+      break;
+    };
+    pos = child.2;
+    children.push(child);
+  }
+  children
 }
 
-#[tauri::command]
-async fn test_dns_servers_with_custom(domain: String, custom_servers: Option<Vec<String>>) -> DnsResponse {
-  let sanitized = sanitize_domain(&domain);
-  if sanitized.is_empty() {
-    return DnsResponse {
-      error: Some("invalid-domain".to_string()),
-      results: vec![],
-    };
+fn der_string(tag: u8, bytes: &[u8]) -> String {
+  if tag == 0x1e {
+    // BMPString is UTF-16BE.
+    let units: Vec<u16> = bytes.chunks(2).map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)])).collect();
+    return String::from_utf16_lossy(&units);
   }
-  let mut all_servers: Vec<String> = DNS_SERVERS.iter().map(|item| item.to_string()).collect();
-  if let Some(custom) = custom_servers {
-    for server in custom {
-      let normalized = server.trim().to_string();
-      if normalized.is_empty() {
+  String::from_utf8_lossy(bytes).to_string()
+}
+
+fn der_name(data: &[u8], start: usize, end: usize) -> String {
+  let mut parts = Vec::new();
+  for (_, set_start, set_end) in der_children(data, start, end) {
+    for (_, attr_start, attr_end) in der_children(data, set_start, set_end) {
+      let attr = der_children(data, attr_start, attr_end);
+      let (Some(oid), Some(value)) = (attr.first(), attr.get(1)) else {
         continue;
-      }
-      if !all_servers.contains(&normalized) {
-        all_servers.push(normalized);
-      }
+      };
+      let label = match &data[oid.1..oid.2] {
+        [0x55, 0x04, 0x03] => "CN",
+        [0x55, 0x04, 0x06] => "C",
+        [0x55, 0x04, 0x07] => "L",
+        [0x55, 0x04, 0x08] => "ST",
+        [0x55, 0x04, 0x0a] => "O",
+        [0x55, 0x04, 0x0b] => "OU",
+        _ => continue,
+      };
+      parts.push(format!("{}={}", label, der_string(value.0, &data[value.1..value.2])));
     }
   }
-  let mut results = Vec::new();
-  for server in all_servers {
-    let start = Instant::now();
-    let socket_addr = parse_dns_server_socket(&server);
-    if socket_addr.is_none() {
-      results.push(DnsResult {
-        server,
-        status: false,
-        response_time_ms: start.elapsed().as_millis(),
-        error: Some("invalid-server".to_string()),
-      });
+  parts.join(", ")
+}
+
+fn der_time(tag: u8, bytes: &[u8]) -> Option<String> {
+  let text = std::str::from_utf8(bytes).ok()?.trim_end_matches('Z');
+  let (year, rest) = match tag {
+    0x17 => {
+      let short: u32 = text.get(0..2)?.parse().ok()?;
+      (if short >= 50 { 1900 + short } else { 2000 + short }, text.get(2..)?)
+    }
+    0x18 => (text.get(0..4)?.parse().ok()?, text.get(4..)?),
+    _ => return None,
+  };
+  let field = |index: usize| rest.get(index..index + 2).unwrap_or("00");
+  Some(format!("{:04}-{}-{}T{}:{}:{}Z", year, field(0), field(2), field(4), field(6), field(8)))
+}
+
+fn der_sans(data: &[u8], start: usize, end: usize) -> Vec<String> {
+  let mut sans = Vec::new();
+  for (_, ext_start, ext_end) in der_children(data, start, end) {
+    let fields = der_children(data, ext_start, ext_end);
+    let Some(oid) = fields.first() else {
+      continue;
+    };
+    if data[oid.1..oid.2] != [0x55, 0x1d, 0x11] {
       continue;
     }
-    let mut resolver_config = ResolverConfig::new();
-    let name_server = NameServerConfig {
-      socket_addr: socket_addr.unwrap(),
-      protocol: Protocol::Udp,
-      tls_dns_name: None,
-      trust_negative_responses: false,
-      bind_addr: None,
-    };
-    resolver_config.add_name_server(name_server);
-    let mut opts = ResolverOpts::default();
-    opts.timeout = Duration::from_millis(DNS_TIMEOUT_MS);
-
-    let resolver = TokioAsyncResolver::tokio(resolver_config, opts);
-    let lookup = timeout(Duration::from_millis(DNS_TIMEOUT_MS), resolver.lookup_ip(sanitized.clone())).await;
-    match lookup {
-      Ok(Ok(_)) => results.push(DnsResult {
-        server,
-        status: true,
-        response_time_ms: start.elapsed().as_millis(),
-        error: None,
-      }),
-      Ok(Err(err)) => results.push(DnsResult {
-        server,
-        status: false,
-        response_time_ms: start.elapsed().as_millis(),
-        error: Some(err.to_string()),
-      }),
-      Err(_) => results.push(DnsResult {
-        server,
-        status: false,
-        response_time_ms: start.elapsed().as_millis(),
-        error: Some("timeout".to_string()),
-      }),
+    let Some(value) = fields.iter().find(|field| field.0 == 0x04) else {
+      continue;
+    };
+    let Some((_, names_start, names_end)) = der_element(data, value.1) else {
+      continue;
+    };
+    for (tag, name_start, name_end) in der_children(data, names_start, names_end) {
+      let bytes = &data[name_start..name_end];
+      match tag {
+        0x82 => sans.push(String::from_utf8_lossy(bytes).to_string()),
+        0x87 if bytes.len() == 4 => {
+          sans.push(std::net::Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).to_string())
+        }
+        0x87 if bytes.len() == 16 => {
+          let mut octets = [0u8; 16];
+          octets.copy_from_slice(bytes);
+          sans.push(std::net::Ipv6Addr::from(octets).to_string());
+        }
+        _ => {}
+      }
     }
   }
+  sans
+}
 
-  DnsResponse { error: None, results }
+fn parse_certificate(der: &[u8], now: &str) -> Option<TlsCertificateInfo> {
+  let (_, cert_start, cert_end) = der_element(der, 0)?;
+  let (_, tbs_start, tbs_end) = *der_children(der, cert_start, cert_end).first()?;
+  let mut fields = der_children(der, tbs_start, tbs_end);
+  // Skip the optional explicit [0] version.
+  if fields.first().map(|field| field.0) == Some(0xa0) {
+    fields.remove(0);
+  }
+  // serialNumber, signature, issuer, validity, subject, subjectPublicKeyInfo, ...
+  let issuer = fields.get(2)?;
+  let validity = fields.get(3)?;
+  let subject = fields.get(4)?;
+  let times = der_children(der, validity.1, validity.2);
+  let not_before = times.first().and_then(|time| der_time(time.0, &der[time.1..time.2]));
+  let not_after = times.get(1).and_then(|time| der_time(time.0, &der[time.1..time.2]));
+  let sans = fields
+    .iter()
+    .find(|field| field.0 == 0xa3)
+    .and_then(|extensions| der_element(der, extensions.1))
+    .map(|(_, start, end)| der_sans(der, start, end))
+    .unwrap_or_default();
+  let subject_name = der_name(der, subject.1, subject.2);
+  let issuer_name = der_name(der, issuer.1, issuer.2);
+  // Both sides are "YYYY-MM-DDTHH:MM:SS..." so a prefix comparison orders them correctly.
+  let expired = not_after.as_ref().is_some_and(|value| value[..19] < now[..19]);
+  Some(TlsCertificateInfo {
+    self_signed: subject.2 - subject.1 == issuer.2 - issuer.1 && der[subject.1..subject.2] == der[issuer.1..issuer.2],
+    subject: subject_name,
+    issuer: issuer_name,
+    not_before,
+    not_after,
+    sans,
+    expired,
+  })
 }
 
-#[tauri::command]
-fn list_dns_adapters(force_refresh: Option<bool>) -> Vec<DnsAdapter> {
-  #[cfg(target_os = "windows")]
-  {
-    let force_refresh = force_refresh.unwrap_or(false);
-    if !force_refresh {
-      if let Ok(guard) = dns_adapter_cache().lock() {
-        if let Some((cached_at, adapters)) = guard.as_ref() {
-          if now_millis().saturating_sub(*cached_at) <= DNS_ADAPTER_CACHE_TTL_MS {
-            return adapters.clone();
-          }
-        }
-      }
-    }
+// Accepts every chain so it can be inspected, but records what normal WebPKI validation said.
+struct RecordingVerifier {
+  inner: tokio_rustls::rustls::client::WebPkiVerifier,
+  outcome: Arc<Mutex<Option<String>>>,
+}
 
-    let command = "Get-DnsClientServerAddress -AddressFamily IPv4 | Select-Object InterfaceAlias,ServerAddresses | ConvertTo-Json -Depth 4 -Compress";
-    let output = match run_powershell(command) {
-      Ok(stdout) => stdout,
-      Err(_) => return vec![],
-    };
-    let adapters = parse_dns_adapters_from_output(&output);
-    if let Ok(mut guard) = dns_adapter_cache().lock() {
-      *guard = Some((now_millis(), adapters.clone()));
+impl tokio_rustls::rustls::client::ServerCertVerifier for RecordingVerifier {
+  fn verify_server_cert(
+    &self,
+    end_entity: &tokio_rustls::rustls::Certificate,
+    intermediates: &[tokio_rustls::rustls::Certificate],
+    server_name: &tokio_rustls::rustls::ServerName,
+    scts: &mut dyn Iterator<Item = &[u8]>,
+    ocsp_response: &[u8],
+    now: std::time::SystemTime,
+  ) -> Result<tokio_rustls::rustls::client::ServerCertVerified, tokio_rustls::rustls::Error> {
+    if let Err(error) = self
+      .inner
+      .verify_server_cert(end_entity, intermediates, server_name, scts, ocsp_response, now)
+    {
+      if let Ok(mut guard) = self.outcome.lock() {
+        *guard = Some(error.to_string());
+      }
     }
-    return adapters;
+    Ok(tokio_rustls::rustls::client::ServerCertVerified::assertion())
   }
+}
 
-  #[cfg(not(target_os = "windows"))]
-  {
-    let _ = force_refresh;
-    vec![]
-  }
+// The recording verifier lets an untrusted chain finish the handshake so it can be inspected.
+fn recording_tls_connector(outcome: Arc<Mutex<Option<String>>>) -> tokio_rustls::TlsConnector {
+  use tokio_rustls::rustls;
+
+  let mut roots = rustls::RootCertStore::empty();
+  roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+    rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(anchor.subject, anchor.spki, anchor.name_constraints)
+  }));
+  let verifier = RecordingVerifier {
+    inner: rustls::client::WebPkiVerifier::new(roots, None),
+    outcome,
+  };
+  let config = rustls::ClientConfig::builder()
+    .with_safe_defaults()
+    .with_custom_certificate_verifier(Arc::new(verifier))
+    .with_no_client_auth();
+  tokio_rustls::TlsConnector::from(Arc::new(config))
 }
 
-#[tauri::command]
-fn set_adapter_dns(adapter_name: String, primary_dns: String, secondary_dns: Option<String>) -> DnsManagerResult {
-  #[cfg(target_os = "windows")]
-  {
-    let adapter = adapter_name.trim();
-    let primary = primary_dns.trim();
-    if adapter.is_empty() || primary.is_empty() {
-      return DnsManagerResult {
-        success: false,
-        error: Some("invalid-input".to_string()),
-      };
+async fn fetch_tls_info(host: &str, port: u16) -> Result<TlsInfoResult, ErrorDetail> {
+  use tokio_rustls::rustls;
+
+  let server_name = rustls::ServerName::try_from(host).map_err(|_| (ErrorCode::InvalidInput, None))?;
+  let outcome = Arc::new(Mutex::new(None));
+  let connector = recording_tls_connector(outcome.clone());
+  let wait = Duration::from_millis(TLS_INFO_TIMEOUT_MS);
+  let stream = match timeout(wait, tokio::net::TcpStream::connect((host, port))).await {
+    Ok(Ok(stream)) => stream,
+    Ok(Err(error)) => return Err((ErrorCode::RequestFailed, Some(error.to_string()))),
+    Err(_) => return Err((ErrorCode::Timeout, None)),
+  };
+  let started = Instant::now();
+  let tls = match timeout(wait, connector.connect(server_name, stream)).await {
+    Ok(Ok(tls)) => tls,
+    Ok(Err(error)) => return Err((ErrorCode::RequestFailed, Some(error.to_string()))),
+    Err(_) => return Err((ErrorCode::Timeout, None)),
+  };
+  let handshake_ms = started.elapsed().as_secs_f64() * 1000.0;
+  let (_, connection) = tls.get_ref();
+  let now = format_utc_timestamp(now_millis());
+  let chain = connection
+    .peer_certificates()
+    .unwrap_or(&[])
+    .iter()
+    .filter_map(|certificate| parse_certificate(&certificate.0, &now))
+    .collect();
+  let verify_error = outcome.lock().ok().and_then(|guard| guard.clone());
+  Ok(TlsInfoResult {
+    host: host.to_string(),
+    port,
+    tls_version: connection.protocol_version().map(|version| format!("{:?}", version)),
+    cipher_suite: connection
+      .negotiated_cipher_suite()
+      .map(|suite| format!("{:?}", suite.suite())),
+    handshake_ms: Some(handshake_ms),
+    trusted: verify_error.is_none(),
+    verify_error,
+    chain,
+    error: None,
+    error_message: None,
+  })
+}
+
+async fn time_tls_handshake(host: String, port: u16) -> TlsHandshakeTiming {
+  use tokio_rustls::rustls;
+
+  let mut timing = TlsHandshakeTiming {
+    host: host.clone(),
+    port,
+    address: None,
+    dns_ms: None,
+    tcp_connect_ms: None,
+    tls_handshake_ms: None,
+    total_ms: None,
+    tls_version: None,
+    error: None,
+    error_message: None,
+  };
+  let Ok(server_name) = rustls::ServerName::try_from(host.as_str()) else {
+    timing.error = Some(ErrorCode::InvalidInput);
+    return timing;
+  };
+  let wait = Duration::from_millis(TLS_INFO_TIMEOUT_MS);
+  let started = Instant::now();
+  let addr = match timeout(wait, lookup_host((host.as_str(), port))).await {
+    Ok(Ok(mut addrs)) => addrs.next(),
+    Ok(Err(error)) => {
+      timing.error = Some(ErrorCode::ResolveFailed);
+      timing.error_message = Some(error.to_string());
+      return timing;
     }
-    let mut servers = vec![format!("'{}'", ps_escape_single(primary))];
-    if let Some(secondary) = secondary_dns {
-      let trimmed = secondary.trim();
-      if !trimmed.is_empty() {
-        servers.push(format!("'{}'", ps_escape_single(trimmed)));
-      }
+    Err(_) => {
+      timing.error = Some(ErrorCode::Timeout);
+      return timing;
     }
-    let command = format!(
-      "Set-DnsClientServerAddress -InterfaceAlias '{}' -ServerAddresses @({})",
-      ps_escape_single(adapter),
-      servers.join(",")
-    );
-    match run_powershell(&command) {
-      Ok(_) => {
-        clear_dns_adapter_cache();
-        DnsManagerResult {
-          success: true,
-          error: None,
-        }
-      }
-      Err(error) => DnsManagerResult {
-        success: false,
-        error: Some(error),
-      },
+  };
+  let Some(addr) = addr else {
+    timing.error = Some(ErrorCode::ResolveFailed);
+    return timing;
+  };
+  timing.address = Some(addr.ip().to_string());
+  timing.dns_ms = Some(round2(started.elapsed().as_secs_f64() * 1000.0));
+
+  let connect_started = Instant::now();
+  let stream = match timeout(wait, tokio::net::TcpStream::connect(addr)).await {
+    Ok(Ok(stream)) => stream,
+    Ok(Err(error)) => {
+      timing.error = Some(ErrorCode::RequestFailed);
+      timing.error_message = Some(error.to_string());
+      return timing;
     }
-  }
+    Err(_) => {
+      timing.error = Some(ErrorCode::Timeout);
+      return timing;
+    }
+  };
+  timing.tcp_connect_ms = Some(round2(connect_started.elapsed().as_secs_f64() * 1000.0));
 
-  #[cfg(not(target_os = "windows"))]
-  {
-    let _ = (adapter_name, primary_dns, secondary_dns);
-    DnsManagerResult {
-      success: false,
-      error: Some("unsupported-platform".to_string()),
+  // Trust problems are get_tls_info's concern; here only the time to finish the handshake matters.
+  let connector = recording_tls_connector(Arc::new(Mutex::new(None)));
+  let handshake_started = Instant::now();
+  match timeout(wait, connector.connect(server_name, stream)).await {
+    Ok(Ok(tls)) => {
+      timing.tls_handshake_ms = Some(round2(handshake_started.elapsed().as_secs_f64() * 1000.0));
+      timing.tls_version = tls.get_ref().1.protocol_version().map(|version| format!("{:?}", version));
+      timing.total_ms = Some(round2(started.elapsed().as_secs_f64() * 1000.0));
+    }
+    Ok(Err(error)) => {
+      timing.error = Some(ErrorCode::RequestFailed);
+      timing.error_message = Some(error.to_string());
     }
+    Err(_) => timing.error = Some(ErrorCode::Timeout),
   }
+  timing
 }
 
 #[tauri::command]
-fn reset_adapter_dns(adapter_name: String) -> DnsManagerResult {
-  #[cfg(target_os = "windows")]
-  {
-    let adapter = adapter_name.trim();
-    if adapter.is_empty() {
-      return DnsManagerResult {
-        success: false,
-        error: Some("invalid-input".to_string()),
-      };
+async fn benchmark_tls_handshakes(hosts: Option<Vec<String>>) -> TlsBenchmarkResult {
+  let mut result = TlsBenchmarkResult {
+    results: vec![],
+    error: None,
+    error_message: None,
+  };
+  let mut targets: Vec<(String, u16)> = Vec::new();
+  let hosts = hosts
+    .filter(|hosts| !hosts.is_empty())
+    .unwrap_or_else(|| TLS_BENCHMARK_DEFAULT_HOSTS.iter().map(|host| host.to_string()).collect());
+  for entry in hosts {
+    let entry = entry.trim();
+    if entry.is_empty() {
+      continue;
     }
-    let command = format!(
-      "Set-DnsClientServerAddress -InterfaceAlias '{}' -ResetServerAddresses",
-      ps_escape_single(adapter)
-    );
-    match run_powershell(&command) {
-      Ok(_) => {
-        clear_dns_adapter_cache();
-        DnsManagerResult {
-          success: true,
-          error: None,
+    // Accept "host" or "host:port".
+    let target = match entry.rsplit_once(':') {
+      Some((host, port)) if !host.contains(':') => match port.parse::<u16>() {
+        Ok(port) => (host.to_string(), port),
+        Err(_) => {
+          result.error = Some(ErrorCode::InvalidInput);
+          result.error_message = Some(format!("Invalid port in {}", entry));
+          return result;
         }
-      }
-      Err(error) => DnsManagerResult {
-        success: false,
-        error: Some(error),
       },
+      _ => (entry.to_string(), 443),
+    };
+    if !targets.contains(&target) {
+      targets.push(target);
     }
   }
-
-  #[cfg(not(target_os = "windows"))]
-  {
-    let _ = adapter_name;
-    DnsManagerResult {
-      success: false,
-      error: Some("unsupported-platform".to_string()),
-    }
-  }
-}
-
-async fn measure_ping(client: &HttpClient, url: &str) -> (f64, f64) {
-  let mut samples = Vec::new();
-  for _ in 0..PING_SAMPLES {
-    let start = Instant::now();
-    let _ = client.get(url).send().await;
-    samples.push(start.elapsed().as_secs_f64() * 1000.0);
+  if targets.len() > TLS_BENCHMARK_MAX_HOSTS {
+    result.error = Some(ErrorCode::InvalidInput);
+    result.error_message = Some(format!("At most {} hosts can be benchmarked at once", TLS_BENCHMARK_MAX_HOSTS));
+    return result;
   }
-  let avg = samples.iter().sum::<f64>() / samples.len().max(1) as f64;
-  let mut jitter = 0.0;
-  if samples.len() > 1 {
-    let mut sum = 0.0;
-    for idx in 1..samples.len() {
-      sum += (samples[idx] - samples[idx - 1]).abs();
+  let tasks: Vec<_> = targets
+    .into_iter()
+    .map(|(host, port)| tauri::async_runtime::spawn(time_tls_handshake(host, port)))
+    .collect();
+  for task in tasks {
+    if let Ok(timing) = task.await {
+      result.results.push(timing);
     }
-    jitter = sum / (samples.len() - 1) as f64;
   }
-  (avg, jitter)
+  result
 }
 
-async fn measure_download_cloudflare(client: &HttpClient) -> f64 {
-  let start = Instant::now();
-  let response = client
-    .get(format!("{}/__down?bytes={}", CLOUDFLARE_BASE, DOWNLOAD_BYTES))
-    .send()
-    .await;
-  if response.is_err() {
-    return 0.0;
-  }
-  let bytes = response.unwrap().bytes().await.unwrap_or_default();
-  let duration = start.elapsed().as_secs_f64();
-  if duration == 0.0 {
-    return 0.0;
-  }
-  (bytes.len() as f64 * 8.0) / duration / 1_000_000.0
+#[tauri::command]
+async fn get_tls_info(host: String, port: Option<u16>) -> TlsInfoResult {
+  let host = host.trim().to_string();
+  let port = port.unwrap_or(443);
+  let outcome = if host.is_empty() {
+    Err((ErrorCode::InvalidInput, None))
+  } else {
+    fetch_tls_info(&host, port).await
+  };
+  outcome.unwrap_or_else(|(code, message)| TlsInfoResult {
+    host,
+    port,
+    tls_version: None,
+    cipher_suite: None,
+    handshake_ms: None,
+    trusted: false,
+    verify_error: None,
+    chain: vec![],
+    error: Some(code),
+    error_message: message,
+  })
 }
 
-async fn measure_download_hetzner(client: &HttpClient) -> f64 {
-  let start = Instant::now();
-  let response = client.get(HETZNER_DOWNLOAD_URL).send().await;
-  if response.is_err() {
-    return 0.0;
+fn os_version() -> String {
+  #[cfg(target_os = "windows")]
+  {
+    run_powershell("[System.Environment]::OSVersion.VersionString")
+      .unwrap_or_else(|_| env::consts::OS.to_string())
   }
-  let bytes = response.unwrap().bytes().await.unwrap_or_default();
-  let duration = start.elapsed().as_secs_f64();
-  if duration == 0.0 {
-    return 0.0;
+
+  #[cfg(not(target_os = "windows"))]
+  {
+    Command::new("uname")
+      .arg("-sr")
+      .output()
+      .ok()
+      .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+      .filter(|value| !value.is_empty())
+      .unwrap_or_else(|| env::consts::OS.to_string())
   }
-  (bytes.len() as f64 * 8.0) / duration / 1_000_000.0
 }
 
-async fn measure_upload_cloudflare(client: &HttpClient) -> f64 {
-  let payload = vec![0u8; UPLOAD_BYTES];
-  let start = Instant::now();
-  let response = client
-    .post(format!("{}/__up", CLOUDFLARE_BASE))
-    .body(payload)
-    .send()
-    .await;
-  if response.is_err() {
-    return 0.0;
-  }
-  let duration = start.elapsed().as_secs_f64();
-  if duration == 0.0 {
-    return 0.0;
+fn mask_ip(ip: &str) -> String {
+  match ip.parse::<std::net::IpAddr>() {
+    Ok(std::net::IpAddr::V4(v4)) => {
+      let octets = v4.octets();
+      format!("{}.{}.{}.x", octets[0], octets[1], octets[2])
+    }
+    Ok(std::net::IpAddr::V6(v6)) => {
+      let segments = v6.segments();
+      format!("{:x}:{:x}:{:x}::x", segments[0], segments[1], segments[2])
+    }
+    Err(_) => ip.to_string(),
   }
-  (UPLOAD_BYTES as f64 * 8.0) / duration / 1_000_000.0
 }
 
-async fn measure_upload_hetzner(client: &HttpClient) -> f64 {
-  let payload = vec![0u8; UPLOAD_BYTES];
-  let start = Instant::now();
-  let response = client
-    .post(HETZNER_UPLOAD_URL)
-    .body(payload)
-    .send()
-    .await;
-  if response.is_err() {
-    return 0.0;
-  }
-  let duration = start.elapsed().as_secs_f64();
-  if duration == 0.0 {
-    return 0.0;
+// A fresh salt per report keeps hashes comparable inside one report while making them useless for
+// brute-forcing the 48-bit address space; the salt itself is never written out.
+fn report_salt() -> [u8; 16] {
+  let mut salt = [0u8; 16];
+  if getrandom::getrandom(&mut salt).is_err() {
+    let seed = format!("{}-{}-{:p}", now_millis(), std::process::id(), &salt);
+    salt.copy_from_slice(&Sha256::digest(seed.as_bytes())[..16]);
   }
-  (UPLOAD_BYTES as f64 * 8.0) / duration / 1_000_000.0
+  salt
 }
 
-fn extract_ip_from_trace(body: &str) -> Option<String> {
-  for line in body.lines() {
-    if let Some(value) = line.strip_prefix("ip=") {
-      let trimmed = value.trim();
-      if !trimmed.is_empty() {
-        return Some(trimmed.to_string());
-      }
-    }
+fn hash_mac(mac: &str, salt: &[u8]) -> String {
+  let normalized: String = mac
+    .chars()
+    .filter(|c| c.is_ascii_hexdigit())
+    .collect::<String>()
+    .to_lowercase();
+  if normalized.is_empty() {
+    return String::new();
   }
-  None
+  let mut hasher = Sha256::new();
+  hasher.update(salt);
+  hasher.update(normalized.as_bytes());
+  format!("sha256:{}", hex_digest(&hasher.finalize()[..6]))
 }
 
-fn extract_country_from_trace(body: &str) -> Option<String> {
-  for line in body.lines() {
-    if let Some(value) = line.strip_prefix("loc=") {
-      let trimmed = value.trim();
-      if !trimmed.is_empty() {
-        return Some(trimmed.to_string());
+fn redact_report(value: &mut serde_json::Value, public_ip: &str, salt: &[u8]) {
+  match value {
+    serde_json::Value::Object(map) => {
+      for (key, item) in map.iter_mut() {
+        if key == "mac" {
+          if let Some(mac) = item.as_str() {
+            *item = serde_json::Value::String(hash_mac(mac, salt));
+          }
+          continue;
+        }
+        if matches!(key.as_str(), "city" | "region" | "latitude" | "longitude") {
+          *item = serde_json::Value::Null;
+          continue;
+        }
+        redact_report(item, public_ip, salt);
+      }
+    }
+    serde_json::Value::Array(items) => {
+      for item in items.iter_mut() {
+        redact_report(item, public_ip, salt);
       }
     }
+    serde_json::Value::String(text)
+      if !public_ip.is_empty() && public_ip != "N/A" && text.contains(public_ip) =>
+    {
+      *text = text.replace(public_ip, &mask_ip(public_ip));
+    }
+    _ => {}
   }
-  None
 }
 
-fn extract_ip_country_from_ipwhois(body: &str) -> (String, String) {
-  if let Ok(value) = serde_json::from_str::<serde_json::Value>(body) {
-    let ip = value
-      .get("ip")
-      .and_then(|item| item.as_str())
-      .unwrap_or("N/A")
-      .to_string();
-    let country = value
-      .get("country_code")
-      .or_else(|| value.get("countryCode"))
-      .and_then(|item| item.as_str())
-      .unwrap_or("N/A")
-      .to_string();
-    return (ip, country);
-  }
-  ("N/A".to_string(), "N/A".to_string())
+fn escape_html(value: &str) -> String {
+  value
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn render_report_html(report: &serde_json::Value) -> String {
+  let pretty = serde_json::to_string_pretty(report).unwrap_or_default();
+  format!(
+    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>PulseNet Diagnostic Report</title>\n</head>\n<body>\n<h1>PulseNet Diagnostic Report</h1>\n<pre>{}</pre>\n</body>\n</html>\n",
+    escape_html(&pretty)
+  )
 }
 
 #[tauri::command]
-async fn get_public_network_info() -> PublicNetworkInfo {
-  let client = HttpClient::new();
-  let mut ip = "N/A".to_string();
-  let mut country = "N/A".to_string();
+async fn save_diagnostic_report(app: AppHandle, path: String, redact: bool) -> DiagnosticReportResult {
+  let target = PathBuf::from(path.trim());
+  if path.trim().is_empty() {
+    return DiagnosticReportResult {
+      success: false,
+      path,
+      redacted: redact,
+      error: Some(ErrorCode::InvalidInput),
+      error_message: None,
+    };
+  }
 
-  if let Ok(resp) = client
-    .get(format!("{}/cdn-cgi/trace", CLOUDFLARE_BASE))
-    .header("User-Agent", "PulseNet")
-    .send()
-    .await
-  {
-    let body = resp.text().await.unwrap_or_default();
-    if let Some(value) = extract_ip_from_trace(&body) {
-      ip = value;
-    }
-    if let Some(value) = extract_country_from_trace(&body) {
-      country = value;
-    }
+  let mut pings = Vec::new();
+  for host in DIAGNOSTIC_PING_HOSTS {
+    let result = ping_host(host.to_string(), None, None, None, None).await;
+    pings.push(serde_json::json!({ "host": host, "result": result }));
   }
+  let dns = test_dns_servers(DIAGNOSTIC_DNS_DOMAIN.to_string()).await;
+  let public_network = get_public_network_info(app.clone()).await;
+  let (client, via_proxy) = shared_http_client(&app.state());
+  let mut speedtest = run_speedtest_cloudflare_with(&client, false, None, None, None).await;
+  speedtest.via_proxy = via_proxy;
+  let (interfaces, adapters, os) = tauri::async_runtime::spawn_blocking(|| {
+    (read_network_interfaces(), list_dns_adapters(Some(true), None), os_version())
+  })
+  .await
+  .unwrap_or_default();
 
-  if ip == "N/A" || country == "N/A" {
-    if let Ok(resp) = client
-      .get(IPWHOIS_URL)
-      .header("User-Agent", "PulseNet")
-      .send()
-      .await
-    {
-      let body = resp.text().await.unwrap_or_default();
-      let (fallback_ip, fallback_country) = extract_ip_country_from_ipwhois(&body);
-      if ip == "N/A" {
-        ip = fallback_ip;
-      }
-      if country == "N/A" {
-        country = fallback_country;
-      }
-    }
+  let public_ip = public_network.ip.clone();
+  let mut report = serde_json::json!({
+    "generatedAt": now_millis(),
+    "redacted": redact,
+    "app": {
+      "name": app.package_info().name,
+      "version": app.package_info().version.to_string(),
+    },
+    "os": {
+      "family": env::consts::OS,
+      "arch": env::consts::ARCH,
+      "version": os,
+    },
+    "publicNetwork": public_network,
+    "ping": pings,
+    "dns": dns,
+    "speedtest": speedtest,
+    "interfaces": interfaces,
+    "dnsAdapters": adapters,
+  });
+  if redact {
+    redact_report(&mut report, &public_ip, &report_salt());
   }
 
-  let error = if ip == "N/A" && country == "N/A" {
-    Some("Failed to fetch network info".to_string())
+  let is_html = target
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .map(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"))
+    .unwrap_or(false);
+  let contents = if is_html {
+    render_report_html(&report)
   } else {
-    None
+    serde_json::to_string_pretty(&report).unwrap_or_default()
   };
-
-  PublicNetworkInfo { ip, country, error }
+  if let Some(parent) = target.parent() {
+    let _ = fs::create_dir_all(parent);
+  }
+  match fs::write(&target, contents) {
+    Ok(_) => DiagnosticReportResult {
+      success: true,
+      path: target.to_string_lossy().to_string(),
+      redacted: redact,
+      error: None,
+      error_message: None,
+    },
+    Err(error) => DiagnosticReportResult {
+      success: false,
+      path: target.to_string_lossy().to_string(),
+      redacted: redact,
+      error: Some(ErrorCode::FileWriteFailed),
+      error_message: Some(error.to_string()),
+    },
+  }
 }
 
-#[tauri::command]
-async fn speedtest_cloudflare() -> SpeedTestResult {
-  let client = HttpClient::new();
-  let (latency, jitter) = measure_ping(&client, &format!("{}/__ping", CLOUDFLARE_BASE)).await;
-  let download = measure_download_cloudflare(&client).await;
-  let upload = measure_upload_cloudflare(&client).await;
-  let (ip, country) = match client
-    .get(format!("{}/cdn-cgi/trace", CLOUDFLARE_BASE))
-    .header("User-Agent", "PulseNet")
-    .send()
-    .await
-  {
-    Ok(resp) => {
-      let body = resp.text().await.unwrap_or_default();
-      let ip = extract_ip_from_trace(&body).unwrap_or_else(|| "N/A".to_string());
-      let country = extract_country_from_trace(&body).unwrap_or_else(|| "N/A".to_string());
-      (ip, country)
-    }
-    Err(_) => ("N/A".to_string(), "N/A".to_string()),
-  };
+struct ParsedVersion {
+  core: Vec<u64>,
+  pre: Vec<String>,
+}
 
-  SpeedTestResult {
-    download_mbps: (download * 100.0).round() / 100.0,
-    upload_mbps: (upload * 100.0).round() / 100.0,
-    latency_ms: (latency * 100.0).round() / 100.0,
-    jitter_ms: (jitter * 100.0).round() / 100.0,
-    ip,
-    country,
-    error: None,
+fn parse_version(version: &str) -> ParsedVersion {
+  let version = version.trim().trim_start_matches(['v', 'V']);
+  // Build metadata never affects precedence.
+  let version = version.split('+').next().unwrap_or("");
+  let (core, pre) = version.split_once('-').unwrap_or((version, ""));
+  ParsedVersion {
+    core: core.split('.').map(|part| part.parse::<u64>().unwrap_or(0)).collect(),
+    pre: pre.split('.').filter(|id| !id.is_empty()).map(str::to_string).collect(),
   }
 }
 
-#[tauri::command]
-async fn speedtest_hetzner() -> SpeedTestResult {
-  let client = HttpClient::new();
-  let (latency, jitter) = measure_ping(&client, "https://www.gstatic.com/generate_204").await;
-  let download = measure_download_hetzner(&client).await;
-  let upload = measure_upload_hetzner(&client).await;
-  let (ip, country) = match client
-    .get(IPWHOIS_URL)
-    .header("User-Agent", "PulseNet")
-    .send()
-    .await
-  {
-    Ok(resp) => {
-      let body = resp.text().await.unwrap_or_default();
-      extract_ip_country_from_ipwhois(&body)
-    }
-    Err(_) => ("N/A".to_string(), "N/A".to_string()),
-  };
-
-  SpeedTestResult {
-    download_mbps: (download * 100.0).round() / 100.0,
-    upload_mbps: (upload * 100.0).round() / 100.0,
-    latency_ms: (latency * 100.0).round() / 100.0,
-    jitter_ms: (jitter * 100.0).round() / 100.0,
-    ip,
-    country,
-    error: None,
+fn compare_prerelease_ids(left: &str, right: &str) -> std::cmp::Ordering {
+  // Numeric identifiers compare numerically and always sort below alphanumeric ones.
+  match (left.parse::<u64>(), right.parse::<u64>()) {
+    (Ok(left), Ok(right)) => left.cmp(&right),
+    (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+    (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+    (Err(_), Err(_)) => left.cmp(right),
   }
 }
 
-fn parse_version_parts(version: &str) -> Vec<u64> {
-  version
-    .trim_start_matches('v')
-    .split('.')
-    .map(|part| part.parse::<u64>().unwrap_or(0))
-    .collect()
+fn compare_versions(left: &str, right: &str) -> std::cmp::Ordering {
+  let left = parse_version(left);
+  let right = parse_version(right);
+  for idx in 0..left.core.len().max(right.core.len()) {
+    let ordering = left.core.get(idx).unwrap_or(&0).cmp(right.core.get(idx).unwrap_or(&0));
+    if ordering != std::cmp::Ordering::Equal {
+      return ordering;
+    }
+  }
+  // A pre-release sorts below the release it precedes: 1.2.0-beta < 1.2.0.
+  match (left.pre.is_empty(), right.pre.is_empty()) {
+    (true, true) => std::cmp::Ordering::Equal,
+    (true, false) => std::cmp::Ordering::Greater,
+    (false, true) => std::cmp::Ordering::Less,
+    (false, false) => left
+      .pre
+      .iter()
+      .zip(&right.pre)
+      .map(|(left, right)| compare_prerelease_ids(left, right))
+      .find(|ordering| *ordering != std::cmp::Ordering::Equal)
+      .unwrap_or_else(|| left.pre.len().cmp(&right.pre.len())),
+  }
 }
 
 fn is_newer_version(latest: &str, current: &str) -> bool {
-  let latest_parts = parse_version_parts(latest);
-  let current_parts = parse_version_parts(current);
-  let max_len = latest_parts.len().max(current_parts.len());
-  for idx in 0..max_len {
-    let left = *latest_parts.get(idx).unwrap_or(&0);
-    let right = *current_parts.get(idx).unwrap_or(&0);
-    if left > right {
-      return true;
-    }
-    if left < right {
-      return false;
-    }
-  }
-  false
+  compare_versions(latest, current) == std::cmp::Ordering::Greater
 }
 
 #[tauri::command]
-async fn check_for_updates(include_prerelease: Option<bool>) -> UpdateCheckResult {
-  let client = HttpClient::new();
+async fn check_for_updates(app: AppHandle, include_prerelease: Option<bool>) -> UpdateCheckResult {
+  let state: State<AppState> = app.state();
+  let (client, _) = shared_http_client(&state);
   let include_prerelease = include_prerelease.unwrap_or(false);
   let current_version = env!("CARGO_PKG_VERSION").to_string();
-  let response = client
-    .get(if include_prerelease { GITHUB_RELEASES_LIST_URL } else { GITHUB_RELEASES_URL })
-    .header("User-Agent", "PulseNet")
-    .send()
-    .await;
-  if response.is_err() {
-    return UpdateCheckResult {
-      current_version,
-      latest_version: String::new(),
-      update_available: false,
-      is_prerelease: false,
-      url: format!("https://github.com/{}/releases/latest", GITHUB_REPO),
-      error: Some("update-check-failed".to_string()),
+  let failure = |error: ErrorCode, error_message: Option<String>| UpdateCheckResult {
+    current_version: current_version.clone(),
+    latest_version: String::new(),
+    update_available: false,
+    is_prerelease: false,
+    url: format!("https://github.com/{}/releases/latest", GITHUB_REPO),
+    asset_url: None,
+    asset_name: None,
+    sha256: None,
+    error: Some(error),
+    error_message,
+  };
+  let mut attempts: u32 = 0;
+  let response = loop {
+    let sent = client
+      .get(if include_prerelease { GITHUB_RELEASES_LIST_URL } else { GITHUB_RELEASES_URL })
+      .header("User-Agent", "PulseNet")
+      .timeout(Duration::from_secs(UPDATE_CHECK_TIMEOUT_SECS))
+      .send()
+      .await;
+    match sent {
+      Ok(response) => break response,
+      Err(_) if attempts < UPDATE_CHECK_RETRIES => {
+        attempts += 1;
+        tokio::time::sleep(Duration::from_millis(UPDATE_CHECK_RETRY_DELAY_MS)).await;
+      }
+      Err(error) => return failure(ErrorCode::UpdateCheckFailed, Some(error.to_string())),
+    }
+  };
+  // GitHub signals an exhausted quota with 403 (or 429) and a zero remaining count.
+  let header = |name: &str| response.headers().get(name).and_then(|value| value.to_str().ok());
+  let status = response.status().as_u16();
+  if (status == 403 || status == 429) && header("x-ratelimit-remaining") == Some("0") {
+    let message = match header("x-ratelimit-reset").and_then(|value| value.trim().parse::<u128>().ok()) {
+      Some(reset) => format!("GitHub API rate limit exceeded; try again after {}", format_utc_timestamp(reset * 1000)),
+      None => "GitHub API rate limit exceeded; try again later".to_string(),
     };
+    return failure(ErrorCode::RateLimited, Some(message));
   }
-  let json = response.unwrap().json::<serde_json::Value>().await;
+  let json = response.json::<serde_json::Value>().await;
   if json.is_err() {
-    return UpdateCheckResult {
-      current_version,
-      latest_version: String::new(),
-      update_available: false,
-      is_prerelease: false,
-      url: format!("https://github.com/{}/releases/latest", GITHUB_REPO),
-      error: Some("invalid-response".to_string()),
-    };
+    return failure(ErrorCode::InvalidResponse, None);
   }
   let data = json.unwrap();
   let release = if include_prerelease {
@@ -1021,6 +11979,13 @@ async fn check_for_updates(include_prerelease: Option<bool>) -> UpdateCheckResul
     .and_then(|value| value.as_str())
     .unwrap_or(&format!("https://github.com/{}/releases/latest", GITHUB_REPO))
     .to_string();
+  let asset = release
+    .get("assets")
+    .and_then(|value| value.as_array())
+    .and_then(|assets| select_update_asset(assets));
+  let notes = release.get("body").and_then(|value| value.as_str()).unwrap_or("");
+  let sha256 = release_notes_sha256(notes, asset.as_ref().map(|(name, _)| name.as_str()));
+  let (asset_name, asset_url) = asset.unzip();
 
   UpdateCheckResult {
     current_version,
@@ -1028,8 +11993,241 @@ async fn check_for_updates(include_prerelease: Option<bool>) -> UpdateCheckResul
     update_available,
     is_prerelease,
     url,
+    asset_url,
+    asset_name,
+    sha256,
+    error: None,
+    error_message: None,
+  }
+}
+
+fn is_sha256_hex(value: &str) -> bool {
+  value.len() == 64 && value.chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+// Release notes list checksums as `<hash>  <asset>` lines; without an asset name only an unambiguous hash counts.
+fn release_notes_sha256(notes: &str, asset_name: Option<&str>) -> Option<String> {
+  let hashes_in = |line: &str| -> Vec<String> {
+    line
+      .split(|ch: char| !ch.is_ascii_alphanumeric())
+      .filter(|token| is_sha256_hex(token))
+      .map(|token| token.to_ascii_lowercase())
+      .collect()
+  };
+  if let Some(asset) = asset_name {
+    if let Some(hash) = notes.lines().filter(|line| line.contains(asset)).flat_map(hashes_in).next() {
+      return Some(hash);
+    }
+  }
+  let mut hashes: Vec<String> = notes.lines().flat_map(hashes_in).collect();
+  hashes.sort();
+  hashes.dedup();
+  if hashes.len() == 1 {
+    hashes.pop()
+  } else {
+    None
+  }
+}
+
+// The architecture an asset filename names, if any. x86_64 is checked before x86 since its spelling contains it.
+fn update_asset_arch(name: &str) -> Option<&'static str> {
+  let lower = name.to_ascii_lowercase();
+  UPDATE_ASSET_ARCHES
+    .iter()
+    .find(|(_, tokens)| tokens.iter().any(|token| lower.contains(token)))
+    .map(|(arch, _)| *arch)
+}
+
+// Walks UPDATE_ASSET_EXTENSIONS in order; within an extension an asset naming our architecture beats one naming
+// none, and assets built for another architecture are skipped. Returns (name, download url).
+fn select_update_asset(assets: &[serde_json::Value]) -> Option<(String, String)> {
+  let candidates: Vec<(String, String)> = assets
+    .iter()
+    .filter_map(|asset| {
+      let name = asset.get("name")?.as_str()?;
+      let url = asset.get("browser_download_url")?.as_str()?;
+      Some((name.to_string(), url.to_string()))
+    })
+    .collect();
+  for extension in UPDATE_ASSET_EXTENSIONS {
+    let matching: Vec<&(String, String)> =
+      candidates.iter().filter(|(name, _)| name.to_ascii_lowercase().ends_with(extension)).collect();
+    let own_arch = matching.iter().find(|(name, _)| update_asset_arch(name) == Some(env::consts::ARCH));
+    let generic = matching.iter().find(|(name, _)| update_asset_arch(name).is_none());
+    if let Some(asset) = own_arch.or(generic) {
+      return Some((*asset).clone());
+    }
+  }
+  None
+}
+
+fn update_download_path(app: &AppHandle, url: &str) -> PathBuf {
+  // The name comes from the server, so keep only plain filename characters and never let
+  // separators or a dot-only name walk out of the cache dir.
+  let file_name: String = url
+    .split(['?', '#'])
+    .next()
+    .and_then(|path| path.rsplit(['/', '\\']).next())
+    .unwrap_or_default()
+    .chars()
+    .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    .collect();
+  let file_name = if file_name.chars().all(|c| c == '.') {
+    "PulseNet-update.bin".to_string()
+  } else {
+    file_name
+  };
+  let dir = app
+    .path_resolver()
+    .app_cache_dir()
+    .filter(|dir| fs::create_dir_all(dir).is_ok())
+    .unwrap_or_else(env::temp_dir);
+  dir.join(file_name)
+}
+
+fn hex_digest(digest: &[u8]) -> String {
+  digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[tauri::command]
+async fn download_update(
+  app: AppHandle,
+  asset_url: String,
+  expected_sha256: String,
+  operation_id: Option<u64>,
+) -> Result<UpdateDownloadResult, ErrorCode> {
+  run_cancellable(&app, operation_id, run_update_download(app.clone(), asset_url, expected_sha256)).await
+}
+
+async fn run_update_download(app: AppHandle, url: String, expected_sha256: String) -> UpdateDownloadResult {
+  let path = update_download_path(&app, &url);
+  let mut result = UpdateDownloadResult {
+    success: false,
+    path: path.to_string_lossy().to_string(),
+    bytes_downloaded: 0,
+    resumes: 0,
+    sha256: String::new(),
+    verified: false,
     error: None,
+    error_message: None,
+  };
+  // Nothing is handed to the installer unverified, so refuse to start without a usable checksum.
+  let expected = expected_sha256.trim().to_ascii_lowercase();
+  if !is_sha256_hex(&expected) {
+    result.error = Some(ErrorCode::InvalidInput);
+    result.error_message = Some("expected_sha256 must be a 64-character hex SHA-256 digest".to_string());
+    return result;
+  }
+  let mut file = match fs::File::create(&path) {
+    Ok(file) => file,
+    Err(error) => {
+      result.error = Some(ErrorCode::FileWriteFailed);
+      result.error_message = Some(error.to_string());
+      return result;
+    }
+  };
+
+  let (client, _) = shared_http_client(&app.state());
+  let mut hasher = Sha256::new();
+  let mut downloaded: u64 = 0;
+  let mut total: Option<u64> = None;
+  let mut attempts: u32 = 0;
+  let mut last_error = String::new();
+
+  loop {
+    if attempts > UPDATE_DOWNLOAD_MAX_RETRIES {
+      result.bytes_downloaded = downloaded;
+      result.error = Some(ErrorCode::DownloadFailed);
+      result.error_message = Some(last_error);
+      return result;
+    }
+    if attempts > 0 {
+      tokio::time::sleep(Duration::from_millis(UPDATE_DOWNLOAD_RETRY_DELAY_MS * attempts as u64)).await;
+    }
+    attempts += 1;
+
+    let mut request = client
+      .get(&url)
+      .header("User-Agent", "PulseNet")
+      .timeout(Duration::from_secs(UPDATE_DOWNLOAD_REQUEST_TIMEOUT_SECS));
+    if downloaded > 0 {
+      request = request.header("Range", format!("bytes={}-", downloaded));
+    }
+    let mut response = match request.send().await.and_then(|response| response.error_for_status()) {
+      Ok(response) => response,
+      Err(error) => {
+        last_error = error.to_string();
+        continue;
+      }
+    };
+
+    if downloaded > 0 {
+      if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        result.resumes += 1;
+      } else {
+        // Server ignored the Range header, so start over from the beginning.
+        downloaded = 0;
+        hasher = Sha256::new();
+        if let Err(error) = file.set_len(0).and_then(|_| file.seek(SeekFrom::Start(0))) {
+          result.error = Some(ErrorCode::FileWriteFailed);
+          result.error_message = Some(error.to_string());
+          return result;
+        }
+      }
+    }
+    if total.is_none() {
+      total = response.content_length().map(|length| length + downloaded);
+    }
+
+    let mut interrupted = false;
+    loop {
+      match response.chunk().await {
+        Ok(Some(chunk)) => {
+          if let Err(error) = file.write_all(&chunk) {
+            result.bytes_downloaded = downloaded;
+            result.error = Some(ErrorCode::FileWriteFailed);
+            result.error_message = Some(error.to_string());
+            return result;
+          }
+          hasher.update(&chunk);
+          downloaded += chunk.len() as u64;
+          // A successful chunk means the connection is making progress again.
+          attempts = 1;
+          let _ = app.emit_all(
+            "update-download-progress",
+            UpdateDownloadProgress {
+              downloaded,
+              total,
+              resumes: result.resumes,
+            },
+          );
+        }
+        Ok(None) => break,
+        Err(error) => {
+          last_error = error.to_string();
+          interrupted = true;
+          break;
+        }
+      }
+    }
+    if !interrupted && total.is_none_or(|total| downloaded >= total) {
+      break;
+    }
+  }
+
+  let _ = file.flush();
+  result.bytes_downloaded = downloaded;
+  result.sha256 = hex_digest(&hasher.finalize());
+  drop(file);
+  if expected != result.sha256 {
+    let _ = fs::remove_file(&path);
+    result.error = Some(ErrorCode::ChecksumMismatch);
+    result.error_message = Some(format!("expected {}, got {}", expected, result.sha256));
+    return result;
   }
+  result.verified = true;
+  result.success = true;
+  result
 }
 
 fn handle_close_requested(window: &Window, state: &State<AppState>) {
@@ -1047,10 +12245,15 @@ fn handle_close_requested(window: &Window, state: &State<AppState>) {
     let _ = window.hide();
     return;
   }
+  if action == "minimize" {
+    let _ = window.minimize();
+    return;
+  }
   let _ = window.emit("close-requested", serde_json::json!({ "reason": "close" }));
 }
 
 fn show_main_window(app: &AppHandle) {
+  mark_activity(&app.state());
   if let Some(window) = app.get_window("main") {
     let _ = window.show();
     let _ = window.unminimize();
@@ -1068,6 +12271,21 @@ fn main() {
 
   tauri::Builder::default()
     .manage(AppState::default())
+    .setup(|app| {
+      if let Some(action) = read_close_action_pref(&app.handle()) {
+        if let Ok(mut guard) = app.state::<AppState>().close_action.lock() {
+          *guard = action;
+        }
+      }
+      // A temporary DNS change from a previous run that was never confirmed.
+      let handle = app.handle();
+      tauri::async_runtime::spawn_blocking(move || {
+        if let Some(result) = revert_pending_dns_change(&handle) {
+          let _ = handle.emit_all("dns-change-reverted", result);
+        }
+      });
+      Ok(())
+    })
     .system_tray(SystemTray::new().with_menu(tray_menu))
     .on_system_tray_event(|app, event| {
       match event {
@@ -1082,10 +12300,13 @@ fn main() {
               let _ = window.emit("tray-open-page", serde_json::json!({ "page": "settings" }));
             }
           }
+          // exit() and restart() end the process without a RunEvent::Exit, so revert here too.
           "restart" => {
+            revert_pending_dns_change(app);
             app.restart();
           }
           "exit" => {
+            revert_pending_dns_change(app);
             app.exit(0);
           }
           _ => {}
@@ -1094,11 +12315,14 @@ fn main() {
       }
     })
     .on_window_event(|event| {
+      if let WindowEvent::Focused(true) = event.event() {
+        mark_activity(&event.window().state());
+      }
       if let WindowEvent::CloseRequested { api, .. } = event.event() {
         api.prevent_close();
         let window = event.window();
         let state: State<AppState> = window.state();
-        handle_close_requested(&window, &state);
+        handle_close_requested(window, &state);
       }
     })
     .invoke_handler(tauri::generate_handler![
@@ -1118,8 +12342,193 @@ fn main() {
       reset_adapter_dns,
       speedtest_cloudflare,
       speedtest_hetzner,
-      check_for_updates
+      check_for_updates,
+      start_throughput_monitor,
+      stop_throughput_monitor,
+      check_time_sync,
+      voip_quality_test,
+      save_diagnostic_report,
+      get_adapter_metric,
+      set_adapter_metric,
+      dns_query,
+      test_game_latency,
+      tcp_quality_test,
+      test_lan_baseline,
+      get_connection_type,
+      download_update,
+      test_split_dns,
+      refresh_dns_adapters,
+      test_dns_failover,
+      trace_cname_chain,
+      record_activity,
+      set_auto_hide,
+      dns_cache_benchmark,
+      check_nat_type,
+      list_active_connections,
+      set_monitor_logging,
+      recommend_dns,
+      check_ipv6_usage,
+      estimate_transfer_times,
+      check_service_reachability,
+      begin_operation,
+      cancel_operation,
+      dns_response_diagnostics,
+      detect_transparent_proxy,
+      get_arp_table,
+      start_ping_monitor,
+      stop_ping_monitor,
+      get_uptime_percent,
+      test_vpn_mtu,
+      get_tls_info,
+      check_ntp_offset,
+      detect_multiple_default_routes,
+      compare_to_history,
+      detect_icmp_rate_limit,
+      set_adapter_dns_temporary,
+      confirm_dns_change,
+      detect_packet_reordering,
+      get_network_category,
+      set_network_category,
+      measure_one_way_delay,
+      check_dns_propagation,
+      realistic_throughput_test,
+      get_system_doh_setting,
+      set_system_doh_setting,
+      compare_geodns,
+      detect_cgnat,
+      ping_dns_servers,
+      test_udp_fragmentation,
+      list_speedtest_servers,
+      dns_reliability_test,
+      test_happy_eyeballs,
+      start_mtr,
+      stop_mtr,
+      speedtest_dual_stack,
+      test_asymmetric_mtu,
+      test_all_system_resolvers,
+      stability_test,
+      detect_local_resolver,
+      benchmark_tls_handshakes,
+      start_ping_stream,
+      stop_ping_stream,
+      speedtest,
+      is_elevated,
+      relaunch_as_admin,
+      traceroute,
+      scan_ports,
+      get_speedtest_history,
+      clear_speedtest_history,
+      get_public_ip_info,
+      set_proxy,
+      flush_dns_cache
     ])
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    .build(tauri::generate_context!())
+    .expect("error while running tauri application")
+    .run(|app, event| {
+      if let RunEvent::Exit = event {
+        revert_pending_dns_change(app);
+        stop_mtr_task(&app.state::<AppState>());
+        stop_all_ping_streams(&app.state::<AppState>());
+      }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn error_codes_serialize_as_kebab_case() {
+    let cases = [
+      (ErrorCode::InvalidDomain, "invalid-domain"),
+      (ErrorCode::InvalidServer, "invalid-server"),
+      (ErrorCode::InvalidInput, "invalid-input"),
+      (ErrorCode::ResolveFailed, "resolve-failed"),
+      (ErrorCode::PermissionDenied, "permission-denied"),
+      (ErrorCode::PingFailed, "ping-failed"),
+      (ErrorCode::Timeout, "timeout"),
+      (ErrorCode::LookupFailed, "lookup-failed"),
+      (ErrorCode::CommandFailed, "command-failed"),
+      (ErrorCode::UnsupportedPlatform, "unsupported-platform"),
+      (ErrorCode::RequestFailed, "request-failed"),
+      (ErrorCode::InvalidResponse, "invalid-response"),
+      (ErrorCode::UpdateCheckFailed, "update-check-failed"),
+      (ErrorCode::FileWriteFailed, "file-write-failed"),
+      (ErrorCode::ElevationRequired, "elevation-required"),
+      (ErrorCode::TooManyServers, "too-many-servers"),
+      (ErrorCode::GatewayNotFound, "gateway-not-found"),
+      (ErrorCode::DownloadFailed, "download-failed"),
+      (ErrorCode::ChecksumMismatch, "checksum-mismatch"),
+      (ErrorCode::Cancelled, "cancelled"),
+      (ErrorCode::FragmentationNeeded, "fragmentation-needed"),
+      (ErrorCode::TlsHandshakeFailed, "tls-handshake-failed"),
+      (ErrorCode::RateLimited, "rate-limited"),
+    ];
+    for (code, expected) in cases {
+      assert_eq!(serde_json::to_value(code).unwrap(), serde_json::Value::from(expected));
+    }
+  }
+
+  #[tokio::test]
+  async fn cancelled_operation_stops_promptly() {
+    let token = CancelToken::default();
+    let canceller = token.clone();
+    tokio::spawn(async move {
+      tokio::time::sleep(Duration::from_millis(50)).await;
+      canceller.cancel();
+    });
+    let started = Instant::now();
+    let outcome = run_with_token(&token, tokio::time::sleep(Duration::from_secs(30))).await;
+    assert_eq!(outcome, Err(ErrorCode::Cancelled));
+    assert!(started.elapsed() < Duration::from_secs(1));
+  }
+
+  #[tokio::test]
+  async fn uncancelled_operation_returns_its_value() {
+    let token = CancelToken::default();
+    assert_eq!(run_with_token(&token, async { 7 }).await, Ok(7));
+  }
+
+  #[test]
+  fn versions_compare_numerically() {
+    assert!(is_newer_version("1.10.0", "1.9.0"));
+    assert!(is_newer_version("2.0", "1.99.99"));
+    assert!(!is_newer_version("1.2.0", "1.2"));
+  }
+
+  #[test]
+  fn prereleases_sort_before_their_release() {
+    let ordered = ["1.2.0-alpha", "1.2.0-alpha.1", "1.2.0-beta", "1.2.0-rc", "1.2.0"];
+    for pair in ordered.windows(2) {
+      assert_eq!(compare_versions(pair[0], pair[1]), std::cmp::Ordering::Less, "{} < {}", pair[0], pair[1]);
+    }
+  }
+
+  #[test]
+  fn version_prefix_is_ignored() {
+    assert_eq!(compare_versions("v1.4.2", "1.4.2"), std::cmp::Ordering::Equal);
+    assert!(is_newer_version("v1.5.0", "1.4.9"));
+  }
+
+  #[test]
+  fn time_exceeded_is_matched_to_the_quoted_echo_request() {
+    let mut request = vec![0x45u8; 20];
+    request.extend_from_slice(&[8, 0, 0, 0, 0x12, 0x34, 0x00, 0x07]);
+    let mut message = vec![0x45u8; 20];
+    message.extend_from_slice(&[11, 0, 0, 0, 0, 0, 0, 0]);
+    message.extend_from_slice(&request);
+    assert_eq!(answered_echo(&message, false), Some((0x1234, 7)));
+
+    let mut reply = vec![0x45u8; 20];
+    reply.extend_from_slice(&[0, 0, 0, 0, 0x12, 0x34, 0x00, 0x08]);
+    assert_eq!(answered_echo(&reply, false), Some((0x1234, 8)));
+
+    let mut quoted_v6 = vec![3u8, 0, 0, 0, 0, 0, 0, 0];
+    quoted_v6.extend_from_slice(&[0u8; 40]);
+    quoted_v6.extend_from_slice(&[128, 0, 0, 0, 0xab, 0xcd, 0x00, 0x02]);
+    assert_eq!(answered_echo(&quoted_v6, true), Some((0xabcd, 2)));
+    // An error quoting anything other than an echo request is ignored.
+    message[20 + 8 + 20] = 13;
+    assert_eq!(answered_echo(&message, false), None);
+  }
 }
@@ -191,7 +191,7 @@ const usePing = (host, statusTexts, intervalMs, enabled = true, showPausedState
         if (isCancelled) return;
         if (result.error) {
           pushHistory(null);
-          if (result.error.includes('permission')) {
+          if (result.error === 'permission-denied') {
             setPingData({
               status: statusTexts.needAdmin,
               hasError: true,
@@ -676,11 +676,15 @@ const App = () => {
     const saved = localStorage.getItem('closeAction');
     return saved || 'ask';
   });
+  const closeActionLoadedRef = useRef(false);
+  const [autoHideMinutes, setAutoHideMinutes] = useState(() => localStorage.getItem('autoHideMinutes') || '0');
   const [speedStarted, setSpeedStarted] = useState(false);
   const [speedMetrics, setSpeedMetrics] = useState(null);
+  const [speedComparison, setSpeedComparison] = useState(null);
   const [speedLoading, setSpeedLoading] = useState(false);
   const [speedPhase, setSpeedPhase] = useState('idle');
   const [speedProvider, setSpeedProvider] = useState(() => localStorage.getItem('speedProvider') || 'cloudflare');
+  const [speedLite, setSpeedLite] = useState(() => localStorage.getItem('speedLite') === 'true');
   const [publicNetworkInfo, setPublicNetworkInfo] = useState({
     ip: 'N/A',
     country: 'N/A',
@@ -694,7 +698,7 @@ const App = () => {
     const saved = localStorage.getItem('betaUpdates');
     return saved === 'true';
   });
-  const speedRequestRef = useRef({ id: 0 });
+  const speedRequestRef = useRef({ id: 0, operationId: null });
   const speedPhaseTimersRef = useRef([]);
   const [dnsDomain, setDnsDomain] = useState('');
   const [dnsResults, setDnsResults] = useState([]);
@@ -734,6 +738,7 @@ const App = () => {
   const [dnsSecondaryInput, setDnsSecondaryInput] = useState('');
   const [dnsManagerStatus, setDnsManagerStatus] = useState('');
   const [updateStatus, setUpdateStatus] = useState('');
+  const [autoLaunchError, setAutoLaunchError] = useState('');
   const [updateInfo, setUpdateInfo] = useState(null);
   const [updateModalOpen, setUpdateModalOpen] = useState(false);
   const [closeModalOpen, setCloseModalOpen] = useState(false);
@@ -860,6 +865,7 @@ const App = () => {
       setPublicNetworkInfo({
         ip: nextIp,
         country: nextCountry,
+        location: [result?.city, result?.region].filter(Boolean).join(', '),
       });
     } catch (error) {
       console.error('Failed to load public network info:', error);
@@ -911,20 +917,20 @@ const App = () => {
   }, []);
 
   useEffect(() => {
-    const saved = localStorage.getItem('closeAction');
-    if (saved) {
-      setCloseAction(saved);
-      invoke('set_close_action', { action: saved }).catch(() => {});
-      return;
-    }
     const loadCloseAction = async () => {
       try {
+        // The backend persists the choice now; only migrate the local copy when it has nothing saved.
         const action = await invoke('get_close_action');
-        if (action) {
+        const saved = localStorage.getItem('closeAction');
+        if (action === 'ask' && saved && saved !== 'ask') {
+          invoke('set_close_action', { action: saved }).catch(() => {});
+        } else if (action) {
           setCloseAction(action);
         }
       } catch (error) {
         console.error('Failed to load close action:', error);
+      } finally {
+        closeActionLoadedRef.current = true;
       }
     };
     loadCloseAction();
@@ -932,9 +938,33 @@ const App = () => {
 
   useEffect(() => {
     localStorage.setItem('closeAction', closeAction);
-    invoke('set_close_action', { action: closeAction }).catch(() => {});
+    if (closeActionLoadedRef.current) {
+      invoke('set_close_action', { action: closeAction }).catch(() => {});
+    }
   }, [closeAction]);
 
+  useEffect(() => {
+    localStorage.setItem('autoHideMinutes', autoHideMinutes);
+    const minutes = Number(autoHideMinutes) || 0;
+    invoke('set_auto_hide', { minutes, enabled: minutes > 0 }).catch(() => {});
+  }, [autoHideMinutes]);
+
+  useEffect(() => {
+    if (autoHideMinutes === '0') return undefined;
+    let lastSent = 0;
+    const handleActivity = () => {
+      const now = Date.now();
+      if (now - lastSent < 5000) return;
+      lastSent = now;
+      invoke('record_activity').catch(() => {});
+    };
+    const events = ['mousemove', 'mousedown', 'keydown', 'wheel'];
+    events.forEach((name) => window.addEventListener(name, handleActivity, { passive: true }));
+    return () => {
+      events.forEach((name) => window.removeEventListener(name, handleActivity));
+    };
+  }, [autoHideMinutes]);
+
   useEffect(() => {
     localStorage.setItem('sidebarCollapsed', String(isSidebarCollapsed));
   }, [isSidebarCollapsed]);
@@ -943,6 +973,10 @@ const App = () => {
     localStorage.setItem('speedProvider', speedProvider);
   }, [speedProvider]);
 
+  useEffect(() => {
+    localStorage.setItem('speedLite', String(speedLite));
+  }, [speedLite]);
+
   useEffect(() => {
     localStorage.setItem('showPublicIp', String(showPublicIp));
   }, [showPublicIp]);
@@ -1244,7 +1278,9 @@ const App = () => {
 
   const loadDnsAdapters = useCallback(async (forceRefresh = false) => {
     try {
-      const adapters = await invoke('list_dns_adapters', { forceRefresh });
+      const adapters = forceRefresh
+        ? await invoke('refresh_dns_adapters')
+        : await invoke('list_dns_adapters', { forceRefresh });
       const normalized = Array.isArray(adapters) ? adapters : [];
       setDnsAdapters(normalized);
       if (normalized.length === 0) {
@@ -1285,17 +1321,26 @@ const App = () => {
         adapterName: dnsSelectedAdapter,
         primaryDns: dnsPrimaryInput.trim(),
         secondaryDns: dnsSecondaryInput.trim() || null,
+        verify: true,
       });
-      if (result && result.success) {
-        setDnsManagerStatus(texts.dnsManagerApplied);
+      if (result && result.success && result.noChange) {
+        setDnsManagerStatus(texts.dnsManagerNoChange);
+      } else if (result && result.success) {
+        setDnsManagerStatus(
+          result.appliedAndVerified
+            ? texts.dnsManagerApplied
+            : `${texts.dnsManagerNotVerified}${result.errorMessage ? ` (${result.errorMessage})` : ''}`
+        );
         addLogEntry({
           type: 'dns',
           title: texts.logDnsResult,
           detail: `${dnsSelectedAdapter} • ${dnsPrimaryInput.trim()}${dnsSecondaryInput.trim() ? `, ${dnsSecondaryInput.trim()}` : ''}`,
         });
         await loadDnsAdapters(true);
+      } else if (result && result.rolledBack) {
+        setDnsManagerStatus(texts.dnsManagerRolledBack);
       } else {
-        setDnsManagerStatus(result?.error || texts.dnsManagerFailed);
+        setDnsManagerStatus(result?.errorMessage || result?.error || texts.dnsManagerFailed);
       }
     } catch (error) {
       console.error('Failed to apply system dns:', error);
@@ -1320,7 +1365,7 @@ const App = () => {
         });
         await loadDnsAdapters(true);
       } else {
-        setDnsManagerStatus(result?.error || texts.dnsManagerFailed);
+        setDnsManagerStatus(result?.errorMessage || result?.error || texts.dnsManagerFailed);
       }
     } catch (error) {
       console.error('Failed to reset system dns:', error);
@@ -1336,6 +1381,7 @@ const App = () => {
     const requestId = speedRequestRef.current.id;
     setSpeedStarted(false);
     setSpeedMetrics(null);
+    setSpeedComparison(null);
     setSpeedLoading(true);
     setSpeedPhase('download');
     speedPhaseTimersRef.current.push(window.setTimeout(() => {
@@ -1344,7 +1390,16 @@ const App = () => {
       }
     }, SPEED_PHASE_DOWNLOAD_DELAY_MS));
     const command = speedProvider === 'hetzner' ? 'speedtest_hetzner' : 'speedtest_cloudflare';
-    invoke(command)
+    invoke('begin_operation')
+      .catch(() => null)
+      .then((operationId) => {
+        if (requestId !== speedRequestRef.current.id) {
+          if (operationId) invoke('cancel_operation', { id: operationId }).catch(() => {});
+          return null;
+        }
+        speedRequestRef.current.operationId = operationId;
+        return invoke(command, { operationId, lite: speedLite });
+      })
       .then((result) => {
         if (requestId !== speedRequestRef.current.id) return;
         if (result && !result.error) {
@@ -1358,16 +1413,26 @@ const App = () => {
             title: texts.logSpeedComplete,
             detail: `${result.downloadMbps} Mbps ? • ${result.uploadMbps} Mbps ? • ${result.latencyMs} ms${countryPart}`,
           });
+          invoke('compare_to_history')
+            .then((comparison) => {
+              if (requestId === speedRequestRef.current.id && comparison && comparison.samples > 0) {
+                setSpeedComparison(comparison);
+              }
+            })
+            .catch(() => {});
           return;
         }
         setSpeedPhase('idle');
       })
       .catch((error) => {
-        console.error('Speed test failed:', error);
+        if (error !== 'cancelled') {
+          console.error('Speed test failed:', error);
+        }
         setSpeedPhase('idle');
       })
       .finally(() => {
         if (requestId === speedRequestRef.current.id) {
+          speedRequestRef.current.operationId = null;
           setSpeedLoading(false);
           clearSpeedPhaseTimers();
         }
@@ -1376,10 +1441,16 @@ const App = () => {
 
   const handleStopSpeed = () => {
     clearSpeedPhaseTimers();
+    const { operationId } = speedRequestRef.current;
+    if (operationId) {
+      invoke('cancel_operation', { id: operationId }).catch(() => {});
+      speedRequestRef.current.operationId = null;
+    }
     speedRequestRef.current.id += 1;
     setSpeedLoading(false);
     setSpeedStarted(false);
     setSpeedMetrics(null);
+    setSpeedComparison(null);
     setSpeedPhase('idle');
   };
 
@@ -1389,7 +1460,11 @@ const App = () => {
     localStorage.setItem('autoLaunch', String(next));
     try {
       const updated = await invoke('set_auto_launch', { enabled: next });
-      setAutoLaunch(Boolean(updated));
+      setAutoLaunch(Boolean(updated?.enabled));
+      localStorage.setItem('autoLaunch', String(Boolean(updated?.enabled)));
+      setAutoLaunchError(
+        updated?.error ? `${texts.settingsAutoLaunchFailed}: ${updated.errorMessage || updated.error}` : ''
+      );
     } catch (error) {
       console.error('Failed to update auto-launch:', error);
     }
@@ -1843,6 +1918,7 @@ const App = () => {
       settingsGeneral: 'General',
       settingsAutoLaunch: 'Auto launch',
       settingsAutoLaunchHint: 'Start app when Windows boots',
+      settingsAutoLaunchFailed: 'Could not change startup',
       settingsPingInterval: 'Ping interval (ms)',
       settingsPingIntervalHint: 'How often pings refresh',
       settingsOptimization: 'Optimization',
@@ -1863,8 +1939,13 @@ const App = () => {
       closeActionTitle: 'Action to closing',
       closeActionHint: 'Choose what happens when closing the app',
       closeActionHide: 'Hide',
+      closeActionMinimize: 'Minimize',
       closeActionExit: 'Exit',
       closeActionAsk: 'Ask every time',
+      autoHideTitle: 'Auto-hide',
+      autoHideHint: 'Hide the window to tray after a period of inactivity',
+      autoHideOff: 'Off',
+      autoHideMinutes: 'min',
       monitoring: 'Monitoring',
       add: 'Add',
       edit: 'Edit',
@@ -1941,6 +2022,9 @@ const App = () => {
       dnsManagerReset: 'Reset (DHCP)',
       dnsManagerNoAdapters: 'No adapter found',
       dnsManagerApplied: 'DNS updated successfully',
+      dnsManagerNoChange: 'These were already your DNS servers',
+      dnsManagerNotVerified: 'Applied, but the new DNS is not in effect yet',
+      dnsManagerRolledBack: 'The new DNS did not resolve, so your previous settings were restored',
       dnsManagerResetDone: 'DNS reset to automatic',
       dnsManagerFailed: 'Failed to update DNS',
       usable: 'Usable',
@@ -1950,6 +2034,7 @@ const App = () => {
       speedUpload: 'Upload',
       speedLatency: 'Latency',
       speedJitter: 'Jitter',
+      speedConnection: 'Connection',
       speedStart: 'Start',
       speedStop: 'Stop',
       speedPhaseIdle: 'Ready',
@@ -1959,6 +2044,9 @@ const App = () => {
       speedProviderTitle: 'Provider',
       speedProviderCloudflare: 'Cloudflare',
       speedProviderHetzner: 'Hetzner',
+      speedLite: 'Lite (~1.5 MB)',
+      speedEstimateNote: 'Estimate',
+      speedVsAverage: 'vs. your 30-day average',
       speedNote: 'Note: If you use IP-changing tools, enable the Tunnel option in the tool settings to show updates.',
       aboutDevTitle: 'Web Application Developer',
       aboutDevLine1: 'This web application was designed and developed by',
@@ -1996,6 +2084,7 @@ const App = () => {
       settingsGeneral: '\u0639\u0645\u0648\u0645\u06cc',
       settingsAutoLaunch: '\u0627\u062c\u0631\u0627\u06cc \u062e\u0648\u062f\u06a9\u0627\u0631',
       settingsAutoLaunchHint: '\u0628\u0627 \u0631\u0648\u0634\u0646 \u0634\u062f\u0646 \u0648\u06cc\u0646\u062f\u0648\u0632 \u0627\u062c\u0631\u0627 \u0634\u0648\u062f',
+      settingsAutoLaunchFailed: '\u062a\u063a\u06cc\u06cc\u0631 \u0627\u062c\u0631\u0627\u06cc \u062e\u0648\u062f\u06a9\u0627\u0631 \u0645\u0645\u06a9\u0646 \u0646\u0634\u062f',
       settingsPingInterval: '\u0628\u0627\u0632\u0647 \u067e\u06cc\u0646\u06af (\u0645\u06cc\u0644\u06cc \u062b\u0627\u0646\u06cc\u0647)',
       settingsPingIntervalHint: '\u0641\u0627\u0635\u0644\u0647 \u0628\u0647 \u0631\u0648\u0632\u0631\u0633\u0627\u0646\u06cc \u067e\u06cc\u0646\u06af',
       settingsOptimization: '????? ????',
@@ -2016,8 +2105,13 @@ const App = () => {
       closeActionTitle: '\u0627\u0642\u062f\u0627\u0645 \u0647\u0646\u06af\u0627\u0645 \u0628\u0633\u062a\u0646',
       closeActionHint: '\u0628\u0627 \u0628\u0633\u062a\u0646 \u0628\u0631\u0646\u0627\u0645\u0647 \u0686\u0647 \u0627\u062a\u0641\u0627\u0642\u06cc \u0628\u06cc\u0641\u062a\u062f',
       closeActionHide: '\u067e\u0646\u0647\u0627\u0646 \u06a9\u0631\u062f\u0646',
+      closeActionMinimize: '\u06a9\u0648\u0686\u06a9 \u06a9\u0631\u062f\u0646',
       closeActionExit: '\u062e\u0631\u0648\u062c',
       closeActionAsk: '\u0647\u0631 \u0628\u0627\u0631 \u0628\u067e\u0631\u0633',
+      autoHideTitle: '\u067e\u0646\u0647\u0627\u0646 \u0634\u062f\u0646 \u062e\u0648\u062f\u06a9\u0627\u0631',
+      autoHideHint: '\u067e\u0646\u0647\u0627\u0646 \u06a9\u0631\u062f\u0646 \u067e\u0646\u062c\u0631\u0647 \u067e\u0633 \u0627\u0632 \u0639\u062f\u0645 \u0641\u0639\u0627\u0644\u06cc\u062a',
+      autoHideOff: '\u062e\u0627\u0645\u0648\u0634',
+      autoHideMinutes: '\u062f\u0642\u06cc\u0642\u0647',
       monitoring: 'Monitoring',
       add: '\u0627\u0641\u0632\u0648\u062f\u0646',
       edit: '\u0648\u06cc\u0631\u0627\u06cc\u0634',
@@ -2094,6 +2188,9 @@ const App = () => {
       dnsManagerReset: '\u0628\u0627\u0632\u06af\u0634\u062a \u0628\u0647 DHCP',
       dnsManagerNoAdapters: '\u06a9\u0627\u0631\u062a \u0634\u0628\u06a9\u0647\u200c\u0627\u06cc \u06cc\u0627\u0641\u062a \u0646\u0634\u062f',
       dnsManagerApplied: 'DNS \u0628\u0627 \u0645\u0648\u0641\u0642\u06cc\u062a \u062a\u063a\u06cc\u06cc\u0631 \u06a9\u0631\u062f',
+      dnsManagerNoChange: '\u0627\u06cc\u0646 \u0633\u0631\u0648\u0631\u0647\u0627 \u0627\u0632 \u0642\u0628\u0644 DNS \u0634\u0645\u0627 \u0628\u0648\u062f\u0646\u062f',
+      dnsManagerNotVerified: '\u0627\u0639\u0645\u0627\u0644 \u0634\u062f\u060c \u0627\u0645\u0627 DNS \u062c\u062f\u06cc\u062f \u0647\u0646\u0648\u0632 \u0641\u0639\u0627\u0644 \u0646\u0634\u062f\u0647 \u0627\u0633\u062a',
+      dnsManagerRolledBack: 'DNS \u062c\u062f\u06cc\u062f \u067e\u0627\u0633\u062e \u0646\u062f\u0627\u062f\u060c \u0628\u0646\u0627\u0628\u0631\u0627\u06cc\u0646 \u062a\u0646\u0638\u06cc\u0645\u0627\u062a \u0642\u0628\u0644\u06cc \u0628\u0627\u0632\u06af\u0631\u062f\u0627\u0646\u062f\u0647 \u0634\u062f',
       dnsManagerResetDone: 'DNS \u0628\u0647 \u062d\u0627\u0644\u062a \u062e\u0648\u062f\u06a9\u0627\u0631 \u0628\u0631\u06af\u0634\u062a',
       dnsManagerFailed: '\u062a\u063a\u06cc\u06cc\u0631 DNS \u0646\u0627\u0645\u0648\u0641\u0642 \u0628\u0648\u062f',
       usable: '\u0642\u0627\u0628\u0644 \u0627\u0633\u062a\u0641\u0627\u062f\u0647',
@@ -2103,6 +2200,7 @@ const App = () => {
       speedUpload: '\u0633\u0631\u0639\u062a \u0622\u067e\u0644\u0648\u062f',
       speedLatency: '\u062a\u0627\u062e\u06cc\u0631',
       speedJitter: '\u0646\u0648\u0633\u0627\u0646',
+      speedConnection: '\u0627\u062a\u0635\u0627\u0644',
       speedStart: '\u0634\u0631\u0648\u0639',
       speedStop: '\u062a\u0648\u0642\u0641',
       speedPhaseIdle: '\u0622\u0645\u0627\u062f\u0647',
@@ -2112,6 +2210,9 @@ const App = () => {
       speedProviderTitle: '\u0633\u0631\u0648\u06cc\u0633',
       speedProviderCloudflare: 'Cloudflare',
       speedProviderHetzner: 'Hetzner',
+      speedLite: '\u0633\u0628\u06a9 (~1.5 MB)',
+      speedEstimateNote: '\u062a\u062e\u0645\u06cc\u0646\u06cc',
+      speedVsAverage: '\u0646\u0633\u0628\u062a \u0628\u0647 \u0645\u06cc\u0627\u0646\u06af\u06cc\u0646 \u06f3\u06f0 \u0631\u0648\u0632\u0647',
       speedNote: '\u0646\u06a9\u062a\u0647 : \u0627\u06af\u0631 \u0627\u0632 \u0627\u0628\u0632\u0627\u0631 \u0647\u0627\u06cc \u062a\u063a\u06cc\u06cc\u0631 \u0622\u06cc\u067e\u06cc \u0627\u0633\u062a\u0641\u0627\u062f\u0647 \u0645\u06cc\u06a9\u0646\u06cc\u062f \u0628\u0631\u0627\u06cc \u0646\u0645\u0627\u06cc\u0634 \u062a\u063a\u06cc\u06cc\u0631\u0627\u062a \u06af\u0632\u06cc\u0646\u0647 \u062a\u0648\u0646\u0644 \u0631\u0648 \u062f\u0631 \u062a\u0646\u0638\u06cc\u0645\u0627\u062a \u0627\u0628\u0632\u0627\u0631 \u0631\u0648\u0634\u0646 \u06a9\u0646\u06cc\u062f',
       aboutDevTitle: '\u062a\u0648\u0633\u0639\u0647\u200c\u062f\u0647\u0646\u062f\u0647 \u0648\u0628 \u0627\u067e\u0644\u06cc\u06a9\u06cc\u0634\u0646',
       aboutDevLine1: '\u0627\u06cc\u0646 \u0648\u0628 \u0627\u067e\u0644\u06cc\u06a9\u06cc\u0634\u0646 \u062a\u0648\u0633\u0637',
@@ -2427,7 +2528,12 @@ const App = () => {
                   ) : (
                     <span className="public-ip-flag-fallback" aria-hidden="true">--</span>
                   )}
-                  <span className="public-ip-value">{visiblePublicIp}</span>
+                  <span
+                    className="public-ip-value"
+                    title={showPublicIp && publicNetworkInfo.location ? publicNetworkInfo.location : undefined}
+                  >
+                    {visiblePublicIp}
+                  </span>
                 </div>
                 <button
                   type="button"
@@ -2867,9 +2973,30 @@ const App = () => {
                 >
                   {texts.speedProviderHetzner}
                 </button>
+                <button
+                  className={`speed-provider-tab ${speedLite ? 'active' : ''}`}
+                  onClick={() => setSpeedLite((prev) => !prev)}
+                  disabled={speedLoading}
+                  aria-pressed={speedLite}
+                >
+                  {texts.speedLite}
+                </button>
               </div>
             </div>
-            <div className={`speed-phase ${speedPhase}`}>{speedPhaseLabel}</div>
+            <div className={`speed-phase ${speedPhase}`}>
+              {speedPhaseLabel}
+              {speedMetrics?.estimated && speedPhase === 'final'
+                ? ` • ${texts.speedEstimateNote} ±${speedMetrics.marginPercent}%`
+                : ''}
+            </div>
+            {speedComparison && speedPhase === 'final' && speedComparison.downloadDeltaPercent !== null && (
+              <div className={`speed-phase ${speedComparison.degraded ? 'degraded' : ''}`}>
+                {`${texts.speedVsAverage}: ${texts.speedDownload} ${speedComparison.downloadDeltaPercent > 0 ? '+' : ''}${speedComparison.downloadDeltaPercent}%`}
+                {speedComparison.uploadDeltaPercent !== null
+                  ? ` • ${texts.speedUpload} ${speedComparison.uploadDeltaPercent > 0 ? '+' : ''}${speedComparison.uploadDeltaPercent}%`
+                  : ''}
+              </div>
+            )}
             {(!speedStarted || speedLoading) ? (
               <div className="speed-start">
                 <button
@@ -2920,6 +3047,18 @@ const App = () => {
                       {speedMetrics ? speedMetrics.ip : 'N/A'}
                     </strong>
                   </div>
+                  <div className="speed-info-item single">
+                    <span>{texts.speedConnection}</span>
+                    <strong>
+                      {speedMetrics?.connectionType
+                        ? [
+                          speedMetrics.connectionType.type,
+                          speedMetrics.connectionType.ssid,
+                          speedMetrics.connectionType.interface,
+                        ].filter(Boolean).join(' • ')
+                        : 'N/A'}
+                    </strong>
+                  </div>
                 </div>
                 <div className="speed-cards">
                   <div className="speed-card">
@@ -3041,7 +3180,7 @@ const App = () => {
               <div className="settings-item">
                 <div className="settings-label">
                   <div className="settings-name">{texts.settingsAutoLaunch}</div>
-                  <div className="settings-hint">{texts.settingsAutoLaunchHint}</div>
+                  <div className="settings-hint">{autoLaunchError || texts.settingsAutoLaunchHint}</div>
                 </div>
                 <label className="settings-switch">
                   <input
@@ -3088,11 +3227,27 @@ const App = () => {
                   onChange={setCloseAction}
                   options={[
                     { value: 'hide', label: texts.closeActionHide },
+                    { value: 'minimize', label: texts.closeActionMinimize },
                     { value: 'exit', label: texts.closeActionExit },
                     { value: 'ask', label: texts.closeActionAsk },
                   ]}
                 />
               </div>
+              <div className="settings-item">
+                <div className="settings-label">
+                  <div className="settings-name">{texts.autoHideTitle}</div>
+                  <div className="settings-hint">{texts.autoHideHint}</div>
+                </div>
+                <AppDropdown
+                  className="settings-select"
+                  value={autoHideMinutes}
+                  onChange={setAutoHideMinutes}
+                  options={[
+                    { value: '0', label: texts.autoHideOff },
+                    ...['5', '15', '30', '60'].map((value) => ({ value, label: `${value} ${texts.autoHideMinutes}` })),
+                  ]}
+                />
+              </div>
               <div className="settings-item">
                 <div className="settings-label">
                   <div className="settings-name">{texts.settingsOptimization}</div>